@@ -1,17 +1,41 @@
+use ratatui::layout::Rect;
 use ratatui::widgets::ListState;
+use regex::RegexBuilder;
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::time::SystemTime;
-
-use crate::cleaner::DryRunResult;
-use crate::config::AppConfig;
-use crate::scanner::ScanKind;
-use crate::utils::expand_tilde;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::cleaner::{
+    CleanError, CleanOptions, Cleaner, DryRunMessage, DryRunResult, SafetyRules, TrashPolicy,
+};
+use crate::cli::ScanTarget;
+use crate::config::{AppConfig, ThemeConfig};
+use crate::scanner::{PeekResult, ScanKind, Scanner, aggregate_category_stats, format_size};
+use crate::utils::{SECONDS_PER_DAY, disk_usage, expand_path};
 
 const DEFAULT_VISIBLE_HEIGHT: usize = 20;
+/// 复选框 `[ ]` 的显示宽度
+const LIST_CHECKBOX_WIDTH: u16 = 3;
 const MIN_PAGE_SCROLL: usize = 1;
+/// Tab 补全最多保留的候选数量（超出部分只计数）
+const MAX_TAB_COMPLETIONS: usize = 200;
 const SCAN_PROGRESS_COMPLETE: u8 = 100;
+/// 大额永久删除时须输入的确认词
+pub const CONFIRM_WORD: &str = "DELETE";
+/// 剩余时间平滑系数：新估算值所占权重
+const SCAN_ETA_SMOOTHING: f64 = 0.3;
+/// 按 m 循环切换的最小显示大小（0 表示不过滤）
+const SIZE_FLOOR_STEPS: [u64; 4] = [0, 1 << 20, 10 << 20, 100 << 20];
+/// 搜索历史最多保留的条数
+const SEARCH_HISTORY_LIMIT: usize = 50;
+/// 详情面板宽度（占主区域百分比）的默认值、调整步长与范围
+const DETAIL_PERCENT_DEFAULT: u16 = 40;
+const DETAIL_PERCENT_STEP: u16 = 10;
+const DETAIL_PERCENT_RANGE: (u16, u16) = (20, 70);
+/// 按 K 应用保留策略时保留的最新子项数
+pub const RETENTION_KEEP: usize = 5;
 
 /// 应用运行模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,6 +44,8 @@ pub enum Mode {
     Normal,
     /// 扫描中
     Scanning,
+    /// 清理中
+    Cleaning,
     /// 确认删除
     Confirm,
     /// 帮助界面
@@ -30,6 +56,16 @@ pub enum Mode {
     Search,
     /// 统计面板
     Stats,
+    /// 目录内容预览
+    Peek,
+    /// 确认清空回收站
+    ConfirmEmptyTrash,
+    /// 书签列表
+    Bookmarks,
+    /// 扫描类别设置
+    Settings,
+    /// 有已选项目时确认退出
+    ConfirmQuit,
 }
 
 /// 排序方式
@@ -45,6 +81,16 @@ pub enum SortOrder {
 }
 
 impl SortOrder {
+    /// 从配置/CLI 名称解析排序方式（"name" / "size" / "time"），无效值返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "name" => Some(SortOrder::ByName),
+            "size" => Some(SortOrder::BySize),
+            "time" => Some(SortOrder::ByTime),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             SortOrder::ByName => "名称",
@@ -53,6 +99,14 @@ impl SortOrder {
         }
     }
 
+    /// 排序方向符号：名称升序为 ▲，大小与时间降序为 ▼
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            SortOrder::ByName => "▲",
+            SortOrder::BySize | SortOrder::ByTime => "▼",
+        }
+    }
+
     pub fn toggle(&self) -> Self {
         match self {
             SortOrder::ByName => SortOrder::BySize,
@@ -62,6 +116,179 @@ impl SortOrder {
     }
 }
 
+/// 替换路径对应条目的大小，返回原大小（未知时为 0）；未找到条目时返回 None
+fn replace_entry_size(entries: &mut [CleanableEntry], path: &PathBuf, size: u64) -> Option<u64> {
+    let entry = entries.iter_mut().find(|entry| entry.path == *path)?;
+    Some(entry.size.replace(size).unwrap_or(0))
+}
+
+/// 为列表中指定路径且大小未知的条目回填大小，返回是否发生回填
+fn fill_entry_size(entries: &mut [CleanableEntry], path: &PathBuf, size: u64) -> bool {
+    match entries.iter_mut().find(|entry| entry.path == *path) {
+        Some(entry) if entry.size.is_none() => {
+            entry.size = Some(size);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 名称匹配搜索词的条目（子串不区分大小写；正则模式下表达式无效时返回 None）
+fn matching_entries(
+    entries: &[CleanableEntry],
+    query: &str,
+    is_regex: bool,
+) -> Option<Vec<CleanableEntry>> {
+    if is_regex {
+        let pattern = RegexBuilder::new(query)
+            .case_insensitive(true)
+            .build()
+            .ok()?;
+        Some(
+            entries
+                .iter()
+                .filter(|entry| pattern.is_match(&entry.name))
+                .cloned()
+                .collect(),
+        )
+    } else {
+        let query = query.to_lowercase();
+        Some(
+            entries
+                .iter()
+                .filter(|entry| entry.name.to_lowercase().contains(&query))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// 跳转模式下的搜索匹配器：大小写不敏感的子串或正则表达式
+pub enum SearchMatcher {
+    Substring(Vec<char>),
+    Regex(regex::Regex),
+}
+
+impl SearchMatcher {
+    /// 查询为空或正则表达式无效时返回 None
+    pub fn new(query: &str, is_regex: bool) -> Option<Self> {
+        if query.is_empty() {
+            return None;
+        }
+        if is_regex {
+            RegexBuilder::new(query)
+                .case_insensitive(true)
+                .build()
+                .ok()
+                .map(Self::Regex)
+        } else {
+            Some(Self::Substring(
+                query.chars().flat_map(char::to_lowercase).collect(),
+            ))
+        }
+    }
+
+    /// 名称是否匹配（与过滤模式的匹配规则一致）
+    pub fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Substring(_) => self.find(name).is_some(),
+            Self::Regex(pattern) => pattern.is_match(name),
+        }
+    }
+
+    /// 名称中第一处非空匹配的字节范围，用于高亮
+    pub fn find(&self, name: &str) -> Option<Range<usize>> {
+        match self {
+            Self::Substring(query) => name.char_indices().find_map(|(start, _)| {
+                let mut matched = 0;
+                for (offset, c) in name[start..].char_indices() {
+                    for lower in c.to_lowercase() {
+                        if query.get(matched) != Some(&lower) {
+                            return None;
+                        }
+                        matched += 1;
+                    }
+                    if matched == query.len() {
+                        return Some(start..start + offset + c.len_utf8());
+                    }
+                }
+                None
+            }),
+            Self::Regex(pattern) => pattern
+                .find_iter(name)
+                .find(|m| !m.is_empty())
+                .map(|m| m.range()),
+        }
+    }
+}
+
+/// 已确认的搜索过滤（用于头部提示当前列表为子集）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveFilter {
+    /// 确认时的搜索词
+    pub query: String,
+    /// 过滤前的条目总数
+    pub total: usize,
+}
+
+/// 书签条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub path: PathBuf,
+    /// 打开书签列表时路径是否存在（不存在的书签灰显且不可选择）
+    pub available: bool,
+}
+
+impl Bookmark {
+    /// 由路径列表创建书签（可用状态在打开列表时刷新）
+    fn from_paths(paths: Vec<PathBuf>) -> Vec<Self> {
+        paths
+            .into_iter()
+            .map(|path| Self {
+                path,
+                available: false,
+            })
+            .collect()
+    }
+}
+
+/// 统计面板的数据范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsScope {
+    /// 整个根扫描结果（`root_entries`），不随导航变化
+    #[default]
+    All,
+    /// 当前列表中的顶层条目
+    CurrentView,
+}
+
+impl StatsScope {
+    pub fn toggle(self) -> Self {
+        match self {
+            StatsScope::All => StatsScope::CurrentView,
+            StatsScope::CurrentView => StatsScope::All,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsScope::All => "全部",
+            StatsScope::CurrentView => "当前视图",
+        }
+    }
+}
+
+/// 单个分类的空间占用统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryStats {
+    pub name: String,
+    pub size: u64,
+    /// 分类下的条目数
+    pub item_count: usize,
+    /// 分类下的文件总数（任一条目文件数未知时为 None）
+    pub file_count: Option<u64>,
+}
+
 /// 扫描项类型
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ItemCategory {
@@ -93,12 +320,65 @@ pub enum ItemCategory {
     DockerData,
     /// Cargo 缓存
     CargoCache,
-    /// 用户自定义扫描目标
-    Custom,
+    /// 用户自定义扫描目标（可带配置中的名称）
+    Custom(Option<String>),
 }
 
 impl ItemCategory {
-    pub fn as_str(&self) -> &'static str {
+    /// 所有类别（按 CLI 帮助中的展示顺序）
+    pub const ALL: [ItemCategory; 15] = [
+        ItemCategory::SystemCache,
+        ItemCategory::AppCache,
+        ItemCategory::Logs,
+        ItemCategory::Temp,
+        ItemCategory::XcodeDerivedData,
+        ItemCategory::NodeModules,
+        ItemCategory::HomebrewCache,
+        ItemCategory::CocoaPods,
+        ItemCategory::NpmCache,
+        ItemCategory::PipCache,
+        ItemCategory::DockerData,
+        ItemCategory::CargoCache,
+        ItemCategory::Downloads,
+        ItemCategory::Trash,
+        ItemCategory::Custom(None),
+    ];
+
+    /// 稳定的英文标识，用于 CLI 参数等需要固定名称的场景
+    pub fn slug(&self) -> &'static str {
+        match self {
+            ItemCategory::SystemCache => "system-cache",
+            ItemCategory::AppCache => "app-cache",
+            ItemCategory::Logs => "logs",
+            ItemCategory::Temp => "temp",
+            ItemCategory::XcodeDerivedData => "xcode",
+            ItemCategory::NodeModules => "node-modules",
+            ItemCategory::HomebrewCache => "homebrew",
+            ItemCategory::CocoaPods => "cocoapods",
+            ItemCategory::NpmCache => "npm",
+            ItemCategory::PipCache => "pip",
+            ItemCategory::DockerData => "docker",
+            ItemCategory::CargoCache => "cargo",
+            ItemCategory::Downloads => "downloads",
+            ItemCategory::Trash => "trash",
+            ItemCategory::Custom(_) => "custom",
+        }
+    }
+
+    /// 是否属于过滤条件指定的类别（自定义目标忽略名称，`custom` 匹配全部自定义目标）
+    pub fn matches_filter(&self, filter: &ItemCategory) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(filter)
+    }
+
+    /// 根据 `slug()` 标识查找类别（忽略大小写）
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.slug().eq_ignore_ascii_case(slug.trim()))
+    }
+
+    /// 显示名称；带名称的自定义目标返回配置中的名称
+    pub fn as_str(&self) -> &str {
         match self {
             ItemCategory::SystemCache => "系统缓存",
             ItemCategory::AppCache => "应用缓存",
@@ -114,7 +394,8 @@ impl ItemCategory {
             ItemCategory::CargoCache => "Cargo 缓存",
             ItemCategory::Downloads => "下载文件夹",
             ItemCategory::Trash => "垃圾桶",
-            ItemCategory::Custom => "自定义目标",
+            ItemCategory::Custom(Some(label)) => label,
+            ItemCategory::Custom(None) => "自定义目标",
         }
     }
 
@@ -134,11 +415,24 @@ impl ItemCategory {
             ItemCategory::CargoCache => "Cargo registry 下载缓存",
             ItemCategory::Downloads => "下载文件夹中的文件",
             ItemCategory::Trash => "回收站中的文件",
-            ItemCategory::Custom => "用户配置的自定义扫描目标",
+            ItemCategory::Custom(_) => "用户配置的自定义扫描目标",
         }
     }
 }
 
+/// 确认清理后对待清理项目的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanAction {
+    /// 覆写后删除
+    SecureDelete,
+    /// 全部移至回收站
+    Trash,
+    /// 全部永久删除
+    Delete,
+    /// 按类别规则部分移至回收站，其余永久删除
+    Mixed { trashed: usize },
+}
+
 /// 条目类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EntryKind {
@@ -156,6 +450,28 @@ pub struct CleanableEntry {
     pub size: Option<u64>,
     /// 最后修改时间
     pub modified_at: Option<SystemTime>,
+    /// 目录下的文件数（仅根扫描计算，未知时为 None）
+    pub file_count: Option<u64>,
+}
+
+/// 根扫描条目加回嵌套在其下的其他根条目（`roots`）的大小
+///
+/// 预设扫描中父目标的大小不含嵌套在其下的子目标（避免重复计算），但清理父目标会一并删除子目标，
+/// 因此确认与清理时按完整大小计算。不在 `roots` 中的条目（子目录列表中的条目本身已含全部内容）原样返回。
+pub fn with_nested_root_sizes(
+    mut item: CleanableEntry,
+    roots: &[CleanableEntry],
+) -> CleanableEntry {
+    if let Some(size) = item.size.as_mut()
+        && roots.iter().any(|root| root.path == item.path)
+    {
+        *size += roots
+            .iter()
+            .filter(|root| root.path != item.path && root.path.starts_with(&item.path))
+            .filter_map(|root| root.size)
+            .sum::<u64>();
+    }
+    item
 }
 
 /// 选中条目
@@ -163,6 +479,18 @@ pub struct CleanableEntry {
 pub struct SelectedEntry {
     pub kind: EntryKind,
     pub size: Option<u64>,
+    /// 所属扫描类别（仅根扫描条目有，用于按类别决定清理方式）
+    pub category: Option<ItemCategory>,
+}
+
+impl SelectedEntry {
+    fn from_entry(entry: &CleanableEntry) -> Self {
+        Self {
+            kind: entry.kind,
+            size: entry.size,
+            category: entry.category.clone(),
+        }
+    }
 }
 
 /// 导航栈帧：保存一层目录的路径、条目和滚动位置
@@ -235,18 +563,42 @@ pub struct App {
     pub entries: Vec<CleanableEntry>,
     /// 根层条目缓存
     pub root_entries: Vec<CleanableEntry>,
+    /// 统计面板的数据范围
+    pub stats_scope: StatsScope,
     /// 列表状态
     pub list_state: ListState,
     /// 扫描进度 (0-100)
     pub scan_progress: u8,
+    /// 当前扫描的开始时间（用于显示已用时间与动画）
+    pub scan_started_at: Option<Instant>,
+    /// 平滑后的剩余时间估算及其计算时刻
+    scan_eta: Option<(Duration, Instant)>,
+    /// 本次扫描已达到的最高进度（忽略回退）
+    scan_peak_progress: u8,
     /// 当前扫描路径
     pub current_scan_path: String,
+    /// 清理进度：已完成条目数
+    pub clean_completed: usize,
+    /// 清理进度：总条目数
+    pub clean_total: usize,
+    /// 清理进度：已处理条目的累计大小（含失败的条目）
+    pub clean_done_bytes: u64,
+    /// 清理进度：所有待清理条目的总大小
+    pub clean_total_bytes: u64,
+    /// 最近处理完成的清理路径
+    pub current_clean_path: String,
+    /// 是否已请求取消清理
+    pub clean_cancel_requested: bool,
+    /// 当前的清理任务是否为清空回收站
+    pub emptying_trash: bool,
     /// 总计可清理大小（当前视图）
     pub total_size: u64,
     /// 已选择大小（跨目录）
     pub selected_size: u64,
     /// 错误消息
     pub error_message: Option<String>,
+    /// 清理失败的结构化错误（错误弹窗中按类别分组展示）
+    pub clean_errors: Vec<CleanError>,
     /// 选中条目
     pub selections: HashMap<PathBuf, SelectedEntry>,
     /// 导航状态
@@ -261,26 +613,176 @@ pub struct App {
     pub sort_order: SortOrder,
     /// 路径输入缓冲区
     pub input_buffer: String,
+    /// 路径输入确认后追加到当前结果（`+`），而不是替换
+    pub input_appends: bool,
     /// 可视区域高度（由渲染时更新）
     pub visible_height: usize,
+    /// 列表区域位置（由渲染时更新，用于鼠标点击定位）
+    pub list_area: Rect,
+    /// 列表行内复选框相对 `list_area` 左边的列偏移（由渲染时按边框、内边距与高亮符号计算）
+    pub list_checkbox_column: u16,
     /// 上次清理结果：(释放空间, 条目数)
     pub last_clean_result: Option<(u64, usize)>,
+    /// 上次移入回收站的路径批次（用于撤销）
+    pub last_trashed_batch: Vec<PathBuf>,
+    /// 底部状态栏的一次性提示消息
+    pub notice: Option<String>,
+    /// 头部显示的回收站当前大小（后台统计；回收站目录不存在时为 None，头部不显示）
+    pub trash_usage: Option<u64>,
     /// 确认弹窗滚动偏移
     pub confirm_scroll: usize,
+    /// 进入确认模式时未通过安全检查的路径（确认后跳过）
+    pub confirm_blocked: HashSet<PathBuf>,
+    /// 确认弹窗（及 dry-run 视图）列表区可见行数（渲染时更新，用于翻页与滚动上限）
+    pub confirm_visible_height: usize,
+    /// 帮助弹窗滚动偏移（渲染时限制在内容范围内）
+    pub help_scroll: usize,
     /// 搜索查询字符串
     pub search_query: String,
     /// 搜索前的原始条目（用于取消搜索时恢复）
     pub pre_search_entries: Vec<CleanableEntry>,
+    /// 已确认且仍生效的搜索过滤（视图被重置时清除）
+    pub active_filter: Option<ActiveFilter>,
+    /// 是否处于树形视图（目录在原位展开子项）
+    pub tree_mode: bool,
+    /// 树形视图中已展开的目录
+    pub expanded: HashSet<PathBuf>,
+    /// 树形视图中已加载的目录子项（按目录路径索引）
+    pub tree_children: HashMap<PathBuf, Vec<CleanableEntry>>,
+    /// 树形视图的顶层条目（`entries` 为展开后的扁平列表）
+    tree_base: Vec<CleanableEntry>,
+    /// 树形视图中 `entries` 每一项的缩进层级
+    entry_depths: Vec<usize>,
+    /// 是否使用正则表达式搜索（默认子串匹配）
+    pub search_is_regex: bool,
+    /// 跳转模式：搜索时保留完整列表，只把光标移到第一个匹配项（默认过滤列表）
+    pub search_jump: bool,
+    /// 正则表达式无效时的提示
+    pub search_error: Option<String>,
+    /// 本次会话中确认过的搜索词（旧的在前，连续重复只记一次）
+    pub search_history: Vec<String>,
+    /// 搜索中用上/下键浏览历史时的位置（None 表示未在浏览）
+    search_history_cursor: Option<usize>,
     /// Dry-run 结果
     pub dry_run_result: Option<DryRunResult>,
     /// 确认弹窗中是否显示 dry-run 视图
     pub dry_run_active: bool,
+    /// 打开确认弹窗时是否直接进入 dry-run 视图
+    pub confirm_dry_run_first: bool,
+    /// 等待主循环启动后台计算的 dry-run 请求
+    dry_run_requested: bool,
+    /// dry-run 请求代数：关闭弹窗或重新请求后，旧的计算结果被丢弃
+    dry_run_generation: u64,
+    /// 后台 dry-run 是否仍在计算
+    dry_run_pending: bool,
+    /// 本次 dry-run 待统计的项目数
+    pub dry_run_total: usize,
+    /// 永久删除总大小超过该值时须输入确认词
+    pub confirm_word_above: Option<u64>,
+    /// 确认弹窗的确认词输入（为 None 时按 Enter 直接确认）
+    pub confirm_word_input: Option<String>,
     /// 是否启用回收站模式（移至回收站而非永久删除）
     pub use_trash: bool,
+    /// 按类别移至回收站的规则（为空时统一按 use_trash 处理）
+    pub trash_categories: Vec<ItemCategory>,
+    /// 是否安全擦除（覆写后删除，优先于回收站）
+    pub use_secure_delete: bool,
+    /// 安全模式：一律移至回收站，禁止永久删除、安全擦除与清空回收站
+    pub force_trash: bool,
+    /// 清理选项（清除 macOS 不可变标志、暂时性错误重试次数与严格模式）
+    pub clean_options: CleanOptions,
+    /// 清理后是否重新统计实际释放的磁盘空间
+    pub measure_freed_space: bool,
+    /// 清理后是否删除已清理目录下残留的空子目录
+    pub prune_empty_dirs: bool,
+    /// 扫描完成后自动选中修改时间早于该天数的条目
+    pub auto_select_older_than_days: Option<u64>,
+    /// 用户配置的删除安全规则（允许根目录 / 额外禁止路径）
+    pub safety_rules: SafetyRules,
+    /// 用户配置的颜色主题
+    pub theme_config: ThemeConfig,
+    /// 列表行是否显示相对大小条
+    pub show_bars: bool,
+    /// 是否在列表右侧显示高亮条目的详情面板（D 切换）
+    pub show_details: bool,
+    /// 详情面板宽度占主区域的百分比（`<` / `>` 调整）
+    pub detail_percent: u16,
+    /// 详情面板中目录内容统计 `(路径, 文件数, 目录数)`，由后台按需计算
+    pub detail_counts: Option<(PathBuf, usize, usize)>,
+    /// 进行中的详情统计目录（光标移到其他目录时由新的统计取代）
+    detail_pending: Option<PathBuf>,
+    /// 上下移动到列表首尾时是否回绕
+    pub wrap_navigation: bool,
+    /// 有已选项目时退出前是否确认
+    pub confirm_quit: bool,
+    /// 进入退出确认前的模式（取消确认时恢复）
+    quit_return_mode: Mode,
+    /// 列表中是否以相对时间显示修改时间
+    pub relative_time: bool,
+    /// 用户配置的修改时间格式串
+    pub date_format: Option<String>,
+    /// 根扫描是否使用扫描缓存（--no-cache 时关闭）
+    pub use_scan_cache: bool,
+    /// 计算目录大小时是否跟随符号链接
+    pub follow_symlinks: bool,
+    /// 并行计算目录大小的线程数上限（None 时使用 rayon 全局线程池）
+    pub scan_max_concurrency: Option<usize>,
+    /// 快速列表：列出子目录时不计算大小，高亮或选中时按需计算
+    pub lazy_sizes: bool,
+    /// 当前子目录列表是否以快速列表方式列出（其中目录大小需按需计算）
+    pub lazy_listing: bool,
+    /// 已请求按需计算大小的目录，及其所属的扫描代数
+    lazy_size_requested: (u64, HashSet<PathBuf>),
+    /// 正在重新计算大小的目录，及其所属的扫描代数
+    recomputing: (u64, HashSet<PathBuf>),
+    /// 原地重新扫描前的高亮位置 (扫描代数, 路径, 索引)，扫描完成后恢复
+    pending_cursor: Option<(u64, PathBuf, usize)>,
     /// Tab 补全候选列表（保留原始 ~ 前缀的显示字符串）
     pub tab_completions: Vec<String>,
     /// Tab 补全当前选中索引
     pub tab_completion_index: Option<usize>,
+    /// 超出 `MAX_TAB_COMPLETIONS` 而未列出的候选数量
+    pub tab_completion_hidden: usize,
+    /// 可按 r 恢复的上次扫描目标（启动时从状态文件加载）
+    pub last_scan: Option<ScanTarget>,
+    /// 进行中的预设/磁盘扫描目标，完成后记为上次扫描目标
+    pub pending_scan_target: Option<ScanTarget>,
+    /// 进行中的扫描是否追加到已有的根结果（与已有条目重叠的条目跳过）
+    pub appending_scan: bool,
+    /// 追加扫描开始前已有的根条目数，新条目只与这些条目比较
+    append_base: usize,
+    /// 本次追加扫描因与已有条目重叠而跳过的条目数
+    append_overlaps: usize,
+    /// 扫描根所在卷的 (总容量, 可用空间)，扫描完成时刷新；查询失败时为 None，头部不显示
+    pub disk_usage: Option<(u64, u64)>,
+    /// 配置的书签列表
+    pub bookmarks: Vec<Bookmark>,
+    /// 书签列表中当前高亮的索引
+    pub bookmark_index: usize,
+    /// 预设扫描时跳过的类别（`scan.disabled_categories`，可在设置面板中切换）
+    pub disabled_categories: Vec<ItemCategory>,
+    /// 设置面板中当前高亮的类别索引（对应 `Scanner::PRESET_CATEGORIES`）
+    pub settings_index: usize,
+    /// 启动时从配置读入的类别开关（退出时与当前开关比较，决定是否写回配置文件）
+    saved_disabled_categories: Vec<ItemCategory>,
+    /// 可视范围选择的锚点索引（按 v 设置）
+    pub select_anchor: Option<usize>,
+    /// 普通模式下输入的数字前缀（供 `g` / `G` 跳到指定行，其他按键清除）
+    pub pending_count: Option<usize>,
+    /// 本次会话中按 h 隐藏的条目路径（不写入配置，只影响列表显示）
+    pub hidden: HashSet<PathBuf>,
+    /// 是否显示已隐藏的条目（按 H 切换）
+    pub show_hidden: bool,
+    /// 最小显示大小：当前视图中小于该值的条目不显示、不计入总大小（0 表示不过滤）
+    pub size_floor: u64,
+    /// 当前视图中因小于 `size_floor` 而未显示的顶层条目（降低阈值时放回列表）
+    below_floor: Vec<CleanableEntry>,
+    /// 单项清理目标（按 x 进入确认时设置，优先于多选集合）
+    pub confirm_target: Option<CleanableEntry>,
+    /// 正在预览的目录
+    pub peek_path: Option<PathBuf>,
+    /// 目录预览结果（None 表示加载中）
+    pub peek_result: Option<Result<PeekResult, String>>,
 }
 
 pub fn sort_entries_by(entries: &mut [CleanableEntry], sort_order: SortOrder) {
@@ -326,23 +828,36 @@ impl App {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
 
-        let sort_order = match config.ui.default_sort.as_deref() {
-            Some("size") => SortOrder::BySize,
-            Some("time") => SortOrder::ByTime,
-            _ => SortOrder::ByName,
-        };
+        let sort_order = config
+            .ui
+            .default_sort
+            .as_deref()
+            .and_then(SortOrder::from_name)
+            .unwrap_or_default();
 
         Self {
             mode: Mode::Normal,
             should_quit: false,
             entries: Vec::new(),
             root_entries: Vec::new(),
+            stats_scope: StatsScope::default(),
             list_state,
             scan_progress: 0,
+            scan_started_at: None,
+            scan_eta: None,
+            scan_peak_progress: 0,
             current_scan_path: String::new(),
+            clean_completed: 0,
+            clean_total: 0,
+            clean_done_bytes: 0,
+            clean_total_bytes: 0,
+            current_clean_path: String::new(),
+            clean_cancel_requested: false,
+            emptying_trash: false,
             total_size: 0,
             selected_size: 0,
             error_message: None,
+            clean_errors: Vec::new(),
             selections: HashMap::new(),
             navigation: NavigationState::new(),
             scan_generation: 0,
@@ -350,16 +865,91 @@ impl App {
             scan_in_progress: false,
             sort_order,
             input_buffer: String::new(),
+            input_appends: false,
             visible_height: DEFAULT_VISIBLE_HEIGHT,
+            list_area: Rect::default(),
+            list_checkbox_column: 0,
             last_clean_result: None,
+            last_trashed_batch: Vec::new(),
+            notice: None,
+            trash_usage: None,
             confirm_scroll: 0,
+            confirm_blocked: HashSet::new(),
+            confirm_visible_height: DEFAULT_VISIBLE_HEIGHT,
+            help_scroll: 0,
             search_query: String::new(),
             pre_search_entries: Vec::new(),
+            active_filter: None,
+            tree_mode: false,
+            expanded: HashSet::new(),
+            tree_children: HashMap::new(),
+            tree_base: Vec::new(),
+            entry_depths: Vec::new(),
+            search_is_regex: false,
+            search_jump: false,
+            search_error: None,
+            search_history: Vec::new(),
+            search_history_cursor: None,
             dry_run_result: None,
             dry_run_active: false,
+            confirm_dry_run_first: config.safety.confirm_dry_run_first,
+            dry_run_requested: false,
+            dry_run_generation: 0,
+            dry_run_pending: false,
+            dry_run_total: 0,
+            confirm_word_above: config.confirm_word_threshold(),
+            confirm_word_input: None,
             use_trash: config.safety.move_to_trash,
+            trash_categories: config.trash_categories(),
+            use_secure_delete: config.safety.secure_delete,
+            force_trash: config.safety.force_trash,
+            clean_options: CleanOptions::from_config(config),
+            measure_freed_space: config.safety.measure_freed_space,
+            prune_empty_dirs: config.safety.prune_empty_dirs,
+            auto_select_older_than_days: config.safety.auto_select_older_than_days,
+            safety_rules: SafetyRules::from_config(config),
+            theme_config: config.ui.theme.clone(),
+            show_bars: config.ui.show_bars,
+            show_details: false,
+            detail_percent: DETAIL_PERCENT_DEFAULT,
+            detail_counts: None,
+            detail_pending: None,
+            wrap_navigation: config.ui.wrap_navigation,
+            confirm_quit: config.ui.confirm_quit,
+            quit_return_mode: Mode::Normal,
+            relative_time: config.ui.relative_time,
+            date_format: config.ui.date_format.clone(),
+            use_scan_cache: true,
+            follow_symlinks: config.scan.follow_symlinks,
+            scan_max_concurrency: config.scan.max_concurrency,
+            lazy_sizes: config.scan.lazy_sizes,
+            lazy_listing: false,
+            lazy_size_requested: (0, HashSet::new()),
+            recomputing: (0, HashSet::new()),
+            pending_cursor: None,
             tab_completions: Vec::new(),
             tab_completion_index: None,
+            tab_completion_hidden: 0,
+            last_scan: None,
+            pending_scan_target: None,
+            appending_scan: false,
+            append_base: 0,
+            append_overlaps: 0,
+            disk_usage: None,
+            bookmarks: Bookmark::from_paths(config.expanded_bookmarks()),
+            bookmark_index: 0,
+            disabled_categories: config.disabled_categories(),
+            settings_index: 0,
+            saved_disabled_categories: config.disabled_categories(),
+            select_anchor: None,
+            pending_count: None,
+            hidden: HashSet::new(),
+            show_hidden: false,
+            size_floor: 0,
+            below_floor: Vec::new(),
+            confirm_target: None,
+            peek_path: None,
+            peek_result: None,
         }
     }
 
@@ -377,13 +967,24 @@ impl App {
         if self.entries.is_empty() {
             return;
         }
+        let last_index = self.entries.len() - 1;
         let next_index = match self.list_state.selected() {
-            Some(current_index) if move_forward => (current_index + 1) % self.entries.len(),
-            Some(current_index) => {
-                if current_index == 0 {
-                    self.entries.len() - 1
+            Some(current_index) if move_forward => {
+                if current_index < last_index {
+                    current_index + 1
+                } else if self.wrap_navigation {
+                    0
                 } else {
+                    last_index
+                }
+            }
+            Some(current_index) => {
+                if current_index > 0 {
                     current_index - 1
+                } else if self.wrap_navigation {
+                    last_index
+                } else {
+                    0
                 }
             }
             None => 0,
@@ -391,6 +992,36 @@ impl App {
         self.list_state.select(Some(next_index));
     }
 
+    /// 跳到下一个已选中的条目（到底后从头开始；没有选中项时不做任何事）
+    pub fn next_selected(&mut self) {
+        self.jump_to_selected(true);
+    }
+
+    /// 跳到上一个已选中的条目（到顶后从尾部开始；没有选中项时不做任何事）
+    pub fn prev_selected(&mut self) {
+        self.jump_to_selected(false);
+    }
+
+    fn jump_to_selected(&mut self, move_forward: bool) {
+        let len = self.entries.len();
+        if len == 0 || self.selections.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0).min(len - 1);
+        let target = (1..=len)
+            .map(|step| {
+                if move_forward {
+                    (current + step) % len
+                } else {
+                    (current + len - step) % len
+                }
+            })
+            .find(|&index| self.selections.contains_key(&self.entries[index].path));
+        if let Some(index) = target {
+            self.list_state.select(Some(index));
+        }
+    }
+
     /// 跳到列表第一项
     pub fn first(&mut self) {
         if !self.entries.is_empty() {
@@ -405,6 +1036,27 @@ impl App {
         }
     }
 
+    /// 数字前缀追加一位；没有前缀时忽略开头的 0，超出 usize 时保持原值
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let current = self.pending_count.unwrap_or(0);
+        if current == 0 && digit == 0 {
+            return;
+        }
+        let next = current
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit as usize))
+            .unwrap_or(current);
+        self.pending_count = Some(next);
+    }
+
+    /// 跳到第 `line` 项（从 1 开始，超出范围时停在最后一项）
+    pub fn jump_to_line(&mut self, line: usize) {
+        if !self.entries.is_empty() {
+            let index = line.saturating_sub(1).min(self.entries.len() - 1);
+            self.list_state.select(Some(index));
+        }
+    }
+
     /// 向下翻半页
     pub fn page_down(&mut self, visible_height: usize) {
         if self.entries.is_empty() {
@@ -433,6 +1085,11 @@ impl App {
         self.entries.get(index)
     }
 
+    /// 高亮条目的绝对路径（相对路径按当前工作目录补全，用于复制到剪贴板）
+    pub fn current_absolute_path(&self) -> Option<PathBuf> {
+        std::path::absolute(&self.current_entry()?.path).ok()
+    }
+
     /// 切换当前项的选中状态
     pub fn toggle_selected(&mut self) {
         if let Some(entry) = self.current_entry().cloned() {
@@ -442,17 +1099,99 @@ impl App {
         }
     }
 
+    /// 返回屏幕坐标所在行对应的列表索引（点在列表内容区外时为 None）
+    pub fn list_index_at(&self, row: u16) -> Option<usize> {
+        let content_top = self.list_area.y + 1;
+        let row_offset = usize::from(row.checked_sub(content_top)?);
+        if row_offset >= self.visible_height {
+            return None;
+        }
+        let index = self.list_state.offset() + row_offset;
+        (index < self.entries.len()).then_some(index)
+    }
+
+    /// 鼠标点击列表：移动光标到所在行，点在复选框上时切换选中状态
+    ///
+    /// 点在列表区域左右两侧之外（如详情面板）时不响应。
+    pub fn click_list(&mut self, column: u16, row: u16) {
+        if !(self.list_area.x..self.list_area.x + self.list_area.width).contains(&column) {
+            return;
+        }
+        let Some(index) = self.list_index_at(row) else {
+            return;
+        };
+        self.list_state.select(Some(index));
+        let checkbox_start = self.list_area.x + self.list_checkbox_column;
+        if (checkbox_start..checkbox_start + LIST_CHECKBOX_WIDTH).contains(&column) {
+            self.toggle_selected();
+        }
+    }
+
     /// 全选/取消全选（当前视图）
+    ///
+    /// 作用于当前列出的条目：搜索过滤生效时只包含过滤后的条目，与 `select_filtered()` 的范围一致；
+    /// 当前视图全部已选时取消这些条目的选择，否则补齐未选条目。
     pub fn toggle_all(&mut self) {
         let all_selected = self
             .entries
             .iter()
             .all(|entry| self.selections.contains_key(&entry.path));
-        let entry_summaries: Vec<_> = self
+        let entry_summaries = self.entries.clone();
+        if all_selected {
+            self.deselect_all_entries(&entry_summaries);
+        } else {
+            self.select_all_entries(&entry_summaries);
+        }
+    }
+
+    /// 反选当前视图：已选条目取消选择，未选条目加入选择
+    ///
+    /// 其他目录中的已选条目（不在当前视图中）保持不变。
+    pub fn invert_selection(&mut self) {
+        let (selected, unselected): (Vec<_>, Vec<_>) = self
             .entries
             .iter()
-            .map(|e| (e.path.clone(), e.kind, e.size))
-            .collect();
+            .cloned()
+            .partition(|entry| self.selections.contains_key(&entry.path));
+        self.deselect_all_entries(&selected);
+        self.select_all_entries(&unselected);
+    }
+
+    /// 设置/取消可视范围选择锚点（锚点为当前高亮项）
+    pub fn toggle_select_anchor(&mut self) {
+        self.select_anchor = if self.select_anchor.is_some() {
+            None
+        } else {
+            self.list_state.selected()
+        };
+    }
+
+    /// 清除可视范围选择锚点（不改变已选条目）
+    pub fn clear_select_anchor(&mut self) {
+        self.select_anchor = None;
+    }
+
+    /// 当前可视范围（锚点与光标之间的闭区间），未设置锚点时返回 None
+    pub fn anchor_range(&self) -> Option<(usize, usize)> {
+        let anchor = self.select_anchor?;
+        let cursor = self.list_state.selected()?;
+        let last_index = self.entries.len().checked_sub(1)?;
+        Some((anchor.min(cursor), anchor.max(cursor).min(last_index)))
+    }
+
+    /// 切换锚点与光标之间所有条目的选中状态，并清除锚点
+    ///
+    /// 范围内全部已选时取消选择，否则全部选中。
+    pub fn select_range(&mut self) {
+        let Some((start, end)) = self.anchor_range() else {
+            return;
+        };
+        self.select_anchor = None;
+        let range_entries = &self.entries[start..=end];
+        let all_selected = range_entries
+            .iter()
+            .all(|entry| self.selections.contains_key(&entry.path));
+        let entry_summaries = range_entries.to_vec();
         if all_selected {
             self.deselect_all_entries(&entry_summaries);
         } else {
@@ -460,25 +1199,22 @@ impl App {
         }
     }
 
-    fn select_all_entries(&mut self, entry_summaries: &[(PathBuf, EntryKind, Option<u64>)]) {
-        for (path, kind, size) in entry_summaries {
+    fn select_all_entries(&mut self, entries: &[CleanableEntry]) {
+        for entry in entries {
             if let std::collections::hash_map::Entry::Vacant(selection_entry) =
-                self.selections.entry(path.clone())
+                self.selections.entry(entry.path.clone())
             {
-                selection_entry.insert(SelectedEntry {
-                    kind: *kind,
-                    size: *size,
-                });
-                if let Some(item_size) = *size {
+                selection_entry.insert(SelectedEntry::from_entry(entry));
+                if let Some(item_size) = entry.size {
                     self.selected_size += item_size;
                 }
             }
         }
     }
 
-    fn deselect_all_entries(&mut self, entry_summaries: &[(PathBuf, EntryKind, Option<u64>)]) {
-        for (path, _, _) in entry_summaries {
-            if let Some(previous_selection) = self.selections.remove(path)
+    fn deselect_all_entries(&mut self, entries: &[CleanableEntry]) {
+        for entry in entries {
+            if let Some(previous_selection) = self.selections.remove(&entry.path)
                 && let Some(item_size) = previous_selection.size
             {
                 self.selected_size = self.selected_size.saturating_sub(item_size);
@@ -492,10 +1228,7 @@ impl App {
             if let std::collections::hash_map::Entry::Vacant(vacant) =
                 self.selections.entry(path.clone())
             {
-                vacant.insert(SelectedEntry {
-                    kind: entry.kind,
-                    size: entry.size,
-                });
+                vacant.insert(SelectedEntry::from_entry(entry));
                 if let Some(size) = entry.size {
                     self.selected_size += size;
                 }
@@ -511,10 +1244,175 @@ impl App {
         self.selections.contains_key(path)
     }
 
+    /// 条目是否因本次会话中被隐藏而不在列表中显示
+    pub fn is_hidden(&self, path: &Path) -> bool {
+        !self.show_hidden && self.hidden.contains(path)
+    }
+
+    /// 隐藏/取消隐藏当前高亮条目
+    ///
+    /// 隐藏时同时取消其选中，避免清理看不见的条目；总大小已计入的部分不扣除，
+    /// 头部总计仍反映整个扫描结果。显示全部时再按 h 取消隐藏。
+    pub fn toggle_hide_current(&mut self) {
+        let Some(entry) = self.current_entry().cloned() else {
+            return;
+        };
+        if self.hidden.remove(&entry.path) {
+            self.notice = Some(format!("已取消隐藏 {}", entry.name));
+            return;
+        }
+        self.hidden.insert(entry.path.clone());
+        self.set_selected(&entry.path, false, &entry);
+        self.drop_hidden_entries();
+        self.notice = Some(format!("已隐藏 {}（按 H 显示全部）", entry.name));
+    }
+
+    /// 切换是否显示已隐藏的条目
+    ///
+    /// 关闭时就地移除当前视图中的隐藏条目；开启时根层从缓存恢复，
+    /// 子目录视图返回其路径，由调用方重新列出以找回已移除的条目。
+    pub fn toggle_show_hidden(&mut self) -> Option<PathBuf> {
+        self.show_hidden = !self.show_hidden;
+        if !self.show_hidden {
+            self.drop_hidden_entries();
+            self.notice = Some("已隐藏的条目不再显示".to_string());
+            return None;
+        }
+        self.notice = Some(format!("显示全部条目（{} 个已隐藏）", self.hidden.len()));
+        if self.hidden.is_empty() {
+            return None;
+        }
+        match self.navigation.current_path.clone() {
+            None => {
+                self.sort_root_entries();
+                None
+            }
+            Some(path) => Some(path),
+        }
+    }
+
+    /// 从当前视图中移除已隐藏的条目，尽量保持高亮位置
+    fn drop_hidden_entries(&mut self) {
+        let hidden = std::mem::take(&mut self.hidden);
+        let visible = |entry: &CleanableEntry| self.show_hidden || !hidden.contains(&entry.path);
+        if self.tree_mode {
+            self.tree_base.retain(visible);
+            self.hidden = hidden;
+            self.rebuild_tree();
+        } else {
+            self.entries.retain(visible);
+            self.hidden = hidden;
+            let index = self
+                .list_state
+                .selected()
+                .zip(self.entries.len().checked_sub(1))
+                .map(|(index, last)| index.min(last));
+            self.list_state.select(index);
+        }
+        self.select_anchor = None;
+    }
+
+    /// 条目是否小于最小显示大小（大小未知的条目始终显示）
+    fn below_size_floor(&self, entry: &CleanableEntry) -> bool {
+        entry.size.is_some_and(|size| size < self.size_floor)
+    }
+
+    /// 循环切换最小显示大小（关 / 1 MiB / 10 MiB / 100 MiB），即时过滤当前视图
+    pub fn cycle_size_floor(&mut self) {
+        let next = SIZE_FLOOR_STEPS
+            .iter()
+            .position(|&step| step == self.size_floor)
+            .map_or(0, |index| (index + 1) % SIZE_FLOOR_STEPS.len());
+        self.size_floor = SIZE_FLOOR_STEPS[next];
+        self.apply_size_floor();
+        self.notice = Some(if self.size_floor == 0 {
+            "最小显示大小: 关".to_string()
+        } else {
+            format!("最小显示大小: {}", format_size(self.size_floor))
+        });
+    }
+
+    /// 按当前 `size_floor` 重新划分顶层条目，同步调整总大小，并尽量保持高亮条目
+    ///
+    /// 放回列表的条目按当前排序方式重新排序；树形视图下只过滤顶层条目。
+    /// 与隐藏条目相同，不再显示的条目同时取消选中，避免清理看不见的条目。
+    fn apply_size_floor(&mut self) {
+        let selected_path = self.current_entry().map(|entry| entry.path.clone());
+        let base = if self.tree_mode {
+            std::mem::take(&mut self.tree_base)
+        } else {
+            std::mem::take(&mut self.entries)
+        };
+        let (mut below, mut kept): (Vec<_>, Vec<_>) = base
+            .into_iter()
+            .partition(|entry| self.below_size_floor(entry));
+        let (still_below, restored): (Vec<_>, Vec<_>) = std::mem::take(&mut self.below_floor)
+            .into_iter()
+            .partition(|entry| self.below_size_floor(entry));
+        let size_sum = |entries: &[CleanableEntry]| -> u64 {
+            entries.iter().filter_map(|entry| entry.size).sum()
+        };
+        // 按增减量调整，已隐藏条目计入的大小保持不变
+        self.total_size = self.total_size.saturating_sub(size_sum(&below)) + size_sum(&restored);
+        if !restored.is_empty() {
+            kept.extend(
+                restored
+                    .into_iter()
+                    .filter(|entry| !self.is_hidden(&entry.path)),
+            );
+            sort_entries_by(&mut kept, self.sort_order);
+        }
+        self.deselect_all_entries(&below);
+        below.extend(still_below);
+        self.below_floor = below;
+        self.select_anchor = None;
+        if self.tree_mode {
+            self.tree_base = kept;
+            self.rebuild_tree();
+        } else {
+            self.entries = kept;
+            let index = selected_path
+                .and_then(|path| self.entries.iter().position(|entry| entry.path == path))
+                .or_else(|| {
+                    let last = self.entries.len().checked_sub(1)?;
+                    Some(self.list_state.selected().unwrap_or(0).min(last))
+                });
+            self.list_state.select(index);
+        }
+    }
+
+    /// 当前视图的全部顶层条目，包括因最小显示大小而未显示的条目（进入子目录时缓存）
+    pub fn view_entries(&self) -> Vec<CleanableEntry> {
+        let base = if self.tree_mode {
+            &self.tree_base
+        } else {
+            &self.entries
+        };
+        base.iter().chain(&self.below_floor).cloned().collect()
+    }
+
     /// 设置当前视图条目
+    ///
+    /// 树形视图下传入的条目作为顶层条目，已展开目录的子项会插入其后。
+    /// 已隐藏的条目不显示，但仍计入当前视图总大小；小于最小显示大小的条目既不显示也不计入。
     pub fn set_entries(&mut self, entries: Vec<CleanableEntry>) {
-        self.entries = entries;
-        self.total_size = self.entries.iter().filter_map(|e| e.size).sum();
+        self.select_anchor = None;
+        let (below, entries): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| self.below_size_floor(entry));
+        self.deselect_all_entries(&below);
+        self.below_floor = below;
+        self.total_size = entries.iter().filter_map(|e| e.size).sum();
+        let entries: Vec<CleanableEntry> = entries
+            .into_iter()
+            .filter(|entry| !self.is_hidden(&entry.path))
+            .collect();
+        if self.tree_mode {
+            self.tree_base = entries;
+            self.rebuild_tree();
+        } else {
+            self.entries = entries;
+        }
         if self.entries.is_empty() {
             self.list_state.select(None);
         } else {
@@ -533,6 +1431,7 @@ impl App {
         cached_entries: Vec<CleanableEntry>,
         selected_index: Option<usize>,
     ) {
+        self.active_filter = None;
         let selected_path = selected_index
             .and_then(|index| cached_entries.get(index))
             .map(|entry| entry.path.clone());
@@ -552,7 +1451,14 @@ impl App {
 
     /// 清空当前视图条目
     pub fn clear_entries(&mut self) {
+        self.active_filter = None;
         self.entries.clear();
+        self.tree_base.clear();
+        self.below_floor.clear();
+        self.entry_depths.clear();
+        self.expanded.clear();
+        self.tree_children.clear();
+        self.select_anchor = None;
         self.total_size = 0;
         self.list_state.select(None);
     }
@@ -562,25 +1468,97 @@ impl App {
         self.root_entries.clear();
     }
 
+    /// 重新扫描到已选路径时，用新的大小刷新选中条目
+    ///
+    /// 新条目大小未知时（如快速列表中的目录）保留已知的大小，避免已选大小被低估。
+    fn refresh_selection(&mut self, entry: &CleanableEntry) {
+        if let Some(selected) = self.selections.get_mut(&entry.path) {
+            selected.kind = entry.kind;
+        }
+        if let Some(size) = entry.size {
+            self.apply_selection_size(&entry.path, size);
+        }
+    }
+
+    /// 用新得知的大小更新已选条目（不要求条目在当前视图中），已选大小按差值更新
+    pub fn apply_selection_size(&mut self, path: &Path, size: u64) {
+        if let Some(selected) = self.selections.get_mut(path) {
+            let previous = selected.size.replace(size).unwrap_or(0);
+            self.selected_size = self.selected_size.saturating_sub(previous) + size;
+        }
+    }
+
+    /// 重新扫描完成后清理失效的选中条目
+    ///
+    /// 路径已不存在，或属于当前列出的目录却未出现在新的条目中时，取消选择。
+    pub fn prune_stale_selections(&mut self) {
+        let listing_dir = self.navigation.current_path.clone();
+        let present_paths: HashSet<&PathBuf> = self.entries.iter().map(|e| &e.path).collect();
+        let stale_paths: Vec<PathBuf> = self
+            .selections
+            .keys()
+            .filter(|path| {
+                let missing_from_listing = listing_dir
+                    .as_deref()
+                    .is_some_and(|dir| path.parent() == Some(dir))
+                    && !present_paths.contains(path);
+                missing_from_listing || !path.exists()
+            })
+            .cloned()
+            .collect();
+        for path in stale_paths {
+            if let Some(removed) = self.selections.remove(&path)
+                && let Some(size) = removed.size
+            {
+                self.selected_size = self.selected_size.saturating_sub(size);
+            }
+        }
+    }
+
     /// 应用根层条目
+    ///
+    /// 追加扫描时，与已有根条目路径相同、位于其下或包含它的条目跳过，避免重复计算与重叠删除。
     pub fn apply_root_entry(&mut self, entry: CleanableEntry) {
+        if self.appending_scan
+            && self.root_entries[..self.append_base].iter().any(|root| {
+                root.path.starts_with(&entry.path) || entry.path.starts_with(&root.path)
+            })
+        {
+            self.append_overlaps += 1;
+            return;
+        }
+        self.refresh_selection(&entry);
         self.root_entries.push(entry.clone());
         if self.navigation.current_path.is_none() {
-            if let Some(size) = entry.size {
-                self.total_size += size;
-            }
-            self.entries.push(entry);
-            if self.entries.len() == 1 {
-                self.list_state.select(Some(0));
-            }
+            self.push_top_level_entry(entry);
         }
     }
 
     /// 应用目录条目
     pub fn apply_dir_entry(&mut self, entry: CleanableEntry) {
+        self.refresh_selection(&entry);
+        self.push_top_level_entry(entry);
+    }
+
+    /// 追加顶层条目并计入总大小（树形视图下同时记入顶层列表，新条目位于末尾，无需重建）
+    ///
+    /// 小于最小显示大小的条目暂存、不计入总大小并取消选中；已隐藏的条目计入总大小但不显示。
+    fn push_top_level_entry(&mut self, entry: CleanableEntry) {
+        if self.below_size_floor(&entry) {
+            self.deselect_all_entries(std::slice::from_ref(&entry));
+            self.below_floor.push(entry);
+            return;
+        }
         if let Some(size) = entry.size {
             self.total_size += size;
         }
+        if self.is_hidden(&entry.path) {
+            return;
+        }
+        if self.tree_mode {
+            self.tree_base.push(entry.clone());
+            self.entry_depths.push(0);
+        }
         self.entries.push(entry);
         if self.entries.len() == 1 {
             self.list_state.select(Some(0));
@@ -588,38 +1566,162 @@ impl App {
     }
 
     /// 回填条目大小
+    ///
+    /// 已有大小时替换为新值（重新计算），当前视图总大小与已选大小按差值更新。
     pub fn apply_entry_size(&mut self, path: &PathBuf, size: u64) {
-        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == *path)
-            && entry.size.is_none()
-        {
-            entry.size = Some(size);
-            self.total_size += size;
+        if self.recomputing.0 == self.scan_generation {
+            self.recomputing.1.remove(path);
         }
-
-        if let Some(selected) = self.selections.get_mut(path)
-            && selected.size.is_none()
-        {
-            selected.size = Some(size);
-            self.selected_size += size;
+        let top_level = if self.tree_mode {
+            let previous = replace_entry_size(&mut self.tree_base, path, size);
+            replace_entry_size(&mut self.entries, path, size);
+            if let Some(children) = path.parent().and_then(|p| self.tree_children.get_mut(p)) {
+                replace_entry_size(children, path, size);
+            }
+            previous
+        } else {
+            replace_entry_size(&mut self.entries, path, size)
+        };
+        if let Some(previous) = top_level {
+            self.total_size = self.total_size.saturating_sub(previous) + size;
         }
-    }
-
-    /// 根层条目排序
-    pub fn sort_root_entries(&mut self) {
-        sort_entries_by(&mut self.root_entries, self.sort_order);
         if self.navigation.current_path.is_none() {
-            self.set_entries(self.root_entries.clone());
+            replace_entry_size(&mut self.root_entries, path, size);
         }
+        self.apply_selection_size(path, size);
     }
 
-    /// 目录条目排序
+    /// 开始重新计算当前高亮目录的大小，返回需要计算的路径
+    ///
+    /// 扫描进行中、高亮的不是目录或该目录已在计算中时返回 None。
+    pub fn start_recompute(&mut self) -> Option<PathBuf> {
+        if self.scan_in_progress {
+            self.notice = Some("扫描进行中，稍后再重新计算".to_string());
+            return None;
+        }
+        let entry = self.current_entry()?;
+        if entry.kind != EntryKind::Directory {
+            self.notice = Some("只能重新计算目录的大小".to_string());
+            return None;
+        }
+        let path = entry.path.clone();
+        if self.recomputing.0 != self.scan_generation {
+            self.recomputing = (self.scan_generation, HashSet::new());
+        }
+        self.recomputing.1.insert(path.clone()).then_some(path)
+    }
+
+    /// 目录是否正在重新计算大小（仅当前扫描代数内有效）
+    pub fn is_recomputing(&self, path: &PathBuf) -> bool {
+        self.recomputing.0 == self.scan_generation && self.recomputing.1.contains(path)
+    }
+
+    /// 切换快速列表（对之后列出的子目录生效），关闭时补算当前列表中未计算的目录大小
+    pub fn toggle_lazy_sizes(&mut self) {
+        self.lazy_sizes = !self.lazy_sizes;
+        self.notice = Some(if self.lazy_sizes {
+            "快速列表: 开（高亮或选中目录时计算大小）".to_string()
+        } else {
+            "快速列表: 关".to_string()
+        });
+    }
+
+    /// 取出需要按需计算大小的目录（每个扫描代数内每个路径只返回一次）
+    ///
+    /// 快速列表下为当前高亮的目录及已选中但大小未知的目录；关闭快速列表后，
+    /// 当前仍是快速列表的视图中所有大小未知的目录都会返回，以便补齐。
+    pub fn take_lazy_size_requests(&mut self) -> Vec<PathBuf> {
+        if !self.lazy_listing || self.navigation.current_path.is_none() {
+            return Vec::new();
+        }
+        let generation = self.scan_generation;
+        if self.lazy_size_requested.0 != generation {
+            self.lazy_size_requested = (generation, HashSet::new());
+        }
+        let unknown_dir =
+            |entry: &&CleanableEntry| entry.kind == EntryKind::Directory && entry.size.is_none();
+        let mut candidates: Vec<PathBuf> = if self.lazy_sizes {
+            self.current_entry()
+                .filter(unknown_dir)
+                .map(|entry| entry.path.clone())
+                .into_iter()
+                .chain(
+                    self.selections
+                        .iter()
+                        .filter(|(_, selected)| {
+                            selected.kind == EntryKind::Directory && selected.size.is_none()
+                        })
+                        .map(|(path, _)| path.clone()),
+                )
+                .collect()
+        } else {
+            self.entries
+                .iter()
+                .filter(unknown_dir)
+                .map(|entry| entry.path.clone())
+                .collect()
+        };
+        let requested = &mut self.lazy_size_requested.1;
+        candidates.retain(|path| requested.insert(path.clone()));
+        candidates
+    }
+
+    /// 根层条目排序
+    pub fn sort_root_entries(&mut self) {
+        sort_entries_by(&mut self.root_entries, self.sort_order);
+        if self.navigation.current_path.is_none() {
+            self.active_filter = None;
+            self.set_entries(self.root_entries.clone());
+        }
+    }
+
+    /// 目录条目排序（大小回填后按最小显示大小重新过滤）
     pub fn sort_dir_entries(&mut self) {
-        sort_entries_by(&mut self.entries, self.sort_order);
+        if self.size_floor > 0 {
+            self.apply_size_floor();
+        }
+        if self.tree_mode {
+            sort_entries_by(&mut self.tree_base, self.sort_order);
+            self.rebuild_tree();
+        } else {
+            sort_entries_by(&mut self.entries, self.sort_order);
+        }
         if !self.entries.is_empty() {
             self.list_state.select(Some(0));
         }
     }
 
+    /// 当前高亮条目的路径与索引（原地重新扫描前记录，随后交给 `set_pending_cursor()`）
+    pub fn cursor_position(&self) -> Option<(PathBuf, usize)> {
+        let index = self.list_state.selected()?;
+        self.entries
+            .get(index)
+            .map(|entry| (entry.path.clone(), index))
+    }
+
+    /// 记录本次扫描完成后要恢复的高亮位置（绑定当前扫描代数）
+    pub fn set_pending_cursor(&mut self, cursor: Option<(PathBuf, usize)>) {
+        self.pending_cursor = cursor.map(|(path, index)| (self.scan_generation, path, index));
+    }
+
+    /// 扫描完成后恢复高亮位置：路径仍存在时选中它，否则选中原索引处（超出末尾时为最后一项）
+    ///
+    /// 记录属于更早的扫描代数（期间已导航到其他目录）时忽略。
+    pub fn restore_pending_cursor(&mut self) {
+        let Some((generation, path, index)) = self.pending_cursor.take() else {
+            return;
+        };
+        if generation != self.scan_generation || self.entries.is_empty() {
+            return;
+        }
+        let restored = self
+            .entries
+            .iter()
+            .position(|entry| entry.path == path)
+            .unwrap_or_else(|| index.min(self.entries.len() - 1));
+        self.list_state.select(Some(restored));
+    }
+
     /// 切换排序方式
     pub fn toggle_sort_order(&mut self) {
         self.sort_order = self.sort_order.toggle();
@@ -630,13 +1732,13 @@ impl App {
         }
     }
 
-    /// 获取选中的项目
+    /// 获取选中的项目（根条目的大小含嵌套在其下的其他根条目）
     pub fn get_selected_items(&self) -> Vec<CleanableEntry> {
         self.selections
             .iter()
             .map(|(path, entry)| CleanableEntry {
                 kind: entry.kind,
-                category: None,
+                category: entry.category.clone(),
                 path: path.clone(),
                 name: path
                     .file_name()
@@ -644,589 +1746,3415 @@ impl App {
                     .unwrap_or_else(|| path.display().to_string()),
                 size: entry.size,
                 modified_at: None,
+                file_count: None,
             })
+            .map(|item| with_nested_root_sizes(item, &self.root_entries))
             .collect()
     }
 
-    /// 进入确认删除模式
-    pub fn enter_confirm_mode(&mut self) {
-        if self.selected_size > 0 {
-            self.confirm_scroll = 0;
-            self.dry_run_result = None;
-            self.dry_run_active = false;
-            self.mode = Mode::Confirm;
+    /// 待清理的项目：单项清理目标优先，否则为全部选中项
+    pub fn confirm_items(&self) -> Vec<CleanableEntry> {
+        match &self.confirm_target {
+            Some(target) => vec![with_nested_root_sizes(target.clone(), &self.root_entries)],
+            None => self.get_selected_items(),
         }
     }
 
-    /// 取消确认
-    pub fn cancel_confirm(&mut self) {
-        self.dry_run_result = None;
-        self.dry_run_active = false;
-        self.mode = Mode::Normal;
+    /// 待清理项目中通过安全检查、确认后实际清理的项目
+    pub fn confirm_cleanable_items(&self) -> Vec<CleanableEntry> {
+        self.confirm_items()
+            .into_iter()
+            .filter(|item| !self.confirm_blocked.contains(&item.path))
+            .collect()
     }
 
-    /// 显示/隐藏帮助
-    pub fn toggle_help(&mut self) {
-        self.mode = if self.mode == Mode::Help {
-            Mode::Normal
+    /// 实际是否安全擦除（安全模式下始终为 false）
+    pub fn secure_delete_active(&self) -> bool {
+        self.use_secure_delete && !self.force_trash
+    }
+
+    /// 当前的回收站策略（全局开关 + 按类别规则；安全模式下全部移至回收站）
+    pub fn trash_policy(&self) -> TrashPolicy {
+        if self.force_trash {
+            return TrashPolicy::uniform(true);
+        }
+        TrashPolicy {
+            default_trash: self.use_trash,
+            categories: self.trash_categories.clone(),
+        }
+    }
+
+    /// 确认后对待清理项目的处理方式
+    pub fn confirm_clean_action(&self) -> CleanAction {
+        if self.force_trash {
+            return CleanAction::Trash;
+        }
+        if self.use_secure_delete {
+            return CleanAction::SecureDelete;
+        }
+        if self.trash_categories.is_empty() {
+            return if self.use_trash {
+                CleanAction::Trash
+            } else {
+                CleanAction::Delete
+            };
+        }
+        let policy = self.trash_policy();
+        let items = self.confirm_cleanable_items();
+        let trashed = items
+            .iter()
+            .filter(|item| policy.should_trash(item))
+            .count();
+        if trashed == 0 {
+            CleanAction::Delete
+        } else if trashed == items.len() {
+            CleanAction::Trash
         } else {
-            Mode::Help
-        };
+            CleanAction::Mixed { trashed }
+        }
     }
 
-    /// 退出应用
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// 确认后将被永久删除（不进入回收站）的项目（位于其他待清理项目之下的不重复计入）
+    fn confirm_permanent_items(&self) -> Vec<CleanableEntry> {
+        let policy = self.trash_policy();
+        Cleaner::without_nested(&self.confirm_cleanable_items())
+            .into_iter()
+            .filter(|item| self.secure_delete_active() || !policy.should_trash(item))
+            .collect()
     }
 
-    /// 设置错误消息
-    pub fn set_error(&mut self, msg: String) {
-        self.error_message = Some(msg);
+    /// 确认后将被永久删除（不进入回收站）的项目总大小（大小未知的项目不计入）
+    pub fn confirm_permanent_size(&self) -> u64 {
+        self.confirm_permanent_items()
+            .iter()
+            .filter_map(|item| item.size)
+            .sum()
     }
 
-    /// 清除错误消息
-    pub fn clear_error(&mut self) {
-        self.error_message = None;
+    /// 当前确认是否须先输入确认词（永久删除大小超过 `confirm_word_above`）
+    ///
+    /// 大小未知的项目（如快速列表中尚未计算的目录）视为超过阈值。
+    fn confirm_word_required(&self) -> bool {
+        self.confirm_word_above.is_some_and(|threshold| {
+            let items = self.confirm_permanent_items();
+            items.iter().any(|item| item.size.is_none())
+                || items.iter().filter_map(|item| item.size).sum::<u64>() > threshold
+        })
     }
 
-    /// 面包屑路径
-    pub fn breadcrumb(&self) -> String {
-        self.navigation.breadcrumb()
+    /// 确认词输入一个字符
+    pub fn confirm_word_char(&mut self, c: char) {
+        if let Some(input) = self.confirm_word_input.as_mut() {
+            input.push(c);
+        }
     }
 
-    /// 重置扫描状态
-    pub fn finish_scan(&mut self) {
-        self.scan_in_progress = false;
-        if self.mode == Mode::Scanning {
-            self.mode = Mode::Normal;
+    /// 确认词删除最后一个字符
+    pub fn confirm_word_backspace(&mut self) {
+        if let Some(input) = self.confirm_word_input.as_mut() {
+            input.pop();
         }
-        self.scan_progress = SCAN_PROGRESS_COMPLETE;
     }
 
-    /// 清除所有选中
-    pub fn clear_selections(&mut self) {
-        self.selections.clear();
-        self.selected_size = 0;
+    /// Enter 是否可以执行清理（无需确认词，或已输入正确的确认词）
+    pub fn confirm_armed(&self) -> bool {
+        self.confirm_word_input
+            .as_deref()
+            .is_none_or(|input| input == CONFIRM_WORD)
     }
 
-    /// 进入搜索模式
-    pub fn start_search(&mut self) {
-        self.search_query.clear();
-        self.pre_search_entries = self.entries.clone();
-        self.mode = Mode::Search;
+    /// 被安全检查阻止的待清理项目总大小
+    pub fn confirm_blocked_size(&self) -> u64 {
+        if self.confirm_blocked.is_empty() {
+            return 0;
+        }
+        self.confirm_items()
+            .iter()
+            .filter(|item| self.confirm_blocked.contains(&item.path))
+            .filter_map(|item| item.size)
+            .sum()
     }
 
-    /// 搜索输入字符
-    pub fn search_char(&mut self, c: char) {
-        self.search_query.push(c);
-        self.apply_search_filter();
+    /// 待清理项目的总大小（含随上级一并删除的嵌套根条目，位于其他待清理项目之下的不重复计入）
+    pub fn confirm_size(&self) -> u64 {
+        Cleaner::without_nested(&self.confirm_items())
+            .iter()
+            .filter_map(|item| item.size)
+            .sum()
     }
 
-    /// 搜索删除字符
-    pub fn search_backspace(&mut self) {
-        self.search_query.pop();
-        self.apply_search_filter();
+    /// 清理成功后取消选择已清理的路径
+    pub fn deselect_cleaned(&mut self, cleaned_items: &[CleanableEntry]) {
+        for item in cleaned_items {
+            if let Some(previous_selection) = self.selections.remove(&item.path)
+                && let Some(size) = previous_selection.size
+            {
+                self.selected_size = self.selected_size.saturating_sub(size);
+            }
+        }
     }
 
-    /// 应用搜索过滤
-    fn apply_search_filter(&mut self) {
-        if self.search_query.is_empty() {
-            self.set_entries(self.pre_search_entries.clone());
-            return;
+    /// 进入确认删除模式
+    pub fn enter_confirm_mode(&mut self) {
+        if self.selected_size > 0 {
+            self.confirm_target = None;
+            self.open_confirm();
         }
-        let query = self.search_query.to_lowercase();
-        let filtered: Vec<CleanableEntry> = self
-            .pre_search_entries
-            .iter()
-            .filter(|entry| entry.name.to_lowercase().contains(&query))
-            .cloned()
-            .collect();
-        self.set_entries(filtered);
     }
 
-    /// 确认搜索（保留过滤结果）
-    pub fn confirm_search(&mut self) {
-        self.mode = Mode::Normal;
+    /// 仅针对当前高亮条目进入确认删除模式（不改变多选集合）
+    pub fn enter_single_confirm_mode(&mut self) {
+        if let Some(entry) = self.current_entry().cloned() {
+            self.confirm_target = Some(entry);
+            self.open_confirm();
+        }
     }
 
-    /// 取消搜索（恢复原始列表）
-    pub fn cancel_search(&mut self) {
-        self.mode = Mode::Normal;
-        let restored = self.pre_search_entries.clone();
-        self.set_entries(restored);
-        self.search_query.clear();
+    /// 确认弹窗当前视图（确认列表或 dry-run）的条目数
+    fn confirm_list_len(&self) -> usize {
+        if self.dry_run_active {
+            self.dry_run_result
+                .as_ref()
+                .map_or(0, |result| result.items.len())
+        } else {
+            self.confirm_items().len()
+        }
     }
 
-    /// 进入路径输入模式
-    pub fn start_input(&mut self) {
-        self.input_buffer.clear();
-        self.reset_tab_completions();
-        self.mode = Mode::InputPath;
+    /// 确认弹窗的最大滚动偏移（与渲染时的截断规则一致）
+    fn max_confirm_scroll(&self) -> usize {
+        self.confirm_list_len()
+            .saturating_sub(self.confirm_visible_height)
     }
 
-    /// 输入字符
-    pub fn input_char(&mut self, c: char) {
-        self.input_buffer.push(c);
-        self.reset_tab_completions();
+    /// 将确认弹窗滚动偏移限制在共 `len` 条、可见 `height` 行时的有效范围内
+    pub fn clamp_confirm_scroll(&mut self, len: usize, height: usize) {
+        self.confirm_scroll = self.confirm_scroll.min(len.saturating_sub(height));
     }
 
-    /// 删除输入字符
-    pub fn input_backspace(&mut self) {
-        self.input_buffer.pop();
-        self.reset_tab_completions();
+    /// 按当前视图（确认列表或 dry-run）的条目数修正滚动偏移，列表变化后调用
+    pub fn clamp_confirm_scroll_to_view(&mut self) {
+        self.clamp_confirm_scroll(self.confirm_list_len(), self.confirm_visible_height);
     }
 
-    /// 确认输入并返回路径
-    pub fn confirm_input(&mut self) -> Option<PathBuf> {
-        self.mode = Mode::Normal;
-        let path = self.input_buffer.trim();
-        if path.is_empty() {
-            return None;
-        }
-        let expanded = Self::expand_input_tilde(path);
-        Some(PathBuf::from(expanded))
+    /// 确认弹窗向下滚动若干行
+    pub fn scroll_confirm_down(&mut self, lines: usize) {
+        self.confirm_scroll = self
+            .confirm_scroll
+            .saturating_add(lines)
+            .min(self.max_confirm_scroll());
     }
 
-    /// 取消输入
-    pub fn cancel_input(&mut self) {
-        self.input_buffer.clear();
-        self.reset_tab_completions();
-        self.mode = Mode::Normal;
+    /// 确认弹窗向上滚动若干行
+    pub fn scroll_confirm_up(&mut self, lines: usize) {
+        self.confirm_scroll = self
+            .confirm_scroll
+            .min(self.max_confirm_scroll())
+            .saturating_sub(lines);
     }
 
-    /// 将路径中的 `~` 展开为主目录绝对路径
-    fn expand_input_tilde(raw: &str) -> String {
-        expand_tilde(raw)
+    /// 确认弹窗滚动到顶部
+    pub fn scroll_confirm_top(&mut self) {
+        self.confirm_scroll = 0;
     }
 
-    /// Tab 正向补全/循环
-    pub fn input_tab_complete(&mut self) {
-        // 已有候选列表时，正向循环
-        if !self.tab_completions.is_empty() {
-            if let Some(index) = self.tab_completion_index {
-                let next_index = (index + 1) % self.tab_completions.len();
-                self.tab_completion_index = Some(next_index);
-                self.input_buffer = self.tab_completions[next_index].clone();
-            }
-            return;
-        }
+    /// 确认弹窗滚动到底部
+    pub fn scroll_confirm_bottom(&mut self) {
+        self.confirm_scroll = self.max_confirm_scroll();
+    }
 
-        self.build_tab_completions();
+    fn open_confirm(&mut self) {
+        self.confirm_blocked = self
+            .confirm_items()
+            .into_iter()
+            .filter(|item| !Cleaner::is_safe_to_delete(&item.path, &self.safety_rules))
+            .map(|item| item.path)
+            .collect();
+        self.confirm_scroll = 0;
+        self.confirm_word_input = self.confirm_word_required().then(String::new);
+        self.dry_run_result = None;
+        self.dry_run_active = false;
+        self.mode = Mode::Confirm;
+        if self.confirm_dry_run_first {
+            self.request_dry_run();
+        }
     }
 
-    /// Shift+Tab 反向循环
-    pub fn input_tab_complete_prev(&mut self) {
-        if self.tab_completions.is_empty() {
-            self.build_tab_completions();
-            return;
+    /// 切换确认弹窗的 dry-run 视图；首次打开时请求后台计算
+    pub fn toggle_dry_run(&mut self) {
+        if self.dry_run_active {
+            self.dry_run_active = false;
+            self.confirm_scroll = 0;
+        } else if self.dry_run_result.is_some() {
+            self.dry_run_active = true;
+            self.confirm_scroll = 0;
+        } else {
+            self.request_dry_run();
         }
+    }
 
-        if let Some(index) = self.tab_completion_index {
-            let prev_index = if index == 0 {
-                self.tab_completions.len() - 1
-            } else {
-                index - 1
-            };
-            self.tab_completion_index = Some(prev_index);
-            self.input_buffer = self.tab_completions[prev_index].clone();
+    /// 打开 dry-run 视图并请求在后台计算（结果逐项到达，完成前显示计算中）
+    fn request_dry_run(&mut self) {
+        self.dry_run_generation += 1;
+        self.dry_run_result = Some(DryRunResult::default());
+        self.dry_run_active = true;
+        self.dry_run_requested = true;
+        self.dry_run_pending = true;
+        self.confirm_scroll = 0;
+    }
+
+    /// 取出待启动的 dry-run 请求：返回请求代数与待计算的项目
+    pub fn take_dry_run_request(&mut self) -> Option<(u64, Vec<CleanableEntry>)> {
+        if !std::mem::take(&mut self.dry_run_requested) {
+            return None;
         }
+        let items = self.confirm_cleanable_items();
+        self.dry_run_total = items.len();
+        Some((self.dry_run_generation, items))
     }
 
-    /// 构建 Tab 补全候选列表
-    fn build_tab_completions(&mut self) {
-        let raw_input = self.input_buffer.trim();
-        if raw_input.is_empty() {
+    /// 应用后台 dry-run 消息（请求已过期或弹窗已关闭时忽略）
+    pub fn apply_dry_run_message(&mut self, message: DryRunMessage) {
+        if message.job_id() != self.dry_run_generation || self.mode != Mode::Confirm {
             return;
         }
+        match message {
+            DryRunMessage::Item { item, .. } => {
+                if let Some(result) = self.dry_run_result.as_mut() {
+                    result.push(item);
+                }
+            }
+            DryRunMessage::Done { .. } => self.dry_run_pending = false,
+        }
+    }
 
-        let expanded_input = Self::expand_input_tilde(raw_input);
-        let Some((parent_dir, prefix)) = Self::parse_path_input(&expanded_input) else {
-            return;
-        };
+    /// 后台 dry-run 是否仍在计算（为 false 时主循环可丢弃接收端）
+    pub fn dry_run_in_progress(&self) -> bool {
+        self.dry_run_pending
+    }
 
-        let matching_directories = Self::read_matching_dirs(&parent_dir, &prefix);
-        if matching_directories.is_empty() {
-            return;
-        }
+    /// dry-run 视图是否正在显示尚未完成的计算
+    pub fn dry_run_computing(&self) -> bool {
+        self.dry_run_active && self.dry_run_pending
+    }
 
-        let home_directory = if raw_input.starts_with('~') {
-            directories::UserDirs::new().map(|dirs| dirs.home_dir().display().to_string())
-        } else {
-            None
-        };
+    /// 进入清理中模式
+    pub fn start_cleaning(&mut self, total: usize, total_bytes: u64) {
+        self.mode = Mode::Cleaning;
+        self.clean_completed = 0;
+        self.clean_total = total;
+        self.clean_done_bytes = 0;
+        self.clean_total_bytes = total_bytes;
+        self.current_clean_path.clear();
+        self.clean_cancel_requested = false;
+        self.emptying_trash = false;
+    }
 
-        let mut completions: Vec<String> = matching_directories
-            .into_iter()
-            .map(|expanded_path| {
-                Self::build_completion_display_path(expanded_path, home_directory.as_deref())
-            })
-            .collect();
-        completions.sort();
-        self.tab_completions = completions;
-        self.tab_completion_index = Some(0);
-        self.input_buffer = self.tab_completions[0].clone();
+    /// 进入清空回收站的清理中模式（条目数随进度更新，总大小取自后台统计的 `trash_usage`）
+    pub fn start_emptying_trash(&mut self) {
+        self.start_cleaning(0, self.trash_usage.unwrap_or(0));
+        self.emptying_trash = true;
     }
 
-    fn parse_path_input(expanded_input: &str) -> Option<(String, String)> {
-        let expanded_path = std::path::Path::new(expanded_input);
-        if expanded_input.ends_with('/') {
-            return Some((expanded_input.to_string(), String::new()));
+    /// 更新清理进度
+    pub fn update_clean_progress(
+        &mut self,
+        completed: usize,
+        total: usize,
+        path: &Path,
+        size: u64,
+    ) {
+        self.clean_completed = completed;
+        self.clean_total = total;
+        self.clean_done_bytes += size;
+        self.current_clean_path = path.display().to_string();
+    }
+
+    /// 清理进度百分比 (0-100)
+    ///
+    /// 按已处理字节占总大小计算；总大小未知（为 0）时按条目数计算。
+    pub fn clean_percent(&self) -> u16 {
+        if let Some(percent) = (self.clean_done_bytes.min(self.clean_total_bytes) * 100)
+            .checked_div(self.clean_total_bytes)
+        {
+            return percent as u16;
+        }
+        if self.clean_total == 0 {
+            return 0;
         }
+        (self.clean_completed * 100 / self.clean_total) as u16
+    }
 
-        let parent_directory = expanded_path.parent()?;
-        let file_name_prefix = expanded_path
-            .file_name()
-            .map(|name| name.to_string_lossy().to_string())
-            .unwrap_or_default();
-        Some((parent_directory.display().to_string(), file_name_prefix))
+    /// 退出清理中模式
+    pub fn finish_cleaning(&mut self) {
+        if self.mode == Mode::Cleaning {
+            self.mode = Mode::Normal;
+        }
+        self.clean_cancel_requested = false;
+        self.emptying_trash = false;
     }
 
-    fn read_matching_dirs(parent_dir: &str, prefix: &str) -> Vec<String> {
-        let read_dir = match std::fs::read_dir(parent_dir) {
-            Ok(read_dir) => read_dir,
-            Err(_) => return Vec::new(),
+    /// 进入清空回收站确认（大小取自后台统计的 `trash_usage`；安全模式下仅提示，不进入确认）
+    pub fn enter_empty_trash_confirm(&mut self) {
+        if self.force_trash {
+            self.notice = Some("安全模式: 禁止清空回收站".to_string());
+            return;
+        }
+        self.mode = Mode::ConfirmEmptyTrash;
+    }
+
+    /// 取消确认
+    pub fn cancel_confirm(&mut self) {
+        self.confirm_target = None;
+        self.confirm_blocked.clear();
+        self.confirm_word_input = None;
+        self.dry_run_result = None;
+        self.dry_run_active = false;
+        self.dry_run_requested = false;
+        self.dry_run_pending = false;
+        self.dry_run_generation += 1;
+        self.mode = Mode::Normal;
+    }
+
+    /// 显示/隐藏帮助
+    pub fn toggle_help(&mut self) {
+        self.mode = if self.mode == Mode::Help {
+            Mode::Normal
+        } else {
+            self.help_scroll = 0;
+            Mode::Help
         };
+    }
 
-        let lowercase_prefix = prefix.to_lowercase();
-        let mut matched_directories = Vec::new();
+    /// 设置书签路径（可用状态在打开列表时刷新）
+    pub fn set_bookmarks(&mut self, paths: Vec<PathBuf>) {
+        self.bookmarks = Bookmark::from_paths(paths);
+        self.bookmark_index = 0;
+    }
 
-        for directory_entry in read_dir.flatten() {
-            let file_type = match directory_entry.file_type() {
-                Ok(file_type) => file_type,
-                Err(_) => continue,
+    /// 打开书签列表：刷新各书签是否存在，并高亮第一个可用书签
+    pub fn open_bookmarks(&mut self) {
+        if self.bookmarks.is_empty() {
+            self.notice = Some("未配置书签（在配置文件 ui.bookmarks 中添加）".to_string());
+            return;
+        }
+        for bookmark in &mut self.bookmarks {
+            bookmark.available = bookmark.path.is_dir();
+        }
+        self.bookmark_index = self
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.available)
+            .unwrap_or(0);
+        self.mode = Mode::Bookmarks;
+    }
+
+    /// 关闭书签列表
+    pub fn close_bookmarks(&mut self) {
+        if self.mode == Mode::Bookmarks {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// 高亮下一个可用书签（跳过不存在的书签，到底部时停留）
+    pub fn bookmark_next(&mut self) {
+        if let Some(index) = (self.bookmark_index + 1..self.bookmarks.len())
+            .find(|&index| self.bookmarks[index].available)
+        {
+            self.bookmark_index = index;
+        }
+    }
+
+    /// 高亮上一个可用书签（跳过不存在的书签，到顶部时停留）
+    pub fn bookmark_previous(&mut self) {
+        if let Some(index) = (0..self.bookmark_index)
+            .rev()
+            .find(|&index| self.bookmarks[index].available)
+        {
+            self.bookmark_index = index;
+        }
+    }
+
+    /// 选择当前高亮的书签：可用时关闭列表并返回其路径，不可用时不做任何事
+    pub fn select_bookmark(&mut self) -> Option<PathBuf> {
+        let bookmark = self
+            .bookmarks
+            .get(self.bookmark_index)
+            .filter(|bookmark| bookmark.available)?;
+        let path = bookmark.path.clone();
+        self.close_bookmarks();
+        Some(path)
+    }
+
+    /// 打开扫描类别设置面板
+    pub fn open_settings(&mut self) {
+        self.settings_index = 0;
+        self.mode = Mode::Settings;
+    }
+
+    /// 关闭扫描类别设置面板
+    pub fn close_settings(&mut self) {
+        if self.mode == Mode::Settings {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// 高亮下一个类别（到底部时停留）
+    pub fn settings_next(&mut self) {
+        if self.settings_index + 1 < Scanner::PRESET_CATEGORIES.len() {
+            self.settings_index += 1;
+        }
+    }
+
+    /// 高亮上一个类别（到顶部时停留）
+    pub fn settings_previous(&mut self) {
+        self.settings_index = self.settings_index.saturating_sub(1);
+    }
+
+    /// 预设扫描是否包含该类别
+    pub fn is_category_enabled(&self, category: &ItemCategory) -> bool {
+        !self
+            .disabled_categories
+            .iter()
+            .any(|disabled| category.matches_filter(disabled))
+    }
+
+    /// 切换高亮类别是否参与预设扫描（下次按 s 扫描时生效）
+    pub fn toggle_settings_category(&mut self) {
+        let Some(category) = Scanner::PRESET_CATEGORIES.get(self.settings_index) else {
+            return;
+        };
+        if self.is_category_enabled(category) {
+            self.disabled_categories.push(category.clone());
+        } else {
+            self.disabled_categories
+                .retain(|disabled| !category.matches_filter(disabled));
+        }
+    }
+
+    /// 类别开关是否与启动时不同（按集合比较，来回切换后视为未修改）
+    pub fn settings_changed(&self) -> bool {
+        let saved = &self.saved_disabled_categories;
+        let current = &self.disabled_categories;
+        !(current.iter().all(|category| saved.contains(category))
+            && saved.iter().all(|category| current.contains(category)))
+    }
+
+    /// 退出应用
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    /// 请求退出：有已选项目且启用 `ui.confirm_quit` 时先进入确认，否则直接退出
+    pub fn request_quit(&mut self) {
+        if self.confirm_quit && !self.selections.is_empty() {
+            self.quit_return_mode = self.mode;
+            self.mode = Mode::ConfirmQuit;
+        } else {
+            self.quit();
+        }
+    }
+
+    /// 取消退出确认，回到进入确认前的模式（确认期间扫描已结束时回到浏览）
+    pub fn cancel_quit(&mut self) {
+        if self.mode == Mode::ConfirmQuit {
+            self.mode = match self.quit_return_mode {
+                Mode::Scanning if !self.scan_in_progress => Mode::Normal,
+                mode => mode,
             };
-            if !file_type.is_dir() {
-                continue;
+        }
+    }
+
+    /// 设置错误消息
+    pub fn set_error(&mut self, msg: String) {
+        self.error_message = Some(msg);
+    }
+
+    /// 设置清理失败的错误列表
+    pub fn set_clean_errors(&mut self, errors: Vec<CleanError>) {
+        self.error_message = Some(format!("部分清理失败（{} 项）", errors.len()));
+        self.clean_errors = errors;
+    }
+
+    /// 按类别分组清理错误（保持首次出现的类别顺序）
+    pub fn grouped_clean_errors(&self) -> Vec<(&'static str, Vec<&CleanError>)> {
+        let mut groups: Vec<(&'static str, Vec<&CleanError>)> = Vec::new();
+        for error in &self.clean_errors {
+            let label = error.label();
+            match groups
+                .iter_mut()
+                .find(|(group_label, _)| *group_label == label)
+            {
+                Some((_, errors)) => errors.push(error),
+                None => groups.push((label, vec![error])),
             }
+        }
+        groups
+    }
 
-            let entry_name = directory_entry.file_name().to_string_lossy().to_string();
-            if !prefix.is_empty() && !entry_name.to_lowercase().starts_with(&lowercase_prefix) {
-                continue;
+    /// 清除错误消息
+    pub fn clear_error(&mut self) {
+        self.error_message = None;
+        self.clean_errors.clear();
+    }
+
+    /// 面包屑路径
+    pub fn breadcrumb(&self) -> String {
+        self.navigation.breadcrumb()
+    }
+
+    /// 开始扫描计时（重置剩余时间估算）
+    pub fn start_scan_clock(&mut self, now: Instant) {
+        self.scan_started_at = Some(now);
+        self.scan_eta = None;
+        self.scan_peak_progress = 0;
+    }
+
+    /// 记录扫描进度并更新平滑后的剩余时间估算
+    ///
+    /// 只有进度超过此前最高值时才更新估算，回退或重复的进度被忽略。
+    pub fn record_scan_progress(&mut self, progress: u8, now: Instant) {
+        self.scan_progress = progress;
+        let Some(started_at) = self.scan_started_at else {
+            return;
+        };
+        if progress <= self.scan_peak_progress || progress >= SCAN_PROGRESS_COMPLETE {
+            return;
+        }
+        self.scan_peak_progress = progress;
+
+        let elapsed = now.saturating_duration_since(started_at).as_secs_f64();
+        let fraction = f64::from(progress) / f64::from(SCAN_PROGRESS_COMPLETE);
+        let sample = elapsed * (1.0 - fraction) / fraction;
+        let smoothed = match self.scan_eta {
+            Some((previous, estimated_at)) => {
+                let previous_remaining = previous
+                    .saturating_sub(now.saturating_duration_since(estimated_at))
+                    .as_secs_f64();
+                previous_remaining * (1.0 - SCAN_ETA_SMOOTHING) + sample * SCAN_ETA_SMOOTHING
             }
+            None => sample,
+        };
+        self.scan_eta = Some((Duration::from_secs_f64(smoothed), now));
+    }
 
-            let expanded_path = format!(
-                "{}{}{}/",
-                parent_dir,
-                if parent_dir.ends_with('/') { "" } else { "/" },
-                entry_name
-            );
-            matched_directories.push(expanded_path);
+    /// 当前的剩余时间估算；尚无进度或估算已耗尽（进度停滞）时返回 None
+    pub fn scan_eta_remaining(&self, now: Instant) -> Option<Duration> {
+        let (eta, estimated_at) = self.scan_eta?;
+        let remaining = eta.saturating_sub(now.saturating_duration_since(estimated_at));
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// 自动选中当前视图中修改时间早于 `auto_select_older_than_days` 的条目，返回新选中的数量
+    ///
+    /// 只在用户发起的预设/磁盘扫描完成时生效（须在 `complete_scan_target()` 之前调用），
+    /// 子目录列出与清理后的重新扫描不会重新选中用户已取消的条目。
+    /// 跳过已选中的条目、修改时间未知的条目、当前目录本身以及未通过安全检查的路径；
+    /// 天数过大导致时间溢出时不选中任何条目。
+    pub fn auto_select_stale_entries(&mut self, now: SystemTime) -> usize {
+        let Some(days) = self.auto_select_older_than_days else {
+            return 0;
+        };
+        if self.pending_scan_target.is_none() {
+            return 0;
+        }
+        let Some(cutoff) = days
+            .checked_mul(SECONDS_PER_DAY as u64)
+            .and_then(|seconds| now.checked_sub(Duration::from_secs(seconds)))
+        else {
+            return 0;
+        };
+        let stale: Vec<CleanableEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.modified_at.is_some_and(|time| time < cutoff))
+            .filter(|entry| !self.selections.contains_key(&entry.path))
+            .filter(|entry| self.navigation.current_path.as_ref() != Some(&entry.path))
+            .filter(|entry| Cleaner::is_safe_to_delete(&entry.path, &self.safety_rules))
+            .cloned()
+            .collect();
+        for entry in &stale {
+            self.set_selected(&entry.path, true, entry);
         }
+        stale.len()
+    }
 
-        matched_directories
+    /// 重置扫描状态
+    pub fn finish_scan(&mut self) {
+        self.scan_in_progress = false;
+        if self.appending_scan && self.append_overlaps > 0 {
+            self.notice = Some(format!(
+                "跳过 {} 个与已有结果重叠的条目",
+                self.append_overlaps
+            ));
+        }
+        self.appending_scan = false;
+        self.scan_started_at = None;
+        self.scan_eta = None;
+        if self.mode == Mode::Scanning {
+            self.mode = Mode::Normal;
+        }
+        self.scan_progress = SCAN_PROGRESS_COMPLETE;
     }
 
-    fn build_completion_display_path(
-        expanded_path: String,
-        home_directory: Option<&str>,
-    ) -> String {
-        if let Some(home_path) = home_directory {
-            expanded_path.replacen(home_path, "~", 1)
-        } else {
-            expanded_path
+    /// 清除所有选中
+    pub fn clear_selections(&mut self) {
+        self.selections.clear();
+        self.selected_size = 0;
+    }
+
+    /// 进入搜索模式
+    pub fn start_search(&mut self) {
+        self.active_filter = None;
+        self.search_query.clear();
+        self.search_error = None;
+        self.search_history_cursor = None;
+        self.pre_search_entries = self.view_entries();
+        self.mode = Mode::Search;
+    }
+
+    /// 搜索中按上键：回填上一条历史搜索词（已在最旧一条时保持不变）
+    pub fn search_history_prev(&mut self) {
+        let index = match self.search_history_cursor {
+            Some(index) => index.saturating_sub(1),
+            None => match self.search_history.len().checked_sub(1) {
+                Some(last) => last,
+                None => return,
+            },
+        };
+        self.recall_search_history(Some(index));
+    }
+
+    /// 搜索中按下键：回填下一条历史搜索词，越过最新一条时清空输入
+    pub fn search_history_next(&mut self) {
+        let Some(index) = self.search_history_cursor else {
+            return;
+        };
+        let next = (index + 1 < self.search_history.len()).then_some(index + 1);
+        self.recall_search_history(next);
+    }
+
+    fn recall_search_history(&mut self, index: Option<usize>) {
+        self.search_history_cursor = index;
+        self.search_query = index
+            .map(|index| self.search_history[index].clone())
+            .unwrap_or_default();
+        self.apply_search_filter();
+    }
+
+    /// 搜索输入字符
+    pub fn search_char(&mut self, c: char) {
+        self.search_query.push(c);
+        self.apply_search_filter();
+    }
+
+    /// 搜索删除字符
+    pub fn search_backspace(&mut self) {
+        self.search_query.pop();
+        self.apply_search_filter();
+    }
+
+    /// 切换正则/子串搜索模式
+    pub fn toggle_search_regex(&mut self) {
+        self.search_is_regex = !self.search_is_regex;
+        self.apply_search_filter();
+    }
+
+    /// 切换过滤/跳转搜索模式；切到跳转模式时恢复完整列表
+    pub fn toggle_search_jump(&mut self) {
+        self.search_jump = !self.search_jump;
+        if self.search_jump {
+            self.set_entries(self.pre_search_entries.clone());
         }
+        self.apply_search_filter();
     }
 
-    /// 清空 Tab 补全状态
-    pub fn reset_tab_completions(&mut self) {
-        self.tab_completions.clear();
-        self.tab_completion_index = None;
+    /// 跳转模式下用于高亮名称中匹配部分的匹配器（不在跳转搜索中时为 None）
+    pub fn search_highlighter(&self) -> Option<SearchMatcher> {
+        if self.mode != Mode::Search || !self.search_jump {
+            return None;
+        }
+        SearchMatcher::new(&self.search_query, self.search_is_regex)
     }
 
-    /// 切换统计面板
-    pub fn toggle_stats(&mut self) {
-        if self.root_entries.is_empty() {
+    /// 应用搜索过滤
+    ///
+    /// 正则模式下表达式无效（例如输入到一半）时保留当前列表并记录提示。
+    /// 跳转模式下不改变列表，只把光标移到第一个匹配项（没有匹配时光标不动）。
+    fn apply_search_filter(&mut self) {
+        self.search_error = None;
+        if self.search_jump {
+            if self.search_query.is_empty() {
+                return;
+            }
+            match SearchMatcher::new(&self.search_query, self.search_is_regex) {
+                Some(matcher) => {
+                    if let Some(index) = self
+                        .entries
+                        .iter()
+                        .position(|entry| matcher.is_match(&entry.name))
+                    {
+                        self.list_state.select(Some(index));
+                    }
+                }
+                None => self.search_error = Some("无效的正则表达式".to_string()),
+            }
             return;
         }
-        self.mode = if self.mode == Mode::Stats {
-            Mode::Normal
+        if self.search_query.is_empty() {
+            self.set_entries(self.pre_search_entries.clone());
+            return;
+        }
+        match matching_entries(
+            &self.pre_search_entries,
+            &self.search_query,
+            self.search_is_regex,
+        ) {
+            Some(filtered) => self.set_entries(filtered),
+            None => self.search_error = Some("无效的正则表达式".to_string()),
+        }
+    }
+
+    /// 选中当前视图中所有名称匹配 `query` 的条目（与滚动位置无关），返回匹配数
+    ///
+    /// 只增加选择、不取消已有选择；树形视图下只匹配顶层条目。正则无效时不选择并返回 0。
+    pub fn select_filtered(&mut self, query: &str) -> usize {
+        let base = if self.tree_mode {
+            &self.tree_base
         } else {
-            Mode::Stats
+            &self.entries
+        };
+        let Some(matched) = matching_entries(base, query, self.search_is_regex) else {
+            return 0;
         };
+        self.select_all_entries(&matched);
+        matched.len()
     }
 
-    /// 按分类聚合统计信息，返回 (分类名, 总大小) 按大小降序
-    pub fn get_category_stats(&self) -> Vec<(String, u64)> {
-        let mut stats: HashMap<String, u64> = HashMap::new();
-        for entry in &self.root_entries {
-            let category_name = entry
-                .category
-                .as_ref()
-                .map(|c| c.as_str().to_string())
-                .unwrap_or_else(|| "其他".to_string());
-            let size = entry.size.unwrap_or(0);
-            *stats.entry(category_name).or_insert(0) += size;
+    /// 按正在输入或已确认的搜索词选中全部匹配条目（搜索模式 Ctrl+a / 普通模式 `A`）
+    pub fn select_search_matches(&mut self) {
+        let query = if self.mode == Mode::Search {
+            self.search_query.clone()
+        } else if let Some(filter) = &self.active_filter {
+            filter.query.clone()
+        } else {
+            self.notice = Some("没有生效的搜索过滤".to_string());
+            return;
+        };
+        if query.is_empty() {
+            self.notice = Some("搜索词为空".to_string());
+            return;
         }
-        let mut result: Vec<(String, u64)> = stats.into_iter().collect();
-        result.sort_by(|a, b| b.1.cmp(&a.1));
-        result
+        let count = self.select_filtered(&query);
+        self.notice = Some(format!("已选中 {} 个匹配“{}”的条目", count, query));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
+    /// 确认搜索（保留过滤结果）
+    pub fn confirm_search(&mut self) {
+        self.mode = Mode::Normal;
+        self.search_history_cursor = None;
+        if !self.search_query.is_empty() && self.search_history.last() != Some(&self.search_query) {
+            self.search_history.push(self.search_query.clone());
+            if self.search_history.len() > SEARCH_HISTORY_LIMIT {
+                self.search_history.remove(0);
+            }
+        }
+        self.active_filter =
+            (!self.search_jump && !self.search_query.is_empty()).then(|| ActiveFilter {
+                query: self.search_query.clone(),
+                total: self.pre_search_entries.len(),
+            });
+    }
 
-    fn entry(path: &str, size: Option<u64>) -> CleanableEntry {
-        CleanableEntry {
-            kind: EntryKind::File,
-            category: None,
-            path: PathBuf::from(path),
-            name: "item".to_string(),
-            size,
-            modified_at: None,
+    /// 取消搜索（恢复原始列表）
+    pub fn cancel_search(&mut self) {
+        self.mode = Mode::Normal;
+        self.search_history_cursor = None;
+        self.active_filter = None;
+        self.search_error = None;
+        // 跳转模式未改变列表，光标停留在最后跳到的位置
+        if !self.search_jump {
+            let restored = self.pre_search_entries.clone();
+            self.set_entries(restored);
+        }
+        self.search_query.clear();
+    }
+
+    /// 切换树形视图：开启时以当前列表为顶层；关闭时恢复顶层列表并收起所有目录
+    pub fn toggle_tree_mode(&mut self) {
+        if self.tree_mode {
+            self.tree_mode = false;
+            let base = std::mem::take(&mut self.tree_base);
+            self.entry_depths.clear();
+            self.expanded.clear();
+            self.tree_children.clear();
+            let selected_path = self.current_entry().map(|entry| entry.path.clone());
+            self.entries = base;
+            let index = selected_path
+                .and_then(|path| self.entries.iter().position(|entry| entry.path == path))
+                .or((!self.entries.is_empty()).then_some(0));
+            self.list_state.select(index);
+        } else {
+            self.tree_mode = true;
+            self.tree_base = self.entries.clone();
+            self.entry_depths = vec![0; self.entries.len()];
         }
+        self.select_anchor = None;
+    }
+
+    /// 展开/收起当前高亮的目录
+    ///
+    /// 首次展开尚未加载子项的目录时返回其路径，由调用方启动该目录的列表扫描。
+    pub fn toggle_expand(&mut self) -> Option<PathBuf> {
+        let entry = self.current_entry()?;
+        if !self.tree_mode || entry.kind != EntryKind::Directory {
+            return None;
+        }
+        let path = entry.path.clone();
+        if self.expanded.remove(&path) {
+            self.rebuild_tree();
+            return None;
+        }
+        self.expanded.insert(path.clone());
+        let needs_scan = !self.tree_children.contains_key(&path);
+        if needs_scan {
+            self.tree_children.insert(path.clone(), Vec::new());
+        }
+        self.rebuild_tree();
+        needs_scan.then_some(path)
+    }
+
+    /// 记录展开目录扫描到的子项（父目录未在等待加载时忽略），需随后调用 `rebuild_tree()`
+    pub fn apply_tree_child(&mut self, entry: CleanableEntry) {
+        let Some(parent) = entry.path.parent() else {
+            return;
+        };
+        if !self.tree_children.contains_key(parent) {
+            return;
+        }
+        self.refresh_selection(&entry);
+        if let Some(children) = self.tree_children.get_mut(parent) {
+            children.push(entry);
+        }
+    }
+
+    /// 回填展开目录中子目录的大小
+    pub fn apply_tree_child_size(&mut self, path: &PathBuf, size: u64) {
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        if let Some(children) = self.tree_children.get_mut(parent) {
+            fill_entry_size(children, path, size);
+            fill_entry_size(&mut self.entries, path, size);
+        }
+        if let Some(selected) = self.selections.get_mut(path)
+            && selected.size.is_none()
+        {
+            selected.size = Some(size);
+            self.selected_size += size;
+        }
+    }
+
+    /// 按展开状态重新生成扁平列表，并尽量保持高亮条目不变
+    pub fn rebuild_tree(&mut self) {
+        if !self.tree_mode {
+            return;
+        }
+        let selected_path = self.current_entry().map(|entry| entry.path.clone());
+        let mut entries = Vec::new();
+        let mut depths = Vec::new();
+        for entry in &self.tree_base {
+            self.push_tree_node(entry, 0, &mut entries, &mut depths);
+        }
+        self.entries = entries;
+        self.entry_depths = depths;
+
+        let index = selected_path
+            .and_then(|path| self.entries.iter().position(|entry| entry.path == path))
+            .or_else(|| {
+                let last = self.entries.len().checked_sub(1)?;
+                Some(self.list_state.selected().unwrap_or(0).min(last))
+            });
+        self.list_state.select(index);
+    }
+
+    fn push_tree_node(
+        &self,
+        entry: &CleanableEntry,
+        depth: usize,
+        entries: &mut Vec<CleanableEntry>,
+        depths: &mut Vec<usize>,
+    ) {
+        if self.is_hidden(&entry.path) {
+            return;
+        }
+        entries.push(entry.clone());
+        depths.push(depth);
+        if !self.expanded.contains(&entry.path) {
+            return;
+        }
+        if let Some(children) = self.tree_children.get(&entry.path) {
+            let mut children = children.clone();
+            sort_entries_by(&mut children, self.sort_order);
+            for child in &children {
+                self.push_tree_node(child, depth + 1, entries, depths);
+            }
+        }
+    }
+
+    /// 树形视图中指定行的缩进层级（普通视图恒为 0）
+    pub fn entry_depth(&self, index: usize) -> usize {
+        self.entry_depths.get(index).copied().unwrap_or(0)
+    }
+
+    /// 进入路径输入模式
+    pub fn start_input(&mut self) {
+        self.input_buffer.clear();
+        self.input_appends = false;
+        self.reset_tab_completions();
+        self.mode = Mode::InputPath;
+    }
+
+    /// 进入路径输入模式，确认后把该路径的扫描结果追加到当前根结果
+    pub fn start_append_input(&mut self) {
+        self.start_input();
+        self.input_appends = true;
+    }
+
+    /// 开始追加扫描：回到根视图（保留已有结果），之后到达的根条目按路径去重后追加
+    pub fn begin_append_scan(&mut self) {
+        self.appending_scan = true;
+        self.append_base = self.root_entries.len();
+        self.append_overlaps = 0;
+        self.navigation.reset_root();
+        self.restore_root_entries();
+    }
+
+    /// 输入字符
+    pub fn input_char(&mut self, c: char) {
+        self.input_buffer.push(c);
+        self.reset_tab_completions();
+    }
+
+    /// 删除输入字符
+    pub fn input_backspace(&mut self) {
+        self.input_buffer.pop();
+        self.reset_tab_completions();
+    }
+
+    /// 确认输入并返回路径
+    pub fn confirm_input(&mut self) -> Option<PathBuf> {
+        self.mode = Mode::Normal;
+        let path = self.input_buffer.trim();
+        if path.is_empty() {
+            return None;
+        }
+        let expanded = Self::expand_input_path(path);
+        Some(PathBuf::from(expanded))
+    }
+
+    /// 取消输入
+    pub fn cancel_input(&mut self) {
+        self.input_buffer.clear();
+        self.reset_tab_completions();
+        self.mode = Mode::Normal;
+    }
+
+    /// 展开输入路径中的 `~` / `~user` 与环境变量
+    fn expand_input_path(raw: &str) -> String {
+        expand_path(raw)
+    }
+
+    /// Tab 正向补全/循环
+    ///
+    /// 多个候选时循环切换；无候选或唯一候选已填入时按当前输入重新补全，
+    /// 因此输入以 `/` 结尾时再按 Tab 会列出并进入该目录的子目录。
+    pub fn input_tab_complete(&mut self) {
+        // 已有多个候选时，正向循环
+        if self.tab_completions.len() > 1 {
+            if let Some(index) = self.tab_completion_index {
+                let next_index = (index + 1) % self.tab_completions.len();
+                self.tab_completion_index = Some(next_index);
+                self.input_buffer = self.tab_completions[next_index].clone();
+            }
+            return;
+        }
+
+        self.reset_tab_completions();
+        self.build_tab_completions();
+    }
+
+    /// Shift+Tab 反向循环
+    pub fn input_tab_complete_prev(&mut self) {
+        if self.tab_completions.len() <= 1 {
+            self.reset_tab_completions();
+            self.build_tab_completions();
+            return;
+        }
+
+        if let Some(index) = self.tab_completion_index {
+            let prev_index = if index == 0 {
+                self.tab_completions.len() - 1
+            } else {
+                index - 1
+            };
+            self.tab_completion_index = Some(prev_index);
+            self.input_buffer = self.tab_completions[prev_index].clone();
+        }
+    }
+
+    /// 构建 Tab 补全候选列表
+    fn build_tab_completions(&mut self) {
+        let raw_input = self.input_buffer.trim();
+        if raw_input.is_empty() {
+            return;
+        }
+
+        let expanded_input = Self::expand_input_path(raw_input);
+        let Some((parent_dir, prefix)) = Self::parse_path_input(&expanded_input) else {
+            return;
+        };
+
+        let matching_directories = Self::read_matching_dirs(&parent_dir, &prefix);
+        if matching_directories.is_empty() {
+            return;
+        }
+
+        let home_directory = if raw_input == "~" || raw_input.starts_with("~/") {
+            crate::utils::home_dir().map(|home| home.display().to_string())
+        } else {
+            None
+        };
+
+        let mut completions: Vec<String> = matching_directories
+            .into_iter()
+            .map(|expanded_path| {
+                Self::build_completion_display_path(expanded_path, home_directory.as_deref())
+            })
+            .collect();
+        completions.sort();
+        self.tab_completion_hidden = completions.len().saturating_sub(MAX_TAB_COMPLETIONS);
+        completions.truncate(MAX_TAB_COMPLETIONS);
+        self.tab_completions = completions;
+        self.tab_completion_index = Some(0);
+        self.input_buffer = self.tab_completions[0].clone();
+    }
+
+    fn parse_path_input(expanded_input: &str) -> Option<(String, String)> {
+        let expanded_path = std::path::Path::new(expanded_input);
+        if expanded_input.ends_with('/') {
+            return Some((expanded_input.to_string(), String::new()));
+        }
+
+        let parent_directory = expanded_path.parent()?;
+        let file_name_prefix = expanded_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some((parent_directory.display().to_string(), file_name_prefix))
+    }
+
+    fn read_matching_dirs(parent_dir: &str, prefix: &str) -> Vec<String> {
+        let read_dir = match std::fs::read_dir(parent_dir) {
+            Ok(read_dir) => read_dir,
+            Err(_) => return Vec::new(),
+        };
+
+        let lowercase_prefix = prefix.to_lowercase();
+        let mut matched_directories = Vec::new();
+
+        for directory_entry in read_dir.flatten() {
+            let file_type = match directory_entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+            if !file_type.is_dir() {
+                continue;
+            }
+
+            let entry_name = directory_entry.file_name().to_string_lossy().to_string();
+            if !prefix.is_empty() && !entry_name.to_lowercase().starts_with(&lowercase_prefix) {
+                continue;
+            }
+
+            let expanded_path = format!(
+                "{}{}{}/",
+                parent_dir,
+                if parent_dir.ends_with('/') { "" } else { "/" },
+                entry_name
+            );
+            matched_directories.push(expanded_path);
+        }
+
+        matched_directories
+    }
+
+    fn build_completion_display_path(
+        expanded_path: String,
+        home_directory: Option<&str>,
+    ) -> String {
+        if let Some(home_path) = home_directory {
+            expanded_path.replacen(home_path, "~", 1)
+        } else {
+            expanded_path
+        }
+    }
+
+    /// 刷新扫描根所在卷的容量信息（须在 `complete_scan_target()` 之前调用）
+    ///
+    /// 指定路径扫描使用该路径，子目录列表使用当前目录，其余（预设、主目录扫描）使用主目录。
+    pub fn refresh_disk_usage(&mut self) {
+        let path = match (&self.pending_scan_target, &self.navigation.current_path) {
+            (Some(ScanTarget::Path(path)), _) | (None, Some(path)) => Some(path.clone()),
+            _ => crate::utils::home_dir(),
+        };
+        self.disk_usage = path.and_then(|path| disk_usage(&path));
+    }
+
+    /// 扫描成功完成时调用：将进行中的目标记为上次扫描目标并返回，供调用方持久化
+    pub fn complete_scan_target(&mut self) -> Option<ScanTarget> {
+        let target = self.pending_scan_target.take()?;
+        self.last_scan = Some(target.clone());
+        Some(target)
+    }
+
+    /// 清空 Tab 补全状态
+    pub fn reset_tab_completions(&mut self) {
+        self.tab_completions.clear();
+        self.tab_completion_index = None;
+        self.tab_completion_hidden = 0;
+    }
+
+    /// 打开当前高亮目录的预览，返回需要读取的目录路径（非目录时不做任何事）
+    ///
+    /// 预览不改变导航状态与选中状态。
+    pub fn start_peek(&mut self) -> Option<PathBuf> {
+        let entry = self.current_entry()?;
+        if entry.kind != EntryKind::Directory {
+            return None;
+        }
+        let path = entry.path.clone();
+        self.peek_path = Some(path.clone());
+        self.peek_result = None;
+        self.mode = Mode::Peek;
+        Some(path)
+    }
+
+    /// 应用后台读取的预览结果（已关闭或已切换到其他目录时忽略）
+    pub fn apply_peek_result(&mut self, result: Result<PeekResult, String>) {
+        let matches_current = match &result {
+            Ok(peek) => self.peek_path.as_ref() == Some(&peek.path),
+            Err(_) => self.peek_path.is_some(),
+        };
+        if self.mode == Mode::Peek && matches_current {
+            self.peek_result = Some(result);
+        }
+    }
+
+    /// 关闭预览
+    pub fn close_peek(&mut self) {
+        self.peek_path = None;
+        self.peek_result = None;
+        if self.mode == Mode::Peek {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// 对当前高亮目录应用「保留最新 N 个」，返回需要在后台列出的目录路径（非目录时不做任何事）
+    pub fn start_retention(&mut self) -> Option<PathBuf> {
+        let entry = self.current_entry()?;
+        if entry.kind != EntryKind::Directory {
+            return None;
+        }
+        let path = entry.path.clone();
+        self.notice = Some(format!("正在按修改时间整理 {}…", entry.name));
+        Some(path)
+    }
+
+    /// 应用后台计算的保留策略结果：选中最新 `RETENTION_KEEP` 个之外的子项
+    ///
+    /// 目录本身、包含它的上级目录及被保留子项（含其下内容）会取消选择，避免清理时连同最新的子项一起删除。
+    pub fn apply_retention(&mut self, dir: &Path, result: Result<Vec<CleanableEntry>, String>) {
+        let excess = match result {
+            Ok(excess) => excess,
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir.display().to_string());
+        let excess_paths: HashSet<&Path> =
+            excess.iter().map(|entry| entry.path.as_path()).collect();
+        let protected: Vec<PathBuf> = self
+            .selections
+            .keys()
+            .filter(|path| {
+                dir.starts_with(path)
+                    || (path.starts_with(dir)
+                        && !path
+                            .ancestors()
+                            .any(|ancestor| excess_paths.contains(ancestor)))
+            })
+            .cloned()
+            .collect();
+        for path in protected {
+            if let Some(previous) = self.selections.remove(&path)
+                && let Some(size) = previous.size
+            {
+                self.selected_size = self.selected_size.saturating_sub(size);
+            }
+        }
+
+        if excess.is_empty() {
+            self.notice = Some(format!("{} 中不超过 {} 项，无需清理", name, RETENTION_KEEP));
+            return;
+        }
+        let excess_size: u64 = excess.iter().filter_map(|entry| entry.size).sum();
+        self.select_all_entries(&excess);
+        self.notice = Some(format!(
+            "已选中 {} 中较旧的 {} 项（{}），保留最新 {} 个",
+            name,
+            excess.len(),
+            format_size(excess_size),
+            RETENTION_KEEP
+        ));
+    }
+
+    /// 切换右侧详情面板
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+    }
+
+    /// 调整详情面板宽度（`wider` 为 true 时加宽，否则收窄），限制在允许范围内
+    pub fn resize_details(&mut self, wider: bool) {
+        if !self.show_details {
+            return;
+        }
+        let (min, max) = DETAIL_PERCENT_RANGE;
+        self.detail_percent = if wider {
+            (self.detail_percent + DETAIL_PERCENT_STEP).min(max)
+        } else {
+            self.detail_percent
+                .saturating_sub(DETAIL_PERCENT_STEP)
+                .max(min)
+        };
+    }
+
+    /// 详情面板需要统计内容的目录：面板打开、高亮项为目录且尚未统计时返回路径
+    ///
+    /// 同一时间只有一个统计有效：光标移到另一个目录时返回新路径，由调用方取消进行中的统计。
+    pub fn take_detail_request(&mut self) -> Option<PathBuf> {
+        if !self.show_details {
+            return None;
+        }
+        let entry = self.current_entry()?;
+        if entry.kind != EntryKind::Directory
+            || self.detail_pending.as_ref() == Some(&entry.path)
+            || self
+                .detail_counts
+                .as_ref()
+                .is_some_and(|(path, _, _)| *path == entry.path)
+        {
+            return None;
+        }
+        let path = entry.path.clone();
+        self.detail_pending = Some(path.clone());
+        Some(path)
+    }
+
+    /// 写入后台统计的目录内容（已被新请求取代的结果忽略）
+    pub fn apply_detail_counts(&mut self, path: PathBuf, files: usize, dirs: usize) {
+        if self.detail_pending.as_ref() != Some(&path) {
+            return;
+        }
+        self.detail_pending = None;
+        self.detail_counts = Some((path, files, dirs));
+    }
+
+    /// 切换统计面板
+    pub fn toggle_stats(&mut self) {
+        if self.root_entries.is_empty() {
+            return;
+        }
+        self.mode = if self.mode == Mode::Stats {
+            Mode::Normal
+        } else {
+            Mode::Stats
+        };
+    }
+
+    /// 切换统计面板的数据范围（全部 / 当前视图）
+    pub fn toggle_stats_scope(&mut self) {
+        self.stats_scope = self.stats_scope.toggle();
+    }
+
+    /// 按 `stats_scope` 选择的数据源按分类聚合统计信息，按大小降序
+    ///
+    /// 当前视图中没有类别的条目（如子目录内容）归入其所在根扫描条目的类别。
+    pub fn get_category_stats(&self) -> Vec<CategoryStats> {
+        let entries = match self.stats_scope {
+            StatsScope::All => &self.root_entries,
+            StatsScope::CurrentView if self.tree_mode => &self.tree_base,
+            StatsScope::CurrentView => &self.entries,
+        };
+        aggregate_category_stats(entries, |entry| {
+            entry
+                .category
+                .as_ref()
+                .or_else(|| self.root_category_of(&entry.path))
+        })
+    }
+
+    /// 确认后实际清理的项目按类别分组的统计，按大小降序
+    ///
+    /// 规则同 `get_category_stats()`：没有类别的条目归入其所在根扫描条目的类别，否则归入“其他”。
+    pub fn confirm_category_stats(&self) -> Vec<CategoryStats> {
+        let items = self.confirm_cleanable_items();
+        aggregate_category_stats(&items, |entry| {
+            entry
+                .category
+                .as_ref()
+                .or_else(|| self.root_category_of(&entry.path))
+        })
+    }
+
+    /// 包含该路径的根扫描条目的类别
+    fn root_category_of(&self, path: &Path) -> Option<&ItemCategory> {
+        self.root_entries
+            .iter()
+            .find(|root| path.starts_with(&root.path))
+            .and_then(|root| root.category.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::DryRunItem;
+    use std::path::PathBuf;
+
+    fn entry(path: &str, size: Option<u64>) -> CleanableEntry {
+        CleanableEntry {
+            kind: EntryKind::File,
+            category: None,
+            path: PathBuf::from(path),
+            name: "item".to_string(),
+            size,
+            modified_at: None,
+            file_count: None,
+        }
+    }
+
+    fn named_entry(name: &str, kind: EntryKind, size: Option<u64>) -> CleanableEntry {
+        CleanableEntry {
+            kind,
+            category: None,
+            path: PathBuf::from(format!("/tmp/{name}")),
+            name: name.to_string(),
+            size,
+            modified_at: None,
+            file_count: None,
+        }
+    }
+
+    fn child_entry(parent: &str, name: &str, kind: EntryKind, size: Option<u64>) -> CleanableEntry {
+        CleanableEntry {
+            kind,
+            category: None,
+            path: PathBuf::from(format!("/tmp/{parent}/{name}")),
+            name: name.to_string(),
+            size,
+            modified_at: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn tree_mode_expands_children_inline() {
+        let mut app = App::new();
+        app.sort_order = SortOrder::ByName;
+        app.set_entries(vec![
+            named_entry("alpha", EntryKind::Directory, Some(30)),
+            named_entry("beta", EntryKind::File, Some(5)),
+        ]);
+        app.toggle_tree_mode();
+
+        // 首次展开需要扫描
+        let scan_path = app.toggle_expand();
+        assert_eq!(scan_path, Some(PathBuf::from("/tmp/alpha")));
+        app.apply_tree_child(child_entry("alpha", "z.log", EntryKind::File, Some(10)));
+        app.apply_tree_child(child_entry("alpha", "cache", EntryKind::Directory, None));
+        app.rebuild_tree();
+
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "cache", "z.log", "beta"]);
+        let depths: Vec<usize> = (0..app.entries.len()).map(|i| app.entry_depth(i)).collect();
+        assert_eq!(depths, vec![0, 1, 1, 0]);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        // 子目录大小回填不计入顶层总计
+        app.apply_tree_child_size(&PathBuf::from("/tmp/alpha/cache"), 20);
+        assert_eq!(app.entries[1].size, Some(20));
+        assert_eq!(app.total_size, 35);
+
+        // 子项可以被选中
+        app.list_state.select(Some(2));
+        app.toggle_selected();
+        assert!(app.is_selected(&PathBuf::from("/tmp/alpha/z.log")));
+
+        // 收起后再次展开复用已加载的子项
+        app.list_state.select(Some(0));
+        assert_eq!(app.toggle_expand(), None);
+        assert_eq!(app.entries.len(), 2);
+        assert_eq!(app.toggle_expand(), None);
+        assert_eq!(app.entries.len(), 4);
+    }
+
+    #[test]
+    fn toggle_tree_mode_off_restores_top_level_entries() {
+        let mut app = App::new();
+        app.set_entries(vec![
+            named_entry("alpha", EntryKind::Directory, Some(30)),
+            named_entry("beta", EntryKind::File, Some(5)),
+        ]);
+        app.toggle_tree_mode();
+        app.toggle_expand();
+        app.apply_tree_child(child_entry("alpha", "inner", EntryKind::File, Some(1)));
+        app.rebuild_tree();
+        assert_eq!(app.entries.len(), 3);
+
+        app.toggle_tree_mode();
+        assert!(!app.tree_mode);
+        assert_eq!(app.entries.len(), 2);
+        assert!(app.expanded.is_empty());
+        assert_eq!(app.entry_depth(1), 0);
+    }
+
+    #[test]
+    fn tree_child_for_unknown_parent_is_ignored() {
+        let mut app = App::new();
+        app.set_entries(vec![named_entry("alpha", EntryKind::Directory, Some(1))]);
+        app.toggle_tree_mode();
+        app.apply_tree_child(child_entry("other", "x", EntryKind::File, Some(1)));
+        app.rebuild_tree();
+        assert_eq!(app.entries.len(), 1);
+    }
+
+    #[test]
+    fn toggle_selected_updates_selected_size() {
+        let mut app = App::new();
+        app.entries = vec![entry("/tmp/a", Some(10)), entry("/tmp/b", Some(5))];
+        app.list_state.select(Some(0));
+
+        app.toggle_selected();
+        assert_eq!(app.selected_size, 10);
+
+        app.toggle_selected();
+        assert_eq!(app.selected_size, 0);
+    }
+
+    #[test]
+    fn click_list_moves_cursor_and_toggles_checkbox() {
+        let mut app = App::new();
+        app.entries = vec![
+            entry("/tmp/a", Some(10)),
+            entry("/tmp/b", Some(5)),
+            entry("/tmp/c", Some(1)),
+        ];
+        app.list_state.select(Some(0));
+        app.list_area = Rect::new(0, 3, 40, 10);
+        app.list_checkbox_column = 4;
+        app.visible_height = 8;
+
+        // 点击名称区域只移动光标
+        app.click_list(20, 5);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.selected_size, 0);
+
+        // 点击复选框切换选中
+        app.click_list(4, 6);
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert_eq!(app.selected_size, 1);
+
+        // 边框与空白行不响应
+        app.click_list(20, 3);
+        app.click_list(20, 9);
+        assert_eq!(app.list_state.selected(), Some(2));
+
+        // 列表右侧（如详情面板）的点击不移动光标
+        app.click_list(45, 4);
+        assert_eq!(app.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn toggle_help_resets_scroll_on_open() {
+        let mut app = App::new();
+        app.toggle_help();
+        app.help_scroll = 7;
+        app.toggle_help();
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.toggle_help();
+        assert_eq!(app.mode, Mode::Help);
+        assert_eq!(app.help_scroll, 0);
+    }
+
+    #[test]
+    fn toggle_all_selects_and_deselects() {
+        let mut app = App::new();
+        let mut logs = entry("/tmp/a", Some(3));
+        logs.category = Some(ItemCategory::Logs);
+        app.entries = vec![logs, entry("/tmp/b", Some(7))];
+
+        app.toggle_all();
+        assert_eq!(app.selections.len(), 2);
+        assert_eq!(app.selected_size, 10);
+        // 类别随选中条目保存，并由 get_selected_items() 还原
+        assert_eq!(
+            app.selections[Path::new("/tmp/a")].category,
+            Some(ItemCategory::Logs)
+        );
+        let mut categories: Vec<(PathBuf, Option<ItemCategory>)> = app
+            .get_selected_items()
+            .into_iter()
+            .map(|item| (item.path, item.category))
+            .collect();
+        categories.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            categories,
+            vec![
+                (PathBuf::from("/tmp/a"), Some(ItemCategory::Logs)),
+                (PathBuf::from("/tmp/b"), None),
+            ]
+        );
+
+        app.toggle_all();
+        assert!(app.selections.is_empty());
+        assert_eq!(app.selected_size, 0);
+    }
+
+    #[test]
+    fn invert_selection_flips_current_view_only() {
+        let mut app = App::new();
+        app.entries = vec![
+            entry("/tmp/a", Some(3)),
+            entry("/tmp/b", Some(7)),
+            entry("/tmp/c", Some(11)),
+        ];
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        app.selections.insert(
+            PathBuf::from("/tmp/other/x"),
+            SelectedEntry {
+                kind: EntryKind::File,
+                size: Some(100),
+                category: None,
+            },
+        );
+        app.selected_size += 100;
+
+        app.invert_selection();
+        assert!(!app.is_selected(&PathBuf::from("/tmp/a")));
+        assert!(app.is_selected(&PathBuf::from("/tmp/b")));
+        assert!(app.is_selected(&PathBuf::from("/tmp/c")));
+        assert!(app.is_selected(&PathBuf::from("/tmp/other/x")));
+        assert_eq!(app.selected_size, 118);
+
+        app.invert_selection();
+        assert_eq!(app.selections.len(), 2);
+        assert_eq!(app.selected_size, 103);
+    }
+
+    #[test]
+    fn select_range_selects_inclusive_range_between_anchor_and_cursor() {
+        let mut app = App::new();
+        app.entries = vec![
+            entry("/tmp/a", Some(1)),
+            entry("/tmp/b", Some(2)),
+            entry("/tmp/c", Some(4)),
+            entry("/tmp/d", Some(8)),
+        ];
+        app.list_state.select(Some(2));
+        app.toggle_select_anchor();
+        assert_eq!(app.select_anchor, Some(2));
+
+        app.previous();
+        app.previous();
+        app.select_range();
+
+        assert!(app.select_anchor.is_none());
+        assert_eq!(app.selections.len(), 3);
+        assert_eq!(app.selected_size, 7);
+        assert!(!app.is_selected(&PathBuf::from("/tmp/d")));
+
+        // 对同一范围再次操作时取消选择
+        app.list_state.select(Some(0));
+        app.toggle_select_anchor();
+        app.list_state.select(Some(2));
+        app.select_range();
+        assert!(app.selections.is_empty());
+        assert_eq!(app.selected_size, 0);
+    }
+
+    #[test]
+    fn clear_select_anchor_keeps_selections() {
+        let mut app = App::new();
+        app.entries = vec![entry("/tmp/a", Some(1)), entry("/tmp/b", Some(2))];
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        app.toggle_select_anchor();
+
+        app.clear_select_anchor();
+        assert!(app.select_anchor.is_none());
+        assert_eq!(app.selected_size, 1);
+    }
+
+    #[test]
+    fn reapplying_entries_preserves_selection_and_size() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-reselect-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let kept = dir.path().join("kept");
+        let removed = dir.path().join("removed");
+        std::fs::write(&kept, b"hello").expect("write kept");
+        std::fs::write(&removed, b"bye").expect("write removed");
+
+        let mut app = App::new();
+        app.navigation
+            .enter(dir.path().to_path_buf(), Vec::new(), None);
+        for path in [&kept, &removed] {
+            app.apply_dir_entry(entry(path.to_str().unwrap(), Some(5)));
+        }
+        app.toggle_all();
+        assert_eq!(app.selected_size, 10);
+
+        // 模拟重新扫描：removed 已被删除，kept 的大小发生变化
+        std::fs::remove_file(&removed).expect("remove file");
+        app.clear_entries();
+        app.apply_dir_entry(entry(kept.to_str().unwrap(), Some(7)));
+        app.prune_stale_selections();
+
+        assert!(app.is_selected(&kept));
+        assert!(!app.is_selected(&removed));
+        assert_eq!(app.selected_size, 7);
+    }
+
+    #[test]
+    fn regex_search_filters_by_pattern() {
+        let mut app = App::new();
+        app.entries = vec![
+            named_entry("app.log", EntryKind::File, Some(1)),
+            named_entry("cache", EntryKind::Directory, Some(2)),
+            named_entry("logs", EntryKind::Directory, Some(3)),
+        ];
+        app.start_search();
+        app.toggle_search_regex();
+        for c in r"\.log$".chars() {
+            app.search_char(c);
+        }
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["app.log"]);
+        assert!(app.search_error.is_none());
+    }
+
+    #[test]
+    fn regex_search_keeps_list_on_invalid_pattern() {
+        let mut app = App::new();
+        app.entries = vec![
+            named_entry("cache", EntryKind::Directory, Some(2)),
+            named_entry("tmp", EntryKind::Directory, Some(3)),
+        ];
+        app.start_search();
+        app.toggle_search_regex();
+        app.search_char('(');
+        assert!(app.search_error.is_some());
+        assert_eq!(app.entries.len(), 2);
+
+        app.search_backspace();
+        for c in "cache|tmp".chars() {
+            app.search_char(c);
+        }
+        assert!(app.search_error.is_none());
+        assert_eq!(app.entries.len(), 2);
+    }
+
+    #[test]
+    fn confirm_search_records_active_filter_until_reset() {
+        let mut app = App::new();
+        app.entries = vec![
+            named_entry("cache", EntryKind::Directory, Some(2)),
+            named_entry("caches", EntryKind::Directory, Some(3)),
+            named_entry("tmp", EntryKind::Directory, Some(4)),
+        ];
+        app.start_search();
+        for c in "cache".chars() {
+            app.search_char(c);
+        }
+        app.confirm_search();
+        assert_eq!(
+            app.active_filter,
+            Some(ActiveFilter {
+                query: "cache".to_string(),
+                total: 3,
+            })
+        );
+        assert_eq!(app.entries.len(), 2);
+
+        app.clear_entries();
+        assert!(app.active_filter.is_none());
+    }
+
+    #[test]
+    fn confirm_empty_search_has_no_active_filter() {
+        let mut app = App::new();
+        app.entries = vec![named_entry("tmp", EntryKind::Directory, Some(4))];
+        app.start_search();
+        app.confirm_search();
+        assert!(app.active_filter.is_none());
+
+        app.start_search();
+        app.search_char('t');
+        app.cancel_search();
+        assert!(app.active_filter.is_none());
+    }
+
+    #[test]
+    fn single_confirm_targets_current_entry_only() {
+        let mut app = App::new();
+        app.entries = vec![entry("/tmp/a", Some(3)), entry("/tmp/b", Some(7))];
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        app.next();
+
+        app.enter_single_confirm_mode();
+        assert_eq!(app.mode, Mode::Confirm);
+        let items = app.confirm_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, PathBuf::from("/tmp/b"));
+        assert_eq!(app.confirm_size(), 7);
+
+        app.cancel_confirm();
+        assert!(app.confirm_target.is_none());
+        assert_eq!(app.confirm_items().len(), 1);
+        assert_eq!(app.confirm_items()[0].path, PathBuf::from("/tmp/a"));
+    }
+
+    #[test]
+    fn start_peek_only_opens_for_directories_and_keeps_navigation() {
+        let mut app = App::new();
+        app.entries = vec![
+            named_entry("file", EntryKind::File, Some(1)),
+            named_entry("dir", EntryKind::Directory, Some(2)),
+        ];
+        app.list_state.select(Some(0));
+        assert!(app.start_peek().is_none());
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.next();
+        let path = app.start_peek().expect("peek directory");
+        assert_eq!(path, PathBuf::from("/tmp/dir"));
+        assert_eq!(app.mode, Mode::Peek);
+        assert!(app.navigation.current_path.is_none());
+
+        app.apply_peek_result(Ok(PeekResult {
+            path: PathBuf::from("/tmp/dir"),
+            entries: Vec::new(),
+            total: 0,
+        }));
+        assert!(matches!(app.peek_result, Some(Ok(_))));
+
+        app.close_peek();
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn apply_entry_size_updates_selected_size() {
+        let mut app = App::new();
+        let entry = entry("/tmp/a", None);
+        app.entries = vec![entry.clone()];
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+
+        app.apply_entry_size(&PathBuf::from("/tmp/a"), 12);
+        assert_eq!(app.selected_size, 12);
+    }
+
+    #[test]
+    fn sort_root_entries_respects_sort_order_by_size() {
+        let mut app = App::new();
+        app.root_entries = vec![
+            named_entry("small", EntryKind::File, Some(10)),
+            named_entry("big", EntryKind::File, Some(100)),
+            named_entry("mid", EntryKind::File, Some(50)),
+        ];
+        app.sort_order = SortOrder::BySize;
+        app.sort_root_entries();
+
+        let names: Vec<&str> = app.root_entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["big", "mid", "small"]);
+    }
+
+    #[test]
+    fn sort_root_entries_respects_sort_order_by_name() {
+        let mut app = App::new();
+        app.root_entries = vec![
+            named_entry("c_file", EntryKind::File, Some(10)),
+            named_entry("a_dir", EntryKind::Directory, Some(100)),
+            named_entry("b_file", EntryKind::File, Some(50)),
+        ];
+        app.sort_order = SortOrder::ByName;
+        app.sort_root_entries();
+
+        let names: Vec<&str> = app.root_entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a_dir", "b_file", "c_file"]);
+    }
+
+    #[test]
+    fn sort_root_entries_respects_sort_order_by_time() {
+        let mut app = App::new();
+        let base = SystemTime::UNIX_EPOCH;
+        let mut old = named_entry("old", EntryKind::File, Some(10));
+        old.modified_at = Some(base + std::time::Duration::from_secs(10));
+        let mut new = named_entry("new", EntryKind::File, Some(10));
+        new.modified_at = Some(base + std::time::Duration::from_secs(100));
+        let unknown = named_entry("unknown", EntryKind::File, Some(10));
+        app.root_entries = vec![old, unknown, new];
+        app.sort_order = SortOrder::ByTime;
+        app.sort_root_entries();
+
+        let names: Vec<&str> = app.root_entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["new", "old", "unknown"]);
+    }
+
+    #[test]
+    fn with_config_applies_default_sort() {
+        let mut config = AppConfig::default();
+        config.ui.default_sort = Some("size".to_string());
+        let app = App::with_config(&config);
+        assert_eq!(app.sort_order, SortOrder::BySize);
+    }
+
+    #[test]
+    fn with_config_falls_back_to_default_sort_for_invalid_value() {
+        let mut config = AppConfig::default();
+        config.ui.default_sort = Some("bogus".to_string());
+        let app = App::with_config(&config);
+        assert_eq!(app.sort_order, SortOrder::default());
+    }
+
+    #[test]
+    fn toggle_sort_order_at_root_applies_to_root_entries() {
+        let mut app = App::new();
+        app.root_entries = vec![
+            named_entry("z_small", EntryKind::File, Some(1)),
+            named_entry("a_big", EntryKind::File, Some(100)),
+        ];
+        // 初始在根目录（navigation.current_path 为 None）
+        assert!(app.navigation.current_path.is_none());
+        app.sort_order = SortOrder::ByName;
+        app.sort_root_entries();
+
+        // 切换到 BySize
+        app.toggle_sort_order();
+        assert_eq!(app.sort_order, SortOrder::BySize);
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a_big", "z_small"]);
+    }
+
+    #[test]
+    fn toggle_sort_order_in_subdir_applies_to_dir_entries() {
+        let mut app = App::new();
+        app.navigation
+            .enter(PathBuf::from("/tmp/subdir"), Vec::new(), None);
+        app.entries = vec![
+            named_entry("z_item", EntryKind::File, Some(1)),
+            named_entry("a_item", EntryKind::File, Some(100)),
+        ];
+        app.sort_order = SortOrder::BySize;
+
+        // BySize -> ByTime
+        app.toggle_sort_order();
+        assert_eq!(app.sort_order, SortOrder::ByTime);
+        assert_eq!(app.sort_order.as_str(), "时间");
+
+        // ByTime -> ByName
+        app.toggle_sort_order();
+        assert_eq!(app.sort_order, SortOrder::ByName);
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a_item", "z_item"]);
+    }
+
+    #[test]
+    fn restore_root_entries_applies_current_sort_order() {
+        let mut app = App::new();
+        app.root_entries = vec![
+            named_entry("z_item", EntryKind::File, Some(1)),
+            named_entry("a_item", EntryKind::File, Some(100)),
+        ];
+        app.sort_order = SortOrder::ByName;
+
+        app.restore_root_entries();
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a_item", "z_item"]);
+    }
+
+    #[test]
+    fn restore_cached_dir_entries_applies_current_sort_order_and_preserves_selection() {
+        let mut app = App::new();
+        app.sort_order = SortOrder::BySize;
+        app.navigation
+            .enter(PathBuf::from("/tmp/parent"), Vec::new(), None);
+
+        let cached_entries = vec![
+            named_entry("z_small", EntryKind::File, Some(1)),
+            named_entry("a_big", EntryKind::File, Some(100)),
+        ];
+        // 之前在缓存顺序中选中 z_small（索引 0），切换到 BySize 后应仍选中该条目
+        app.restore_cached_dir_entries(cached_entries, Some(0));
+
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a_big", "z_small"]);
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn back_returns_cached_entries_and_selected_index() {
+        let mut nav = NavigationState::new();
+        let root_entries = vec![
+            named_entry("dir_a", EntryKind::Directory, Some(100)),
+            named_entry("dir_b", EntryKind::Directory, Some(50)),
+        ];
+
+        // 进入 dir_a，缓存根层条目和选中位置
+        nav.enter(PathBuf::from("/tmp/dir_a"), root_entries.clone(), Some(0));
+        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir_a")));
+
+        // 回退：应恢复缓存的条目和选中位置
+        let result = nav.back();
+        assert!(result.is_none()); // 回到根目录，栈为空
+        assert!(nav.current_path.is_none());
+    }
+
+    #[test]
+    fn back_from_nested_restores_parent_cache() {
+        let mut nav = NavigationState::new();
+        let level1_entries = vec![
+            named_entry("child_a", EntryKind::Directory, Some(30)),
+            named_entry("child_b", EntryKind::File, Some(20)),
+        ];
+
+        // 进入第一层（从根进入，缓存空的根条目）
+        nav.enter(PathBuf::from("/tmp/dir"), Vec::new(), Some(0));
+        // 进入第二层，缓存第一层条目
+        nav.enter(
+            PathBuf::from("/tmp/dir/sub"),
+            level1_entries.clone(),
+            Some(1),
+        );
+        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir/sub")));
+
+        // 从第二层回退，应恢复进入第二层时缓存的条目（level1_entries）
+        let result = nav.back();
+        assert!(result.is_some());
+        let (cached, idx) = result.unwrap();
+        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir")));
+        assert_eq!(cached.len(), 2); // 进入第二层时缓存的 level1_entries
+        assert_eq!(idx, Some(1));
+
+        // 再回退到根目录
+        let result = nav.back();
+        assert!(result.is_none());
+        assert!(nav.current_path.is_none());
+    }
+
+    #[test]
+    fn back_restores_entries_in_app() {
+        let mut app = App::new();
+        let root_entries = vec![named_entry("dir_parent", EntryKind::Directory, Some(200))];
+        app.set_entries(root_entries.clone());
+
+        // 进入第一层子目录，缓存根条目
+        let parent_entries = vec![
+            named_entry("file_a", EntryKind::File, Some(100)),
+            named_entry("file_b", EntryKind::File, Some(50)),
+        ];
+        app.navigation
+            .enter(PathBuf::from("/tmp/parent"), app.entries.clone(), Some(0));
+        app.set_entries(parent_entries.clone());
+
+        // 进入第二层子目录，缓存第一层条目
+        app.navigation.enter(
+            PathBuf::from("/tmp/parent/child"),
+            app.entries.clone(),
+            Some(1),
+        );
+        app.set_entries(vec![named_entry("sub_file", EntryKind::File, Some(10))]);
+        assert_eq!(app.entries.len(), 1);
+
+        // 从第二层回退到第一层：恢复缓存
+        if let Some((cached_entries, selected_index)) = app.navigation.back() {
+            app.set_entries(cached_entries);
+            app.list_state.select(selected_index);
+        }
+        assert_eq!(app.entries.len(), 2);
+        assert_eq!(app.list_state.selected(), Some(1));
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["file_a", "file_b"]);
+    }
+
+    #[test]
+    fn reset_root_clears_navigation_stack() {
+        let mut nav = NavigationState::new();
+        nav.enter(PathBuf::from("/tmp/a"), Vec::new(), None);
+        nav.enter(PathBuf::from("/tmp/a/b"), Vec::new(), None);
+        assert!(nav.current_path.is_some());
+
+        nav.reset_root();
+        assert!(nav.current_path.is_none());
+        assert!(nav.back().is_none());
+    }
+
+    #[test]
+    fn clean_progress_tracks_completion_and_resets_mode() {
+        let mut app = App::new();
+        app.start_cleaning(4, 0);
+        assert_eq!(app.mode, Mode::Cleaning);
+        assert_eq!(app.clean_percent(), 0);
+
+        app.update_clean_progress(1, 4, Path::new("/tmp/a"), 0);
+        assert_eq!(app.clean_percent(), 25);
+        assert_eq!(app.current_clean_path, "/tmp/a");
+
+        app.start_cleaning(2, 400);
+        app.update_clean_progress(1, 2, Path::new("/tmp/big"), 300);
+        assert_eq!(app.clean_percent(), 75);
+        app.update_clean_progress(2, 2, Path::new("/tmp/failed"), 100);
+        assert_eq!(app.clean_percent(), 100);
+
+        app.clean_cancel_requested = true;
+        app.finish_cleaning();
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(!app.clean_cancel_requested);
+
+        // 清空回收站沿用清理进度，总大小取自后台统计
+        app.trash_usage = Some(800);
+        app.start_emptying_trash();
+        assert_eq!(app.mode, Mode::Cleaning);
+        assert!(app.emptying_trash);
+        app.update_clean_progress(1, 3, Path::new("/tmp/.Trash/a"), 200);
+        assert_eq!(app.clean_percent(), 25);
+        app.finish_cleaning();
+        assert!(!app.emptying_trash);
+    }
+
+    #[test]
+    fn set_clean_errors_groups_by_kind_in_first_seen_order() {
+        let mut app = App::new();
+        app.set_clean_errors(vec![
+            CleanError::PermissionDenied {
+                path: PathBuf::from("/tmp/a"),
+            },
+            CleanError::NotFound {
+                path: PathBuf::from("/tmp/b"),
+            },
+            CleanError::PermissionDenied {
+                path: PathBuf::from("/tmp/c"),
+            },
+        ]);
+
+        assert!(app.error_message.is_some());
+        let groups = app.grouped_clean_errors();
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|(label, errors)| (*label, errors.len()))
+            .collect();
+        assert_eq!(summary, vec![("权限不足", 2), ("路径不存在", 1)]);
+
+        app.clear_error();
+        assert!(app.error_message.is_none());
+        assert!(app.clean_errors.is_empty());
+    }
+
+    #[test]
+    fn tab_completion_descends_into_unique_directory() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-complete-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let base = dir.path().display().to_string();
+        std::fs::create_dir_all(dir.path().join("Library/Caches")).expect("create dirs");
+        std::fs::create_dir_all(dir.path().join("Library/Logs")).expect("create dirs");
+        std::fs::write(dir.path().join("Library/notes.txt"), b"x").expect("write file");
+
+        let mut app = App::new();
+        app.input_buffer = format!("{base}/Lib");
+        app.input_tab_complete();
+        assert_eq!(app.input_buffer, format!("{base}/Library/"));
+
+        // 唯一候选已填入后再按 Tab，列出其子目录（不含文件）
+        app.input_tab_complete();
+        assert_eq!(
+            app.tab_completions,
+            vec![
+                format!("{base}/Library/Caches/"),
+                format!("{base}/Library/Logs/")
+            ]
+        );
+        assert_eq!(app.input_buffer, format!("{base}/Library/Caches/"));
+
+        app.input_tab_complete();
+        assert_eq!(app.input_buffer, format!("{base}/Library/Logs/"));
+        app.input_tab_complete_prev();
+        assert_eq!(app.input_buffer, format!("{base}/Library/Caches/"));
+    }
+
+    #[test]
+    fn tab_completion_caps_candidate_list() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-complete-cap-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        for index in 0..MAX_TAB_COMPLETIONS + 3 {
+            std::fs::create_dir(dir.path().join(format!("d{index:03}"))).expect("create dir");
+        }
+
+        let mut app = App::new();
+        app.input_buffer = format!("{}/", dir.path().display());
+        app.input_tab_complete();
+        assert_eq!(app.tab_completions.len(), MAX_TAB_COMPLETIONS);
+        assert_eq!(app.tab_completion_hidden, 3);
+
+        app.reset_tab_completions();
+        assert_eq!(app.tab_completion_hidden, 0);
+    }
+
+    #[test]
+    fn complete_scan_target_records_last_scan_once() {
+        let mut app = App::new();
+        assert_eq!(app.complete_scan_target(), None);
+
+        app.pending_scan_target = Some(ScanTarget::Home);
+        assert_eq!(app.complete_scan_target(), Some(ScanTarget::Home));
+        assert_eq!(app.last_scan, Some(ScanTarget::Home));
+        assert!(app.pending_scan_target.is_none());
+        assert_eq!(app.complete_scan_target(), None);
+        assert_eq!(app.last_scan, Some(ScanTarget::Home));
+    }
+
+    #[test]
+    fn bookmarks_skip_missing_paths() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let first = dir.path().join("first");
+        let missing = dir.path().join("missing");
+        let last = dir.path().join("last");
+        std::fs::create_dir(&first).expect("create first");
+        std::fs::create_dir(&last).expect("create last");
+
+        let mut app = App::new();
+        app.set_bookmarks(vec![missing.clone(), first.clone(), missing, last.clone()]);
+        app.open_bookmarks();
+        assert_eq!(app.mode, Mode::Bookmarks);
+        assert_eq!(app.bookmark_index, 1);
+
+        app.bookmark_next();
+        assert_eq!(app.bookmark_index, 3);
+        app.bookmark_next();
+        assert_eq!(app.bookmark_index, 3);
+        app.bookmark_previous();
+        assert_eq!(app.bookmark_index, 1);
+        app.bookmark_previous();
+        assert_eq!(app.bookmark_index, 1);
+
+        assert_eq!(app.select_bookmark(), Some(first));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn bookmarks_without_available_paths_cannot_be_selected() {
+        let mut app = App::new();
+        app.open_bookmarks();
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.notice.is_some());
+
+        app.set_bookmarks(vec![PathBuf::from("/nonexistent/vac-bookmark")]);
+        app.open_bookmarks();
+        assert_eq!(app.mode, Mode::Bookmarks);
+        assert_eq!(app.select_bookmark(), None);
+        assert_eq!(app.mode, Mode::Bookmarks);
+    }
+
+    #[test]
+    fn next_and_previous_wrap_only_when_enabled() {
+        let mut app = App::new();
+        app.entries = vec![entry("/tmp/a", Some(1)), entry("/tmp/b", Some(2))];
+        assert!(app.wrap_navigation);
+
+        app.list_state.select(Some(1));
+        app.next();
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.previous();
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        app.wrap_navigation = false;
+        app.next();
+        assert_eq!(app.list_state.selected(), Some(1));
+        app.list_state.select(Some(0));
+        app.previous();
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.next();
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn jump_between_selected_entries_wraps_around() {
+        let mut app = App::new();
+        app.entries = (0..6)
+            .map(|index| entry(&format!("/tmp/item{index}"), Some(1)))
+            .collect();
+        for index in [1, 4] {
+            app.list_state.select(Some(index));
+            app.toggle_selected();
+        }
+        app.list_state.select(Some(0));
+
+        app.next_selected();
+        assert_eq!(app.list_state.selected(), Some(1));
+        app.next_selected();
+        assert_eq!(app.list_state.selected(), Some(4));
+        app.next_selected();
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        app.prev_selected();
+        assert_eq!(app.list_state.selected(), Some(4));
+        app.list_state.select(Some(3));
+        app.prev_selected();
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn jump_between_selected_entries_is_noop_without_selection() {
+        let mut app = App::new();
+        app.entries = vec![entry("/tmp/a", Some(1)), entry("/tmp/b", Some(1))];
+        app.list_state.select(Some(1));
+
+        app.next_selected();
+        assert_eq!(app.list_state.selected(), Some(1));
+        app.prev_selected();
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn confirm_scroll_pages_and_clamps_to_visible_height() {
+        let mut app = App::new();
+        app.entries = (0..25)
+            .map(|index| entry(&format!("/tmp/item{index}"), Some(1)))
+            .collect();
+        app.toggle_all();
+        app.enter_confirm_mode();
+        app.confirm_visible_height = 10;
+
+        app.scroll_confirm_down(app.confirm_visible_height);
+        assert_eq!(app.confirm_scroll, 10);
+        app.scroll_confirm_down(app.confirm_visible_height);
+        assert_eq!(app.confirm_scroll, 15);
+        app.scroll_confirm_down(1);
+        assert_eq!(app.confirm_scroll, 15);
+
+        app.scroll_confirm_up(app.confirm_visible_height);
+        assert_eq!(app.confirm_scroll, 5);
+        app.scroll_confirm_top();
+        assert_eq!(app.confirm_scroll, 0);
+        app.scroll_confirm_bottom();
+        assert_eq!(app.confirm_scroll, 15);
+    }
+
+    #[test]
+    fn confirm_scroll_uses_dry_run_item_count() {
+        let mut app = App::new();
+        app.entries = (0..25)
+            .map(|index| entry(&format!("/tmp/item{index}"), Some(1)))
+            .collect();
+        app.toggle_all();
+        app.enter_confirm_mode();
+        app.confirm_visible_height = 10;
+        app.dry_run_result = Some(DryRunResult {
+            total_files: 12,
+            total_dirs: 0,
+            total_size: 12,
+            ..DryRunResult::default()
+        });
+        app.dry_run_active = true;
+
+        app.scroll_confirm_bottom();
+        assert_eq!(app.confirm_scroll, 0);
+        app.scroll_confirm_down(3);
+        assert_eq!(app.confirm_scroll, 0);
+    }
+
+    #[test]
+    fn confirm_mode_flags_unsafe_items_and_skips_them() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-blocked-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let safe = dir.path().join("safe.log");
+        std::fs::write(&safe, b"data").expect("write file");
+        let missing = dir.path().join("missing.log");
+
+        let mut app = App::new();
+        app.entries = vec![
+            entry(safe.to_str().unwrap(), Some(4)),
+            entry(missing.to_str().unwrap(), Some(6)),
+        ];
+        app.toggle_all();
+        app.enter_confirm_mode();
+
+        assert_eq!(app.confirm_blocked, HashSet::from([missing.clone()]));
+        assert_eq!(app.confirm_blocked_size(), 6);
+        let cleanable = app.confirm_cleanable_items();
+        assert_eq!(cleanable.len(), 1);
+        assert_eq!(cleanable[0].path, safe);
+
+        app.cancel_confirm();
+        assert!(app.confirm_blocked.is_empty());
+    }
+
+    #[test]
+    fn category_stats_sum_file_counts_and_mark_unknown() {
+        let mut app = App::new();
+        let mut logs = entry("/tmp/logs-a", Some(10));
+        logs.category = Some(ItemCategory::Logs);
+        logs.file_count = Some(3);
+        let mut more_logs = entry("/tmp/logs-b", Some(20));
+        more_logs.category = Some(ItemCategory::Logs);
+        more_logs.file_count = Some(4);
+        let mut cache = entry("/tmp/cache", Some(5));
+        cache.category = Some(ItemCategory::AppCache);
+        let mut cache_known = entry("/tmp/cache-known", Some(1));
+        cache_known.category = Some(ItemCategory::AppCache);
+        cache_known.file_count = Some(2);
+        app.root_entries = vec![logs, more_logs, cache, cache_known];
+
+        let stats = app.get_category_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].name, ItemCategory::Logs.as_str());
+        assert_eq!(stats[0].size, 30);
+        assert_eq!(stats[0].file_count, Some(7));
+        assert_eq!(stats[1].size, 6);
+        assert_eq!(stats[1].file_count, None);
+    }
+
+    #[test]
+    fn scan_eta_waits_for_progress_and_ignores_backward_jumps() {
+        let mut app = App::new();
+        let start = Instant::now();
+        app.start_scan_clock(start);
+        assert_eq!(app.scan_eta_remaining(start), None);
+
+        app.record_scan_progress(0, start + Duration::from_secs(1));
+        assert_eq!(app.scan_eta_remaining(start + Duration::from_secs(1)), None);
+
+        // 10 秒完成 50%：剩余约 10 秒
+        let at_half = start + Duration::from_secs(10);
+        app.record_scan_progress(50, at_half);
+        assert_eq!(
+            app.scan_eta_remaining(at_half),
+            Some(Duration::from_secs(10))
+        );
+
+        // 回退的进度不影响估算
+        app.record_scan_progress(20, at_half);
+        assert_eq!(app.scan_progress, 20);
+        assert_eq!(
+            app.scan_eta_remaining(at_half),
+            Some(Duration::from_secs(10))
+        );
+
+        // 估算耗尽仍无新进度时视为停滞
+        assert_eq!(
+            app.scan_eta_remaining(at_half + Duration::from_secs(11)),
+            None
+        );
+    }
+
+    #[test]
+    fn scan_eta_smooths_new_samples() {
+        let mut app = App::new();
+        let start = Instant::now();
+        app.start_scan_clock(start);
+        app.record_scan_progress(50, start + Duration::from_secs(10));
+
+        // 新样本: 20 秒完成 80% => 剩余 5 秒；上次估算此时剩余 0 秒
+        let later = start + Duration::from_secs(20);
+        app.record_scan_progress(80, later);
+        let remaining = app.scan_eta_remaining(later).expect("eta");
+        assert_eq!(remaining.as_millis(), 1_500);
+    }
+
+    #[test]
+    fn category_stats_separate_named_custom_targets() {
+        let mut app = App::new();
+        let mut projects = entry("/tmp/dev", Some(30));
+        projects.category = Some(ItemCategory::Custom(Some("My Projects".to_string())));
+        let mut builds = entry("/tmp/builds", Some(20));
+        builds.category = Some(ItemCategory::Custom(Some("Builds".to_string())));
+        let mut unnamed = entry("/tmp/extra", Some(10));
+        unnamed.category = Some(ItemCategory::Custom(None));
+        app.root_entries = vec![projects, builds, unnamed];
+
+        let names: Vec<String> = app
+            .get_category_stats()
+            .into_iter()
+            .map(|category| category.name)
+            .collect();
+        assert_eq!(names, vec!["My Projects", "Builds", "自定义目标"]);
+
+        let filter = ItemCategory::from_slug("custom").expect("custom slug");
+        assert!(ItemCategory::Custom(Some("Builds".to_string())).matches_filter(&filter));
+        assert!(!ItemCategory::Logs.matches_filter(&filter));
+    }
+
+    #[test]
+    fn confirm_clean_action_follows_trash_categories() {
+        let mut app = App::new();
+        let mut downloads = entry("/tmp/dl", Some(10));
+        downloads.category = Some(ItemCategory::Downloads);
+        let mut cache = entry("/tmp/cache", Some(20));
+        cache.category = Some(ItemCategory::AppCache);
+        app.entries = vec![downloads, cache];
+        app.toggle_all();
+        assert_eq!(app.confirm_clean_action(), CleanAction::Delete);
+
+        // 选中条目保留类别，供清理时按类别分组
+        assert!(
+            app.get_selected_items()
+                .iter()
+                .any(|item| item.category == Some(ItemCategory::Downloads))
+        );
+
+        app.trash_categories = vec![ItemCategory::Downloads];
+        assert_eq!(
+            app.confirm_clean_action(),
+            CleanAction::Mixed { trashed: 1 }
+        );
+
+        app.trash_categories = vec![ItemCategory::Downloads, ItemCategory::AppCache];
+        assert_eq!(app.confirm_clean_action(), CleanAction::Trash);
+
+        app.use_secure_delete = true;
+        assert_eq!(app.confirm_clean_action(), CleanAction::SecureDelete);
+    }
+
+    #[test]
+    fn auto_select_stale_entries_respects_threshold_and_safety() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-stale-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let now = SystemTime::now();
+        let old_time = now - Duration::from_secs(40 * SECONDS_PER_DAY as u64);
+        let recent_time = now - Duration::from_secs(SECONDS_PER_DAY as u64);
+
+        std::fs::write(dir.path().join("old"), b"old").expect("write file");
+        let mut old = entry(&dir.path().join("old").to_string_lossy(), Some(100));
+        old.modified_at = Some(old_time);
+        let mut recent = entry(&dir.path().join("recent").to_string_lossy(), Some(10));
+        recent.modified_at = Some(recent_time);
+        let unknown = entry(&dir.path().join("unknown").to_string_lossy(), Some(1));
+        let mut forbidden = entry("/usr", Some(1_000));
+        forbidden.modified_at = Some(old_time);
+
+        let mut app = App::new();
+        app.entries = vec![old.clone(), recent, unknown, forbidden];
+        app.pending_scan_target = Some(ScanTarget::Home);
+        assert_eq!(app.auto_select_stale_entries(now), 0);
+
+        // 天数过大时不溢出，也不选中任何条目
+        app.auto_select_older_than_days = Some(u64::MAX);
+        assert_eq!(app.auto_select_stale_entries(now), 0);
+
+        // 子目录列出与清理后的重新扫描没有扫描目标，不自动选中
+        app.auto_select_older_than_days = Some(30);
+        app.pending_scan_target = None;
+        assert_eq!(app.auto_select_stale_entries(now), 0);
+
+        app.pending_scan_target = Some(ScanTarget::Home);
+        assert_eq!(app.auto_select_stale_entries(now), 1);
+        assert!(app.selections.contains_key(&old.path));
+        assert_eq!(app.selected_size, 100);
+
+        // 已选中的条目不会重复计数
+        assert_eq!(app.auto_select_stale_entries(now), 0);
+        assert_eq!(app.selected_size, 100);
+    }
+
+    #[test]
+    fn confirm_dry_run_first_streams_preview_and_drops_stale_results() {
+        let item = |name: &str| DryRunItem {
+            path: PathBuf::from(name),
+            file_count: 2,
+            dir_count: 1,
+            size: 10,
+            removable_size: 10,
+            retained_size: 0,
+            skipped: false,
+        };
+        let dir = tempfile::Builder::new()
+            .prefix("vac-dry-run-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let file_path = dir.path().join("a");
+        std::fs::write(&file_path, b"a").expect("write file");
+        let mut app = App::new();
+        app.entries = vec![entry(&file_path.to_string_lossy(), Some(1))];
+        app.toggle_all();
+
+        app.enter_confirm_mode();
+        assert!(!app.dry_run_active);
+        assert!(app.take_dry_run_request().is_none());
+        app.cancel_confirm();
+
+        app.confirm_dry_run_first = true;
+        app.enter_confirm_mode();
+        assert!(app.dry_run_computing());
+        let (job_id, items) = app.take_dry_run_request().expect("dry-run request");
+        assert_eq!(items.len(), 1);
+        assert_eq!(app.dry_run_total, 1);
+        assert!(app.take_dry_run_request().is_none());
+
+        // 结果逐项累加，Done 之前仍在计算
+        app.apply_dry_run_message(DryRunMessage::Item {
+            job_id,
+            item: item("/tmp/a"),
+        });
+        let result = app.dry_run_result.as_ref().expect("partial result");
+        assert_eq!((result.total_files, result.total_size), (2, 10));
+        assert!(app.dry_run_computing());
+        app.apply_dry_run_message(DryRunMessage::Done { job_id });
+        assert!(!app.dry_run_computing());
+        assert!(!app.dry_run_in_progress());
+
+        // 关闭后到达的旧结果被丢弃
+        app.cancel_confirm();
+        app.enter_confirm_mode();
+        app.apply_dry_run_message(DryRunMessage::Item {
+            job_id,
+            item: item("/tmp/stale"),
+        });
+        app.apply_dry_run_message(DryRunMessage::Done { job_id });
+        assert!(app.dry_run_computing());
+        assert!(
+            app.dry_run_result
+                .as_ref()
+                .expect("result")
+                .items
+                .is_empty()
+        );
+
+        // 已有结果时切换视图不再重新计算
+        let (job_id, _) = app.take_dry_run_request().expect("dry-run request");
+        app.apply_dry_run_message(DryRunMessage::Done { job_id });
+        app.toggle_dry_run();
+        app.toggle_dry_run();
+        assert!(app.dry_run_active);
+        assert!(app.take_dry_run_request().is_none());
+    }
+
+    #[test]
+    fn confirm_word_required_only_for_large_permanent_deletes() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-confirm-word-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        std::fs::write(dir.path().join("dl"), b"dl").expect("write file");
+        std::fs::write(dir.path().join("cache"), b"cache").expect("write file");
+        let mut downloads = entry(&dir.path().join("dl").to_string_lossy(), Some(600));
+        downloads.category = Some(ItemCategory::Downloads);
+        let cache = entry(&dir.path().join("cache").to_string_lossy(), Some(500));
+
+        let mut app = App::new();
+        app.entries = vec![downloads, cache];
+        app.toggle_all();
+
+        // 未设置阈值时行为不变
+        app.enter_confirm_mode();
+        assert!(app.confirm_word_input.is_none());
+        assert!(app.confirm_armed());
+        app.cancel_confirm();
+
+        app.confirm_word_above = Some(1_000);
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_permanent_size(), 1_100);
+        assert!(!app.confirm_armed());
+        for c in "DELET".chars() {
+            app.confirm_word_char(c);
+        }
+        assert!(!app.confirm_armed());
+        app.confirm_word_char('E');
+        assert!(app.confirm_armed());
+        app.confirm_word_backspace();
+        assert!(!app.confirm_armed());
+        app.cancel_confirm();
+        assert!(app.confirm_word_input.is_none());
+
+        // 下载目录移至回收站后，永久删除部分低于阈值
+        app.trash_categories = vec![ItemCategory::Downloads];
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_permanent_size(), 500);
+        assert!(app.confirm_armed());
+        app.cancel_confirm();
+
+        app.use_trash = true;
+        app.trash_categories.clear();
+        app.enter_confirm_mode();
+        assert!(app.confirm_armed());
+        app.cancel_confirm();
+
+        // 安全擦除优先于回收站，始终算作永久删除
+        app.use_secure_delete = true;
+        app.enter_confirm_mode();
+        assert!(!app.confirm_armed());
+        app.cancel_confirm();
+
+        // 大小未知的永久删除项目视为超过阈值
+        std::fs::create_dir(dir.path().join("lazy")).expect("create dir");
+        app.use_secure_delete = false;
+        app.use_trash = false;
+        app.clear_selections();
+        app.entries = vec![
+            entry(&dir.path().join("cache").to_string_lossy(), Some(500)),
+            entry(&dir.path().join("lazy").to_string_lossy(), None),
+        ];
+        app.toggle_all();
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_permanent_size(), 500);
+        assert!(!app.confirm_armed());
+    }
+
+    #[test]
+    fn force_trash_overrides_secure_delete_and_blocks_empty_trash() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-force-trash-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        std::fs::write(dir.path().join("dl"), b"dl").expect("write file");
+        let mut downloads = entry(&dir.path().join("dl").to_string_lossy(), Some(600));
+        downloads.category = Some(ItemCategory::Downloads);
+
+        let mut app = App::new();
+        app.entries = vec![downloads.clone()];
+        app.toggle_all();
+        app.use_secure_delete = true;
+        app.trash_categories = vec![ItemCategory::Logs];
+        app.force_trash = true;
+
+        assert!(!app.secure_delete_active());
+        assert!(app.trash_policy().should_trash(&downloads));
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_clean_action(), CleanAction::Trash);
+        assert_eq!(app.confirm_permanent_size(), 0);
+        app.cancel_confirm();
+
+        app.trash_usage = Some(1_024);
+        app.enter_empty_trash_confirm();
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.notice.is_some());
+
+        app.force_trash = false;
+        app.enter_empty_trash_confirm();
+        assert_eq!(app.mode, Mode::ConfirmEmptyTrash);
+    }
+
+    #[test]
+    fn confirm_category_stats_groups_selection_with_item_counts() {
+        let mut logs = entry("/tmp/logs", Some(100));
+        logs.category = Some(ItemCategory::Logs);
+        let mut cache = entry("/tmp/cache", Some(300));
+        cache.category = Some(ItemCategory::AppCache);
+
+        let mut app = App::new();
+        app.root_entries = vec![logs.clone(), cache];
+        app.entries = vec![
+            logs,
+            entry("/tmp/cache/a", Some(40)),
+            entry("/tmp/cache/b", Some(160)),
+            entry("/opt/stray", Some(5)),
+        ];
+        app.toggle_all();
+
+        let summary: Vec<(String, usize, u64)> = app
+            .confirm_category_stats()
+            .into_iter()
+            .map(|category| (category.name, category.item_count, category.size))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("应用缓存".to_string(), 2, 200),
+                ("日志文件".to_string(), 1, 100),
+                ("其他".to_string(), 1, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn category_stats_scope_switches_between_root_scan_and_current_view() {
+        let mut logs = entry("/tmp/logs", Some(100));
+        logs.category = Some(ItemCategory::Logs);
+        let mut cache = entry("/tmp/cache", Some(300));
+        cache.category = Some(ItemCategory::AppCache);
+        let child = entry("/tmp/cache/app", Some(40));
+        let stray = entry("/opt/elsewhere", Some(5));
+
+        let mut app = App::new();
+        app.root_entries = vec![logs, cache];
+        app.entries = vec![child, stray];
+        let names_and_sizes = |app: &App| -> Vec<(String, u64)> {
+            app.get_category_stats()
+                .into_iter()
+                .map(|category| (category.name, category.size))
+                .collect()
+        };
+
+        assert_eq!(app.stats_scope, StatsScope::All);
+        assert_eq!(
+            names_and_sizes(&app),
+            vec![("应用缓存".to_string(), 300), ("日志文件".to_string(), 100)]
+        );
+
+        app.toggle_stats_scope();
+        assert_eq!(app.stats_scope, StatsScope::CurrentView);
+        assert_eq!(
+            names_and_sizes(&app),
+            vec![("应用缓存".to_string(), 40), ("其他".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn lazy_size_requests_follow_cursor_and_selection_once_per_generation() {
+        let mut dir_a = entry("/tmp/lazy/a", None);
+        dir_a.kind = EntryKind::Directory;
+        let mut dir_b = entry("/tmp/lazy/b", None);
+        dir_b.kind = EntryKind::Directory;
+        let mut dir_c = entry("/tmp/lazy/c", None);
+        dir_c.kind = EntryKind::Directory;
+        let file = entry("/tmp/lazy/file", Some(3));
+
+        let mut app = App::new();
+        app.navigation.current_path = Some(PathBuf::from("/tmp/lazy"));
+        app.set_entries(vec![dir_a.clone(), dir_b.clone(), dir_c.clone(), file]);
+        app.lazy_sizes = true;
+        assert!(app.take_lazy_size_requests().is_empty());
+
+        app.lazy_listing = true;
+        assert_eq!(app.take_lazy_size_requests(), vec![dir_a.path.clone()]);
+        assert!(app.take_lazy_size_requests().is_empty());
+
+        app.next();
+        app.next();
+        app.toggle_selected();
+        app.previous();
+        let mut requests = app.take_lazy_size_requests();
+        requests.sort();
+        assert_eq!(requests, vec![dir_b.path.clone(), dir_c.path.clone()]);
+
+        app.apply_entry_size(&dir_b.path, 7);
+        assert_eq!(app.total_size, 10);
+
+        // 新的扫描代数重新请求；关闭快速列表后补齐所有未知大小的目录
+        app.scan_generation += 1;
+        app.toggle_lazy_sizes();
+        let mut requests = app.take_lazy_size_requests();
+        requests.sort();
+        assert_eq!(requests, vec![dir_a.path, dir_c.path]);
+    }
+
+    #[test]
+    fn recompute_replaces_existing_size_and_tracks_running_directory() {
+        let dir = named_entry("stale", EntryKind::Directory, Some(100));
+        let file = named_entry("file", EntryKind::File, Some(5));
+
+        let mut app = App::new();
+        app.navigation.current_path = Some(PathBuf::from("/tmp"));
+        app.set_entries(vec![dir.clone(), file]);
+        app.toggle_selected();
+        assert_eq!((app.total_size, app.selected_size), (105, 100));
+
+        assert_eq!(app.start_recompute(), Some(dir.path.clone()));
+        assert!(app.is_recomputing(&dir.path));
+        assert_eq!(app.start_recompute(), None);
+
+        app.apply_entry_size(&dir.path, 40);
+        assert!(!app.is_recomputing(&dir.path));
+        assert_eq!(app.entries[0].size, Some(40));
+        assert_eq!((app.total_size, app.selected_size), (45, 40));
+
+        app.next();
+        assert_eq!(app.start_recompute(), None);
+        assert!(app.notice.is_some());
     }
 
-    fn named_entry(name: &str, kind: EntryKind, size: Option<u64>) -> CleanableEntry {
-        CleanableEntry {
-            kind,
-            category: None,
-            path: PathBuf::from(format!("/tmp/{name}")),
-            name: name.to_string(),
-            size,
-            modified_at: None,
-        }
+    #[test]
+    fn confirm_scroll_clamps_when_list_shrinks() {
+        let mut app = App::new();
+        app.entries = (0..25)
+            .map(|index| entry(&format!("/tmp/item{index}"), Some(1)))
+            .collect();
+        app.toggle_all();
+        app.enter_confirm_mode();
+        app.confirm_visible_height = 10;
+        app.scroll_confirm_bottom();
+        assert_eq!(app.confirm_scroll, 15);
+
+        app.clamp_confirm_scroll(30, 10);
+        assert_eq!(app.confirm_scroll, 15);
+        app.clamp_confirm_scroll(18, 10);
+        assert_eq!(app.confirm_scroll, 8);
+        app.clamp_confirm_scroll(5, 10);
+        assert_eq!(app.confirm_scroll, 0);
+
+        // 切换到条目更少的 dry-run 视图后，按当前视图修正
+        app.confirm_scroll = 15;
+        app.dry_run_result = Some(DryRunResult {
+            items: (0..12)
+                .map(|index| crate::cleaner::DryRunItem {
+                    path: PathBuf::from(format!("/tmp/item{index}")),
+                    file_count: 1,
+                    dir_count: 0,
+                    size: 1,
+                    removable_size: 1,
+                    retained_size: 0,
+                    skipped: false,
+                })
+                .collect(),
+            ..DryRunResult::default()
+        });
+        app.dry_run_active = true;
+        app.clamp_confirm_scroll_to_view();
+        assert_eq!(app.confirm_scroll, 2);
     }
 
     #[test]
-    fn toggle_selected_updates_selected_size() {
+    fn count_prefix_builds_number_and_jumps_to_clamped_line() {
         let mut app = App::new();
-        app.entries = vec![entry("/tmp/a", Some(10)), entry("/tmp/b", Some(5))];
+        app.entries = (0..20)
+            .map(|i| entry(&format!("/tmp/{i}"), Some(1)))
+            .collect();
+
+        app.push_count_digit(0);
+        assert_eq!(app.pending_count, None);
+        app.push_count_digit(1);
+        app.push_count_digit(2);
+        assert_eq!(app.pending_count, Some(12));
+        let line = app.pending_count.take().unwrap();
+        app.jump_to_line(line);
+        assert_eq!(app.list_state.selected(), Some(11));
+
+        app.jump_to_line(999);
+        assert_eq!(app.list_state.selected(), Some(19));
+        app.jump_to_line(0);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        app.pending_count = Some(usize::MAX / 10 + 1);
+        app.push_count_digit(9);
+        assert_eq!(app.pending_count, Some(usize::MAX / 10 + 1));
+    }
+
+    #[test]
+    fn current_absolute_path_resolves_highlighted_entry() {
+        let mut app = App::new();
+        assert_eq!(app.current_absolute_path(), None);
+
+        app.entries = vec![entry("/tmp/big", Some(1)), entry("relative/dir", Some(2))];
         app.list_state.select(Some(0));
+        assert_eq!(app.current_absolute_path(), Some(PathBuf::from("/tmp/big")));
+        app.list_state.select(Some(1));
+        let expected = std::env::current_dir().unwrap().join("relative/dir");
+        assert_eq!(app.current_absolute_path(), Some(expected));
+    }
 
-        app.toggle_selected();
-        assert_eq!(app.selected_size, 10);
+    #[test]
+    fn pending_cursor_restores_path_or_nearest_index_after_rescan() {
+        let names = ["a", "b", "c", "d"];
+        let mut app = App::new();
+        app.navigation.current_path = Some(PathBuf::from("/tmp"));
+        app.set_entries(
+            names
+                .iter()
+                .map(|name| named_entry(name, EntryKind::File, Some(1)))
+                .collect(),
+        );
+        app.list_state.select(Some(2));
+
+        // 高亮条目仍存在：按路径恢复
+        let cursor = app.cursor_position();
+        app.scan_generation += 1;
+        app.set_pending_cursor(cursor);
+        app.set_entries(
+            ["b", "c", "d"]
+                .iter()
+                .map(|name| named_entry(name, EntryKind::File, Some(1)))
+                .collect(),
+        );
+        app.restore_pending_cursor();
+        assert_eq!(app.current_entry().map(|e| e.name.as_str()), Some("c"));
+
+        // 高亮条目已删除：停在原索引（超出末尾时为最后一项）
+        app.list_state.select(Some(2));
+        let cursor = app.cursor_position();
+        app.scan_generation += 1;
+        app.set_pending_cursor(cursor);
+        app.set_entries(vec![
+            named_entry("b", EntryKind::File, Some(1)),
+            named_entry("c", EntryKind::File, Some(1)),
+        ]);
+        app.restore_pending_cursor();
+        assert_eq!(app.list_state.selected(), Some(1));
 
-        app.toggle_selected();
-        assert_eq!(app.selected_size, 0);
+        // 期间开始了其他扫描：忽略
+        app.list_state.select(Some(1));
+        let cursor = app.cursor_position();
+        app.set_pending_cursor(cursor);
+        app.scan_generation += 1;
+        app.list_state.select(Some(0));
+        app.restore_pending_cursor();
+        assert_eq!(app.list_state.selected(), Some(0));
     }
 
     #[test]
-    fn toggle_all_selects_and_deselects() {
+    fn select_filtered_matches_toggle_all_scope_under_active_filter() {
         let mut app = App::new();
-        app.entries = vec![entry("/tmp/a", Some(3)), entry("/tmp/b", Some(7))];
+        app.entries = vec![
+            named_entry("app.log", EntryKind::File, Some(2)),
+            named_entry("db.log", EntryKind::File, Some(3)),
+            named_entry("data.bin", EntryKind::File, Some(4)),
+        ];
+        app.start_search();
+        for c in ".log".chars() {
+            app.search_char(c);
+        }
+        app.confirm_search();
+        app.list_state.select(Some(1));
 
-        app.toggle_all();
+        app.select_search_matches();
         assert_eq!(app.selections.len(), 2);
-        assert_eq!(app.selected_size, 10);
+        assert_eq!(app.selected_size, 5);
+        assert!(!app.is_selected(&PathBuf::from("/tmp/data.bin")));
 
+        // toggle_all 作用于同一过滤后集合：已全部选中时取消
         app.toggle_all();
         assert!(app.selections.is_empty());
-        assert_eq!(app.selected_size, 0);
+        app.toggle_all();
+        assert_eq!(app.selections.len(), 2);
+
+        // select_filtered 只增加选择，重复调用结果不变
+        assert_eq!(app.select_filtered(".log"), 2);
+        assert_eq!(app.selected_size, 5);
+
+        app.cancel_search();
+        app.clear_selections();
+        app.select_search_matches();
+        assert!(app.selections.is_empty());
+        assert!(app.notice.is_some());
     }
 
     #[test]
-    fn apply_entry_size_updates_selected_size() {
+    fn hidden_entries_leave_view_but_stay_in_totals() {
         let mut app = App::new();
-        let entry = entry("/tmp/a", None);
-        app.entries = vec![entry.clone()];
-        app.list_state.select(Some(0));
+        app.root_entries = vec![entry("/tmp/a", Some(3)), entry("/tmp/b", Some(7))];
+        app.set_entries(app.root_entries.clone());
         app.toggle_selected();
 
-        app.apply_entry_size(&PathBuf::from("/tmp/a"), 12);
-        assert_eq!(app.selected_size, 12);
+        app.toggle_hide_current();
+        assert_eq!(app.entries.len(), 1);
+        assert_eq!(app.entries[0].path, PathBuf::from("/tmp/b"));
+        assert_eq!(app.total_size, 10);
+        assert!(app.selections.is_empty());
+
+        // 重新设置视图时仍然过滤，但总大小计入隐藏条目
+        app.set_entries(app.root_entries.clone());
+        assert_eq!(app.entries.len(), 1);
+        assert_eq!(app.total_size, 10);
+
+        // 根层显示全部时从缓存恢复，再按 h 取消隐藏
+        assert_eq!(app.toggle_show_hidden(), None);
+        assert_eq!(app.entries.len(), 2);
+        app.list_state.select(Some(
+            app.entries
+                .iter()
+                .position(|e| e.path == Path::new("/tmp/a"))
+                .unwrap(),
+        ));
+        app.toggle_hide_current();
+        assert!(app.hidden.is_empty());
+
+        // 子目录视图开启显示全部时要求重新列出
+        app.hidden.insert(PathBuf::from("/tmp/a"));
+        app.toggle_show_hidden();
+        app.navigation.current_path = Some(PathBuf::from("/tmp"));
+        assert_eq!(app.toggle_show_hidden(), Some(PathBuf::from("/tmp")));
     }
 
     #[test]
-    fn sort_root_entries_respects_sort_order_by_size() {
+    fn size_floor_cycles_and_filters_view_reversibly() {
         let mut app = App::new();
-        app.root_entries = vec![
-            named_entry("small", EntryKind::File, Some(10)),
-            named_entry("big", EntryKind::File, Some(100)),
-            named_entry("mid", EntryKind::File, Some(50)),
-        ];
         app.sort_order = SortOrder::BySize;
-        app.sort_root_entries();
+        app.set_entries(vec![
+            named_entry("big", EntryKind::Directory, Some(20 << 20)),
+            named_entry("small", EntryKind::File, Some(2 << 20)),
+            named_entry("tiny", EntryKind::File, Some(100)),
+            named_entry("unknown", EntryKind::Directory, None),
+        ]);
+
+        app.cycle_size_floor();
+        assert_eq!(app.size_floor, 1 << 20);
+        assert_eq!(app.entries.len(), 3);
+        assert_eq!(app.total_size, 22 << 20);
+
+        let small = app.entries[1].clone();
+        assert_eq!(small.name, "small");
+        app.list_state.select(Some(1));
+        app.toggle_selected();
+        assert_eq!(app.selected_size, 2 << 20);
 
-        let names: Vec<&str> = app.root_entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["big", "mid", "small"]);
+        app.cycle_size_floor();
+        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["big", "unknown"]);
+        // 不再显示的条目同时取消选中
+        assert!(!app.is_selected(&small.path));
+        assert_eq!(app.selected_size, 0);
+        assert_eq!(app.total_size, 20 << 20);
+        // 进入子目录时缓存的视图包含未显示的条目
+        assert_eq!(app.view_entries().len(), 4);
+
+        app.cycle_size_floor();
+        app.cycle_size_floor();
+        assert_eq!(app.size_floor, 0);
+        assert_eq!(app.entries.len(), 4);
+        assert_eq!(app.total_size, (22 << 20) + 100);
     }
 
     #[test]
-    fn sort_root_entries_respects_sort_order_by_name() {
+    fn retention_selects_excess_and_protects_dir_and_newest() {
         let mut app = App::new();
-        app.root_entries = vec![
-            named_entry("c_file", EntryKind::File, Some(10)),
-            named_entry("a_dir", EntryKind::Directory, Some(100)),
-            named_entry("b_file", EntryKind::File, Some(50)),
-        ];
-        app.sort_order = SortOrder::ByName;
-        app.sort_root_entries();
+        let dir = named_entry("builds", EntryKind::Directory, Some(300));
+        app.set_entries(vec![dir.clone()]);
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        let kept = CleanableEntry {
+            path: dir.path.join("newest"),
+            ..named_entry("newest", EntryKind::File, Some(50))
+        };
+        app.select_all_entries(std::slice::from_ref(&kept));
 
-        let names: Vec<&str> = app.root_entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["a_dir", "b_file", "c_file"]);
+        assert_eq!(app.start_retention(), Some(dir.path.clone()));
+        let old = CleanableEntry {
+            path: dir.path.join("old"),
+            ..named_entry("old", EntryKind::File, Some(100))
+        };
+        app.apply_retention(&dir.path, Ok(vec![old.clone()]));
+
+        assert!(!app.is_selected(&dir.path));
+        assert!(!app.is_selected(&kept.path));
+        assert!(app.is_selected(&old.path));
+        assert_eq!(app.selected_size, 100);
     }
 
     #[test]
-    fn toggle_sort_order_at_root_applies_to_root_entries() {
+    fn retention_deselects_ancestors_of_dir() {
         let mut app = App::new();
-        app.root_entries = vec![
-            named_entry("z_small", EntryKind::File, Some(1)),
-            named_entry("a_big", EntryKind::File, Some(100)),
-        ];
-        // 初始在根目录（navigation.current_path 为 None）
-        assert!(app.navigation.current_path.is_none());
-        app.sort_order = SortOrder::ByName;
-        app.sort_root_entries();
+        let root = named_entry("releases", EntryKind::Directory, Some(1000));
+        app.set_entries(vec![root.clone()]);
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        let dir = root.path.join("app");
+        let nested_kept = CleanableEntry {
+            path: dir.join("newest").join("bin"),
+            ..named_entry("bin", EntryKind::File, Some(30))
+        };
+        app.select_all_entries(std::slice::from_ref(&nested_kept));
 
-        // 切换到 BySize
-        app.toggle_sort_order();
-        assert_eq!(app.sort_order, SortOrder::BySize);
-        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["a_big", "z_small"]);
+        let old = CleanableEntry {
+            path: dir.join("old"),
+            ..named_entry("old", EntryKind::File, Some(100))
+        };
+        app.apply_retention(&dir, Ok(vec![old.clone()]));
+
+        assert!(!app.is_selected(&root.path));
+        assert!(!app.is_selected(&nested_kept.path));
+        assert!(app.is_selected(&old.path));
+        assert_eq!(app.selected_size, 100);
     }
 
     #[test]
-    fn toggle_sort_order_in_subdir_applies_to_dir_entries() {
+    fn selection_sizes_reconcile_outside_current_view() {
         let mut app = App::new();
-        app.navigation
-            .enter(PathBuf::from("/tmp/subdir"), Vec::new(), None);
-        app.entries = vec![
-            named_entry("z_item", EntryKind::File, Some(1)),
-            named_entry("a_item", EntryKind::File, Some(100)),
-        ];
-        app.sort_order = SortOrder::BySize;
+        let dir = named_entry("cache", EntryKind::Directory, None);
+        app.set_entries(vec![dir.clone()]);
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        assert_eq!(app.selected_size, 0);
 
-        // BySize -> ByTime
-        app.toggle_sort_order();
-        assert_eq!(app.sort_order, SortOrder::ByTime);
+        app.set_entries(vec![named_entry("other", EntryKind::File, Some(5))]);
+        app.apply_entry_size(&dir.path, 40);
+        assert_eq!(app.selected_size, 40);
 
-        // ByTime -> ByName
-        app.toggle_sort_order();
-        assert_eq!(app.sort_order, SortOrder::ByName);
-        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["a_item", "z_item"]);
+        app.apply_dir_entry(dir.clone());
+        assert_eq!(app.selected_size, 40);
+        app.apply_selection_size(&dir.path, 25);
+        assert_eq!(app.selected_size, 25);
+        assert_eq!(app.selections[&dir.path].size, Some(25));
     }
 
     #[test]
-    fn restore_root_entries_applies_current_sort_order() {
+    fn search_history_records_confirmed_queries_and_recalls_them() {
         let mut app = App::new();
-        app.root_entries = vec![
-            named_entry("z_item", EntryKind::File, Some(1)),
-            named_entry("a_item", EntryKind::File, Some(100)),
+        let all = vec![
+            named_entry("cache", EntryKind::Directory, Some(10)),
+            named_entry("logs", EntryKind::Directory, Some(20)),
         ];
-        app.sort_order = SortOrder::ByName;
+        for query in ["cache", "logs", "logs", ""] {
+            app.set_entries(all.clone());
+            app.start_search();
+            query.chars().for_each(|c| app.search_char(c));
+            app.confirm_search();
+        }
+        app.set_entries(all);
+        app.start_search();
+        app.search_char('x');
+        app.cancel_search();
+        assert_eq!(app.search_history, ["cache", "logs"]);
+
+        app.start_search();
+        app.search_history_next();
+        assert!(app.search_query.is_empty());
+        app.search_history_prev();
+        assert_eq!(app.search_query, "logs");
+        assert_eq!(app.entries.len(), 1);
+        app.search_history_prev();
+        app.search_history_prev();
+        assert_eq!(app.search_query, "cache");
+        app.search_history_next();
+        assert_eq!(app.search_query, "logs");
+        app.search_history_next();
+        assert!(app.search_query.is_empty());
+        assert_eq!(app.entries.len(), 2);
 
-        app.restore_root_entries();
-        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["a_item", "z_item"]);
+        for index in 0..SEARCH_HISTORY_LIMIT + 5 {
+            app.search_query = format!("q{index}");
+            app.confirm_search();
+        }
+        assert_eq!(app.search_history.len(), SEARCH_HISTORY_LIMIT);
+        assert_eq!(app.search_history[0], "q5");
     }
 
     #[test]
-    fn restore_cached_dir_entries_applies_current_sort_order_and_preserves_selection() {
+    fn jump_search_moves_cursor_without_filtering() {
         let mut app = App::new();
-        app.sort_order = SortOrder::BySize;
-        app.navigation
-            .enter(PathBuf::from("/tmp/parent"), Vec::new(), None);
+        app.set_entries(vec![
+            named_entry("alpha", EntryKind::File, Some(1)),
+            named_entry("Build-Cache", EntryKind::Directory, Some(2)),
+            named_entry("cache", EntryKind::Directory, Some(3)),
+        ]);
+        app.start_search();
+        app.toggle_search_jump();
+        "CACHE".chars().for_each(|c| app.search_char(c));
+        assert_eq!(app.entries.len(), 3);
+        assert_eq!(app.list_state.selected(), Some(1));
 
-        let cached_entries = vec![
-            named_entry("z_small", EntryKind::File, Some(1)),
-            named_entry("a_big", EntryKind::File, Some(100)),
-        ];
-        // 之前在缓存顺序中选中 z_small（索引 0），切换到 BySize 后应仍选中该条目
-        app.restore_cached_dir_entries(cached_entries, Some(0));
+        let matcher = app
+            .search_highlighter()
+            .expect("highlighter while searching");
+        assert_eq!(matcher.find("Build-Cache"), Some(6..11));
+        assert_eq!(matcher.find("alpha"), None);
 
-        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["a_big", "z_small"]);
+        app.search_char('x');
         assert_eq!(app.list_state.selected(), Some(1));
+        app.confirm_search();
+        assert!(app.active_filter.is_none());
+        assert!(app.search_highlighter().is_none());
+
+        app.start_search();
+        app.toggle_search_jump();
+        app.search_char('a');
+        assert_eq!(app.entries.len(), 3);
+        app.search_char('l');
+        assert_eq!(app.entries.len(), 1);
     }
 
     #[test]
-    fn back_returns_cached_entries_and_selected_index() {
-        let mut nav = NavigationState::new();
-        let root_entries = vec![
-            named_entry("dir_a", EntryKind::Directory, Some(100)),
-            named_entry("dir_b", EntryKind::Directory, Some(50)),
-        ];
-
-        // 进入 dir_a，缓存根层条目和选中位置
-        nav.enter(PathBuf::from("/tmp/dir_a"), root_entries.clone(), Some(0));
-        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir_a")));
+    fn append_scan_keeps_existing_roots_and_skips_duplicates() {
+        let mut app = App::new();
+        app.apply_root_entry(named_entry("Downloads", EntryKind::Directory, Some(100)));
+        app.finish_scan();
+
+        app.begin_append_scan();
+        app.apply_root_entry(named_entry("Desktop", EntryKind::Directory, Some(50)));
+        app.apply_root_entry(named_entry("Downloads", EntryKind::Directory, Some(100)));
+        app.apply_root_entry(named_entry("Downloads/a.zip", EntryKind::File, Some(10)));
+        app.apply_root_entry(CleanableEntry {
+            path: PathBuf::from("/tmp"),
+            ..named_entry("tmp", EntryKind::Directory, Some(500))
+        });
+        app.finish_scan();
+        app.sort_root_entries();
 
-        // 回退：应恢复缓存的条目和选中位置
-        let result = nav.back();
-        assert!(result.is_none()); // 回到根目录，栈为空
-        assert!(nav.current_path.is_none());
+        assert_eq!(app.root_entries.len(), 2);
+        assert_eq!(app.entries.len(), 2);
+        assert_eq!(app.total_size, 150);
+        assert!(!app.appending_scan);
+        assert_eq!(app.notice.as_deref(), Some("跳过 3 个与已有结果重叠的条目"));
     }
 
     #[test]
-    fn back_from_nested_restores_parent_cache() {
-        let mut nav = NavigationState::new();
-        let level1_entries = vec![
-            named_entry("child_a", EntryKind::Directory, Some(30)),
-            named_entry("child_b", EntryKind::File, Some(20)),
-        ];
-
-        // 进入第一层（从根进入，缓存空的根条目）
-        nav.enter(PathBuf::from("/tmp/dir"), Vec::new(), Some(0));
-        // 进入第二层，缓存第一层条目
-        nav.enter(
-            PathBuf::from("/tmp/dir/sub"),
-            level1_entries.clone(),
-            Some(1),
+    fn detail_requests_follow_cursor_and_replace_pending() {
+        let mut app = App::new();
+        let first = CleanableEntry {
+            path: PathBuf::from("/tmp/first"),
+            ..named_entry("first", EntryKind::Directory, Some(1))
+        };
+        let second = CleanableEntry {
+            path: PathBuf::from("/tmp/second"),
+            ..named_entry("second", EntryKind::Directory, Some(2))
+        };
+        app.set_entries(vec![first.clone(), second.clone()]);
+        assert_eq!(app.take_detail_request(), None);
+
+        app.toggle_details();
+        assert_eq!(app.take_detail_request(), Some(first.path.clone()));
+        assert_eq!(app.take_detail_request(), None);
+        // 移到另一个目录时立即改为统计新目录，旧目录迟到的结果被丢弃
+        app.next();
+        assert_eq!(app.take_detail_request(), Some(second.path.clone()));
+        app.apply_detail_counts(first.path.clone(), 3, 1);
+        assert_eq!(app.detail_counts, None);
+        app.apply_detail_counts(second.path.clone(), 0, 0);
+        assert_eq!(app.detail_counts, Some((second.path.clone(), 0, 0)));
+        assert_eq!(app.take_detail_request(), None);
+
+        app.resize_details(true);
+        assert_eq!(
+            app.detail_percent,
+            DETAIL_PERCENT_DEFAULT + DETAIL_PERCENT_STEP
         );
-        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir/sub")));
-
-        // 从第二层回退，应恢复进入第二层时缓存的条目（level1_entries）
-        let result = nav.back();
-        assert!(result.is_some());
-        let (cached, idx) = result.unwrap();
-        assert_eq!(nav.current_path, Some(PathBuf::from("/tmp/dir")));
-        assert_eq!(cached.len(), 2); // 进入第二层时缓存的 level1_entries
-        assert_eq!(idx, Some(1));
-
-        // 再回退到根目录
-        let result = nav.back();
-        assert!(result.is_none());
-        assert!(nav.current_path.is_none());
+        for _ in 0..10 {
+            app.resize_details(false);
+        }
+        assert_eq!(app.detail_percent, DETAIL_PERCENT_RANGE.0);
     }
 
     #[test]
-    fn back_restores_entries_in_app() {
+    fn quit_confirms_only_with_selections_when_enabled() {
         let mut app = App::new();
-        let root_entries = vec![named_entry("dir_parent", EntryKind::Directory, Some(200))];
-        app.set_entries(root_entries.clone());
+        app.entries = vec![entry("/tmp/a", Some(1))];
+        assert!(app.confirm_quit);
 
-        // 进入第一层子目录，缓存根条目
-        let parent_entries = vec![
-            named_entry("file_a", EntryKind::File, Some(100)),
-            named_entry("file_b", EntryKind::File, Some(50)),
-        ];
-        app.navigation
-            .enter(PathBuf::from("/tmp/parent"), app.entries.clone(), Some(0));
-        app.set_entries(parent_entries.clone());
+        app.request_quit();
+        assert!(app.should_quit);
 
-        // 进入第二层子目录，缓存第一层条目
-        app.navigation.enter(
-            PathBuf::from("/tmp/parent/child"),
-            app.entries.clone(),
-            Some(1),
-        );
-        app.set_entries(vec![named_entry("sub_file", EntryKind::File, Some(10))]);
-        assert_eq!(app.entries.len(), 1);
+        app.should_quit = false;
+        app.list_state.select(Some(0));
+        app.toggle_selected();
+        app.request_quit();
+        assert_eq!(app.mode, Mode::ConfirmQuit);
+        assert!(!app.should_quit);
+        app.cancel_quit();
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.selections.len(), 1);
+
+        // 扫描中取消确认回到扫描；确认期间扫描结束则回到浏览
+        app.mode = Mode::Scanning;
+        app.scan_in_progress = true;
+        app.request_quit();
+        app.cancel_quit();
+        assert_eq!(app.mode, Mode::Scanning);
+        app.request_quit();
+        app.finish_scan();
+        app.cancel_quit();
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.confirm_quit = false;
+        app.request_quit();
+        assert!(app.should_quit);
+    }
 
-        // 从第二层回退到第一层：恢复缓存
-        if let Some((cached_entries, selected_index)) = app.navigation.back() {
-            app.set_entries(cached_entries);
-            app.list_state.select(selected_index);
+    #[test]
+    fn settings_toggle_disabled_categories() {
+        let mut app = App::new();
+        app.open_settings();
+        assert_eq!(app.mode, Mode::Settings);
+        assert!(!app.settings_changed());
+
+        app.settings_previous();
+        assert_eq!(app.settings_index, 0);
+        app.toggle_settings_category();
+        assert!(!app.is_category_enabled(&ItemCategory::SystemCache));
+        assert_eq!(app.disabled_categories, vec![ItemCategory::SystemCache]);
+        assert!(app.settings_changed());
+
+        for _ in 0..Scanner::PRESET_CATEGORIES.len() + 2 {
+            app.settings_next();
         }
-        assert_eq!(app.entries.len(), 2);
-        assert_eq!(app.list_state.selected(), Some(1));
-        let names: Vec<&str> = app.entries.iter().map(|e| e.name.as_str()).collect();
-        assert_eq!(names, vec!["file_a", "file_b"]);
+        assert_eq!(app.settings_index, Scanner::PRESET_CATEGORIES.len() - 1);
+        app.toggle_settings_category();
+        assert!(!app.is_category_enabled(&ItemCategory::Custom(Some("dev".to_string()))));
+
+        app.settings_index = 0;
+        app.toggle_settings_category();
+        assert!(app.is_category_enabled(&ItemCategory::SystemCache));
+        assert_eq!(app.disabled_categories, vec![ItemCategory::Custom(None)]);
+        assert!(app.settings_changed());
+
+        app.settings_index = Scanner::PRESET_CATEGORIES.len() - 1;
+        app.toggle_settings_category();
+        assert!(app.disabled_categories.is_empty());
+        assert!(!app.settings_changed());
+
+        app.close_settings();
+        assert_eq!(app.mode, Mode::Normal);
     }
 
     #[test]
-    fn reset_root_clears_navigation_stack() {
-        let mut nav = NavigationState::new();
-        nav.enter(PathBuf::from("/tmp/a"), Vec::new(), None);
-        nav.enter(PathBuf::from("/tmp/a/b"), Vec::new(), None);
-        assert!(nav.current_path.is_some());
+    fn confirm_size_includes_nested_root_targets() {
+        let mut app = App::new();
+        let parent = entry("/tmp/caches", Some(100));
+        let child = entry("/tmp/caches/Homebrew", Some(30));
+        app.apply_root_entry(parent.clone());
+        app.apply_root_entry(child.clone());
+        let parent_index = app
+            .entries
+            .iter()
+            .position(|item| item.path == parent.path)
+            .unwrap();
 
-        nav.reset_root();
-        assert!(nav.current_path.is_none());
-        assert!(nav.back().is_none());
+        app.list_state.select(Some(parent_index));
+        app.toggle_selected();
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_size(), 130);
+        assert_eq!(app.get_selected_items()[0].size, Some(130));
+        app.cancel_confirm();
+
+        // 同时选中子目标时不重复计入
+        app.list_state.select(Some(1 - parent_index));
+        app.toggle_selected();
+        app.enter_confirm_mode();
+        assert_eq!(app.confirm_size(), 130);
+    }
+
+    #[test]
+    fn item_category_slug_round_trips() {
+        for category in ItemCategory::ALL {
+            assert_eq!(ItemCategory::from_slug(category.slug()), Some(category));
+        }
+        assert_eq!(
+            ItemCategory::from_slug(" Cargo "),
+            Some(ItemCategory::CargoCache)
+        );
+        assert_eq!(ItemCategory::from_slug("unknown"), None);
     }
 }
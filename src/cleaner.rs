@@ -1,16 +1,210 @@
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
-
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
-use crate::app::CleanableEntry;
+use crate::app::{CleanableEntry, EntryKind, ItemCategory};
+use crate::config::AppConfig;
 
 /// 清理结果
 #[derive(Debug)]
 pub struct CleanResult {
     pub success: bool,
     pub freed_space: u64,
-    pub errors: Vec<String>,
+    pub errors: Vec<CleanError>,
+    /// 实际移入回收站的路径（仅回收站模式，用于撤销）
+    pub trashed_paths: Vec<PathBuf>,
+    /// 已处理的条目路径（取消时不含被跳过的条目）
+    pub processed_paths: Vec<PathBuf>,
+    /// 是否在中途被取消
+    pub cancelled: bool,
+}
+
+impl CleanResult {
+    /// 合并另一组清理结果（用于按类别分组执行后汇总）
+    pub fn merge(mut self, other: CleanResult) -> CleanResult {
+        self.success &= other.success;
+        self.freed_space += other.freed_space;
+        self.errors.extend(other.errors);
+        self.trashed_paths.extend(other.trashed_paths);
+        self.processed_paths.extend(other.processed_paths);
+        self.cancelled |= other.cancelled;
+        self
+    }
+
+    /// 未处理任何条目即失败的结果
+    fn from_error(error: CleanError) -> Self {
+        Self {
+            success: false,
+            freed_space: 0,
+            errors: vec![error],
+            trashed_paths: Vec::new(),
+            processed_paths: Vec::new(),
+            cancelled: false,
+        }
+    }
+
+    /// 成功处理的条目数（`process_items()` 中每个失败的条目对应一条错误）
+    pub fn succeeded_count(&self) -> usize {
+        self.processed_paths.len().saturating_sub(self.errors.len())
+    }
+}
+
+/// 单个条目的清理错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CleanError {
+    /// 权限不足
+    PermissionDenied { path: PathBuf },
+    /// 路径不存在
+    NotFound { path: PathBuf },
+    /// 只读文件系统
+    ReadOnly { path: PathBuf },
+    /// 其他 I/O 错误
+    Io { path: PathBuf, message: String },
+    /// 未通过安全检查
+    Unsafe { path: PathBuf },
+    /// 带有 macOS 不可变标志 (uchg/uappnd) 且未启用清除
+    Immutable { path: PathBuf },
+    /// 资源暂时被其他进程占用（重试后仍失败）
+    Busy { path: PathBuf },
+    /// 安全擦除时有文件存在其他硬链接，只删除了链接而未覆写（`count` 为这类文件数）
+    HardLinked { path: PathBuf, count: usize },
+}
+
+impl CleanError {
+    /// 根据 I/O 错误类型归类
+    pub fn from_io(path: &Path, error: &std::io::Error) -> Self {
+        let path = path.to_path_buf();
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { path },
+            std::io::ErrorKind::NotFound => Self::NotFound { path },
+            std::io::ErrorKind::ReadOnlyFilesystem => Self::ReadOnly { path },
+            std::io::ErrorKind::ResourceBusy | std::io::ErrorKind::WouldBlock => {
+                Self::Busy { path }
+            }
+            _ => Self::Io {
+                path,
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// 出错的路径
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::PermissionDenied { path }
+            | Self::NotFound { path }
+            | Self::ReadOnly { path }
+            | Self::Io { path, .. }
+            | Self::Unsafe { path }
+            | Self::Immutable { path }
+            | Self::Busy { path }
+            | Self::HardLinked { path, .. } => path,
+        }
+    }
+
+    /// 是否为可重试的暂时性错误（权限不足、路径不存在等永久错误不重试）
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Busy { .. })
+    }
+
+    /// 由回收站错误归类：底层为暂时性系统错误时为 `Busy`，其余保留原始描述
+    fn from_trash(path: &Path, error: &trash::Error) -> Self {
+        let io_error = match error {
+            trash::Error::Os { code, .. } => Some(std::io::Error::from_raw_os_error(*code)),
+            #[cfg(all(
+                unix,
+                not(target_os = "macos"),
+                not(target_os = "ios"),
+                not(target_os = "android")
+            ))]
+            trash::Error::FileSystem { source, .. } => Some(std::io::Error::from(source.kind())),
+            _ => None,
+        };
+        match io_error.map(|io_error| Self::from_io(path, &io_error)) {
+            Some(busy @ Self::Busy { .. }) => busy,
+            _ => Self::Io {
+                path: path.to_path_buf(),
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// 错误类别的中文描述（用于分组展示）
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PermissionDenied { .. } => "权限不足",
+            Self::NotFound { .. } => "路径不存在",
+            Self::ReadOnly { .. } => "只读文件系统",
+            Self::Io { .. } => "I/O 错误",
+            Self::Unsafe { .. } => "不安全的路径",
+            Self::Immutable { .. } => "不可变标志 (uchg)",
+            Self::Busy { .. } => "资源被占用",
+            Self::HardLinked { .. } => "存在硬链接，未覆写",
+        }
+    }
+}
+
+impl std::fmt::Display for CleanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            Self::Unsafe { path } => write!(f, "不安全的路径: {}", path.display()),
+            Self::Immutable { path } => write!(
+                f,
+                "{}: 带有不可变标志 (uchg)，可启用 safety.clear_flags 自动清除",
+                path.display()
+            ),
+            Self::HardLinked { path, count } => write!(
+                f,
+                "{}: {} 个文件存在其他硬链接，已删除但未覆写（数据仍可经其他链接访问）",
+                path.display(),
+                count
+            ),
+            other => write!(f, "{}: {}", other.path().display(), other.label()),
+        }
+    }
+}
+
+/// 清理进度消息
+#[derive(Debug)]
+pub enum CleanMessage {
+    /// 单项处理完成 (已完成数, 总数, 当前路径, 该项大小)
+    ///
+    /// 失败的条目同样发送，`size` 计入已处理字节，使总进度最终到达 100%。
+    Progress {
+        completed: usize,
+        total: usize,
+        path: PathBuf,
+        size: u64,
+    },
+    /// 清理结束（含取消）
+    Done(CleanResult),
+}
+
+/// 后台 dry-run 计算消息
+#[derive(Debug)]
+pub enum DryRunMessage {
+    /// 单项统计完成
+    Item { job_id: u64, item: DryRunItem },
+    /// 全部统计完成
+    Done { job_id: u64 },
+}
+
+impl DryRunMessage {
+    pub fn job_id(&self) -> u64 {
+        match self {
+            DryRunMessage::Item { job_id, .. } | DryRunMessage::Done { job_id } => *job_id,
+        }
+    }
 }
 
 /// Dry-run 单项详情
@@ -19,21 +213,232 @@ pub struct DryRunItem {
     pub path: std::path::PathBuf,
     pub file_count: usize,
     pub dir_count: usize,
+    /// 路径下所有文件的总大小
     pub size: u64,
+    /// 实际会被删除的字节数（未通过安全检查时为 0）
+    pub removable_size: u64,
+    /// 清理后仍保留的字节数：被清空的目录本身，或未通过安全检查而整体跳过的内容
+    ///
+    /// `size` 不含目录本身的占用，因此 `removable_size + retained_size = size + 目录本身的占用`。
+    pub retained_size: u64,
+    /// 是否因未通过安全检查而跳过
+    pub skipped: bool,
 }
 
 /// Dry-run 结果（不执行实际删除）
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct DryRunResult {
     pub total_files: usize,
     pub total_dirs: usize,
     pub total_size: u64,
+    /// 实际会被删除的总字节数
+    pub removable_size: u64,
+    /// 保留（被清空的目录本身）或跳过（未通过安全检查）的总字节数，
+    /// 与 `removable_size` 之和等于 `total_size` 加上各目录本身的占用
+    pub retained_size: u64,
+    /// 未通过安全检查而跳过的项目数
+    pub skipped_count: usize,
     pub items: Vec<DryRunItem>,
 }
 
+impl DryRunResult {
+    /// 追加单项结果并累加总计
+    pub fn push(&mut self, item: DryRunItem) {
+        self.total_files += item.file_count;
+        self.total_dirs += item.dir_count;
+        self.total_size += item.size;
+        self.removable_size += item.removable_size;
+        self.retained_size += item.retained_size;
+        self.skipped_count += usize::from(item.skipped);
+        self.items.push(item);
+    }
+}
+
 /// 磁盘清理器
 pub struct Cleaner;
 
+/// 用户配置的删除安全规则（在内置规则之外生效）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafetyRules {
+    /// 除用户目录与临时目录外允许清理的根目录
+    pub allowed_roots: Vec<PathBuf>,
+    /// 额外禁止清理的路径（含其下所有内容）
+    pub forbidden_extra: Vec<PathBuf>,
+    /// VAC 自身的配置/状态/缓存目录及当前使用的配置文件，其本身及其下路径禁止清理；
+    /// 清理包含它的上级目录时由 `Cleaner::without_own_data()` 跳过这部分内容
+    pub own_data: Vec<PathBuf>,
+}
+
+impl SafetyRules {
+    /// 从配置构建（路径已展开 ~）
+    ///
+    /// 除默认数据目录外，`--config` / `VAC_CONFIG` 指定的配置文件也计入 `own_data`：
+    /// 清理该文件会被拒绝，清理包含它的目录时跳过该文件。
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut own_data = crate::utils::home_dir()
+            .map(|home| Self::vac_data_dirs(&home))
+            .unwrap_or_default();
+        if let Some(config_path) = &config.source_path
+            && let Ok(config_path) = std::path::absolute(config_path)
+            && !own_data.iter().any(|dir| config_path.starts_with(dir))
+        {
+            own_data.push(config_path);
+        }
+        Self {
+            allowed_roots: config.expanded_allowed_roots(),
+            forbidden_extra: config.expanded_forbidden_extra(),
+            own_data,
+        }
+    }
+
+    /// VAC 在用户目录下使用的目录：配置（~/.config/vac）与状态、扫描缓存（~/.cache/vac）
+    pub fn vac_data_dirs(home: &Path) -> Vec<PathBuf> {
+        vec![
+            home.join(".config").join("vac"),
+            home.join(".cache").join("vac"),
+        ]
+    }
+
+    /// 路径是否为 VAC 自身的数据或位于其中（用于给出明确的拒绝原因）
+    pub fn is_own_data(&self, path: &Path) -> bool {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.own_data.iter().any(|own| {
+            let own = own.canonicalize().unwrap_or_else(|_| own.clone());
+            path.starts_with(own)
+        })
+    }
+
+    /// 已存在的自身数据路径（已规范化），尚不存在的路径中没有需要在上级目录里跳过的内容
+    fn existing_own_data(&self) -> Vec<PathBuf> {
+        self.own_data
+            .iter()
+            .filter_map(|own| own.canonicalize().ok())
+            .collect()
+    }
+}
+
+/// 按类别决定移至回收站还是永久删除
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrashPolicy {
+    /// 未按类别指定时是否移至回收站（对应 `safety.move_to_trash`）
+    pub default_trash: bool,
+    /// 移至回收站的类别；为空时所有条目按 `default_trash` 处理
+    pub categories: Vec<ItemCategory>,
+}
+
+impl TrashPolicy {
+    /// 所有条目使用同一种方式
+    pub fn uniform(trash: bool) -> Self {
+        Self {
+            default_trash: trash,
+            categories: Vec::new(),
+        }
+    }
+
+    /// 从配置构建（`safety.move_to_trash` 与 `safety.trash_categories`；`safety.force_trash` 时全部移至回收站）
+    pub fn from_config(config: &AppConfig) -> Self {
+        if config.safety.force_trash {
+            return Self::uniform(true);
+        }
+        Self {
+            default_trash: config.safety.move_to_trash,
+            categories: config.trash_categories(),
+        }
+    }
+
+    /// 条目是否应移至回收站
+    ///
+    /// 配置了类别规则时，带类别的条目仅在类别被列出时移至回收站；无类别的条目回退到 `default_trash`。
+    pub fn should_trash(&self, item: &CleanableEntry) -> bool {
+        match &item.category {
+            Some(category) if !self.categories.is_empty() => self
+                .categories
+                .iter()
+                .any(|filter| category.matches_filter(filter)),
+            _ => self.default_trash,
+        }
+    }
+
+    /// 将条目拆分为 `(移至回收站, 永久删除)` 两组，组内保持原有顺序
+    pub fn split(&self, items: &[CleanableEntry]) -> (Vec<CleanableEntry>, Vec<CleanableEntry>) {
+        items
+            .iter()
+            .cloned()
+            .partition(|item| self.should_trash(item))
+    }
+}
+
+/// 批量处理时的进度区间：分组处理时各组共用同一个总数
+#[derive(Debug, Clone, Copy)]
+struct ProgressRange {
+    /// 本组之前已处理的条目数
+    offset: usize,
+    /// 所有分组的条目总数
+    total: usize,
+}
+
+impl ProgressRange {
+    fn whole(items: &[CleanableEntry]) -> Self {
+        Self {
+            offset: 0,
+            total: items.len(),
+        }
+    }
+}
+
+/// 安全擦除默认覆写遍数
+pub const SECURE_DELETE_PASSES: u32 = 3;
+
+/// 安全擦除时每次覆写的缓冲区大小
+const SECURE_DELETE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 暂时性错误的默认重试次数（`safety.delete_retries` 未设置时使用）
+pub const DEFAULT_DELETE_RETRIES: u32 = 2;
+
+/// 暂时性错误重试次数的上限（`safety.delete_retries` 超出时按此截断）
+pub const MAX_DELETE_RETRIES: u32 = 10;
+
+/// 第 N 次重试前等待 N 倍该时长
+const DELETE_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// 清理行为选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// 遇到 macOS 不可变标志时先清除再重试一次
+    pub clear_flags: bool,
+    /// 暂时性错误（如资源被占用）的重试次数，0 表示不重试
+    pub delete_retries: u32,
+    /// 严格模式：任一条目失败后不再开始后续条目
+    pub stop_on_error: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            clear_flags: false,
+            delete_retries: DEFAULT_DELETE_RETRIES,
+            stop_on_error: false,
+        }
+    }
+}
+
+impl CleanOptions {
+    /// 从配置构建（`safety.clear_flags`、`safety.delete_retries` 与 `safety.stop_on_error`）
+    ///
+    /// 重试次数超过 `MAX_DELETE_RETRIES` 时截断，避免退避等待无限拉长。
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            clear_flags: config.safety.clear_flags,
+            delete_retries: config
+                .safety
+                .delete_retries
+                .unwrap_or(DEFAULT_DELETE_RETRIES)
+                .min(MAX_DELETE_RETRIES),
+            stop_on_error: config.safety.stop_on_error,
+        }
+    }
+}
+
 const FORBIDDEN_PATHS: &[&str] = &[
     "/",
     "/System",
@@ -49,46 +454,569 @@ const FORBIDDEN_PATHS: &[&str] = &[
 ];
 
 impl Cleaner {
+    /// 去掉位于其他选中项之下（或与之前的选中项重复）的条目，保持原有顺序
+    ///
+    /// 清理目录会清空其下全部内容，被上级覆盖的条目再单独处理既多余，
+    /// 并行删除时还会与上级相互竞争，产生虚假的“不存在”错误并重复计入释放空间。
+    pub fn without_nested(items: &[CleanableEntry]) -> Vec<CleanableEntry> {
+        let selected: HashSet<&Path> = items.iter().map(|item| item.path.as_path()).collect();
+        let mut seen = HashSet::new();
+        items
+            .iter()
+            .filter(|item| {
+                !item
+                    .path
+                    .ancestors()
+                    .skip(1)
+                    .any(|ancestor| selected.contains(ancestor))
+                    && seen.insert(item.path.as_path())
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// 把包含已存在的 VAC 自身数据的目录条目展开为其子项，跳过自身数据所在的子树
+    ///
+    /// 清理目录会清空其下全部内容；`~/.cache` 这类上级目录不因其中的 `vac` 目录被整体拒绝，
+    /// 而是只清理其余子项（逐层展开到自身数据所在的目录）。展开得到的子项沿用原条目的类别，
+    /// 大小按当前内容统计；其余条目原样保留。
+    pub fn without_own_data(items: &[CleanableEntry], rules: &SafetyRules) -> Vec<CleanableEntry> {
+        let own_data = rules.existing_own_data();
+        let mut result = Vec::with_capacity(items.len());
+        for item in items {
+            if item.kind == EntryKind::Directory && Self::contains_any(&item.path, &own_data) {
+                Self::push_children_outside(item, &item.path, &own_data, &mut result);
+            } else {
+                result.push(item.clone());
+            }
+        }
+        result
+    }
+
+    /// 路径（解析符号链接后）是否为 `protected` 中某一项的上级目录
+    fn contains_any(path: &Path, protected: &[PathBuf]) -> bool {
+        path.canonicalize().is_ok_and(|canonical| {
+            protected
+                .iter()
+                .any(|own| own != &canonical && own.starts_with(&canonical))
+        })
+    }
+
+    fn push_children_outside(
+        item: &CleanableEntry,
+        dir: &Path,
+        protected: &[PathBuf],
+        result: &mut Vec<CleanableEntry>,
+    ) {
+        let Ok(children) = fs::read_dir(dir) else {
+            return;
+        };
+        for child in children.filter_map(|child| child.ok()) {
+            let path = child.path();
+            let Ok(file_type) = child.file_type() else {
+                continue;
+            };
+            if !file_type.is_symlink() {
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                if protected.iter().any(|own| canonical.starts_with(own)) {
+                    continue;
+                }
+                if file_type.is_dir() && Self::contains_any(&path, protected) {
+                    Self::push_children_outside(item, &path, protected, result);
+                    continue;
+                }
+            }
+            let (file_count, _, size) = Self::count_path_contents(&path);
+            result.push(CleanableEntry {
+                kind: if file_type.is_dir() {
+                    EntryKind::Directory
+                } else {
+                    EntryKind::File
+                },
+                category: item.category.clone(),
+                name: child.file_name().to_string_lossy().to_string(),
+                size: Some(size),
+                modified_at: child.metadata().ok().and_then(|m| m.modified().ok()),
+                file_count: file_type.is_dir().then_some(file_count as u64),
+                path,
+            });
+        }
+    }
+
     /// 清理选中的项目（永久删除）
-    pub fn clean(items: &[CleanableEntry]) -> CleanResult {
-        Self::process_items(items, |item| {
-            Self::remove_path(&item.path).map_err(|error| error.to_string())?;
+    ///
+    /// `options.clear_flags` 为 true 时，遇到 macOS 不可变标志会先清除再重试一次。
+    pub fn clean(items: &[CleanableEntry], options: CleanOptions) -> CleanResult {
+        let (tx, _rx) = mpsc::channel();
+        Self::clean_with_progress(items, options, &tx, &AtomicBool::new(false))
+    }
+
+    /// 清理选中的项目（永久删除），每处理完一项发送进度
+    ///
+    /// 选中路径须互不重叠（调用方先经 `without_nested()` 去重），使用 rayon 并行删除（严格模式下顺序删除）；`cancel` 置位后尚未开始的条目被跳过。
+    pub fn clean_with_progress(
+        items: &[CleanableEntry],
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+    ) -> CleanResult {
+        Self::delete_in_range(items, options, tx, cancel, ProgressRange::whole(items))
+    }
+
+    fn delete_in_range(
+        items: &[CleanableEntry],
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+        range: ProgressRange,
+    ) -> CleanResult {
+        Self::process_items(items, tx, cancel, true, options, range, |item| {
+            Self::remove_path_clearing_flags(&item.path, options.clear_flags)?;
             Ok(true)
         })
     }
 
-    /// 将选中的项目移至系统回收站
+    /// 按回收站策略清理：策略指定的条目移至回收站，其余永久删除，结果合并返回
+    pub fn clean_with_policy(
+        items: &[CleanableEntry],
+        policy: &TrashPolicy,
+        options: CleanOptions,
+    ) -> CleanResult {
+        let (tx, _rx) = mpsc::channel();
+        Self::clean_with_policy_progress(items, policy, options, &tx, &AtomicBool::new(false))
+    }
+
+    /// 按回收站策略清理，每处理完一项发送进度（两组共用同一个总数）
+    ///
+    /// 先并行永久删除，再顺序移至回收站；`cancel` 置位后两组中尚未开始的条目均被跳过。
+    /// 严格模式下永久删除一组出错时不再处理回收站一组。
+    pub fn clean_with_policy_progress(
+        items: &[CleanableEntry],
+        policy: &TrashPolicy,
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+    ) -> CleanResult {
+        let (to_trash, to_delete) = policy.split(items);
+        let deleted = Self::delete_in_range(
+            &to_delete,
+            options,
+            tx,
+            cancel,
+            ProgressRange {
+                offset: 0,
+                total: items.len(),
+            },
+        );
+        let to_trash = if options.stop_on_error && !deleted.errors.is_empty() {
+            Vec::new()
+        } else {
+            to_trash
+        };
+        let trashed = Self::trash_in_range(
+            &to_trash,
+            options,
+            tx,
+            cancel,
+            ProgressRange {
+                offset: to_delete.len(),
+                total: items.len(),
+            },
+        );
+        deleted.merge(trashed)
+    }
+
+    /// 执行清理并按需重新测量实际释放的空间
+    ///
+    /// `measure` 为 true 时先统计各条目实际占用的磁盘空间，执行 `run` 后对已处理路径再次统计，
+    /// 以前后差值覆盖 `freed_space`（条目大小未知或已过期时仍准确）；为 false 时直接执行 `run`。
+    pub fn with_measured_freed(
+        items: &[CleanableEntry],
+        measure: bool,
+        run: impl FnOnce() -> CleanResult,
+    ) -> CleanResult {
+        if !measure {
+            return run();
+        }
+        let before: Vec<(PathBuf, u64)> = items
+            .par_iter()
+            .map(|item| (item.path.clone(), allocated_size(&item.path)))
+            .collect();
+        let mut result = run();
+        let processed: std::collections::HashSet<&PathBuf> =
+            result.processed_paths.iter().collect();
+        result.freed_space = before
+            .par_iter()
+            .filter(|(path, _)| processed.contains(path))
+            .map(|(path, size)| size.saturating_sub(allocated_size(path)))
+            .sum();
+        result
+    }
+
+    /// 执行清理后按需删除已处理目录下残留的空子目录
+    ///
+    /// `prune` 为 true 时，对 `run` 处理过的每个目录自底向上删除空子目录（部分文件删除失败时
+    /// 会留下空目录结构），目录本身按保留规则不删除；为 false 时直接执行 `run`。
+    pub fn with_pruned_empty_dirs(prune: bool, run: impl FnOnce() -> CleanResult) -> CleanResult {
+        let result = run();
+        if prune {
+            for path in &result.processed_paths {
+                if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir()) {
+                    Self::prune_empty_dirs(path);
+                }
+            }
+        }
+        result
+    }
+
+    /// 自底向上删除 `root` 下的空子目录（保留 `root` 本身，不跟随符号链接），返回删除的目录数
+    pub fn prune_empty_dirs(root: &Path) -> usize {
+        WalkDir::new(root)
+            .follow_links(false)
+            .min_depth(1)
+            .contents_first(true)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_dir())
+            // 非空目录删除失败，即为“保留”
+            .filter(|entry| fs::remove_dir(entry.path()).is_ok())
+            .count()
+    }
+
+    /// 安全擦除选中的项目（覆写后删除）
+    pub fn secure_clean(
+        items: &[CleanableEntry],
+        passes: u32,
+        options: CleanOptions,
+    ) -> CleanResult {
+        let (tx, _rx) = mpsc::channel();
+        Self::secure_clean_with_progress(items, passes, options, &tx, &AtomicBool::new(false))
+    }
+
+    /// 安全擦除选中的项目，每处理完一项发送进度
+    pub fn secure_clean_with_progress(
+        items: &[CleanableEntry],
+        passes: u32,
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+    ) -> CleanResult {
+        Self::process_items(
+            items,
+            tx,
+            cancel,
+            true,
+            options,
+            ProgressRange::whole(items),
+            |item| {
+                Self::secure_delete(&item.path, passes, options.clear_flags)?;
+                Ok(true)
+            },
+        )
+    }
+
+    /// 以随机字节覆写文件内容 `passes` 遍后删除
+    ///
+    /// 仅覆写普通文件；目录递归处理其内容并保留目录本身，符号链接只删除链接本身。
+    /// 带不可变标志的文件无法覆写：遇到权限错误时才检查标志，按 `clear_flags` 清除后重试一次。
+    /// 存在其他硬链接的文件不覆写（否则会破坏经其他链接看到的数据），只删除链接，
+    /// 删除完成后以 `CleanError::HardLinked` 报告。
+    pub fn secure_delete(path: &Path, passes: u32, clear_flags: bool) -> Result<(), CleanError> {
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return Ok(());
+        };
+
+        // 返回因存在其他硬链接而跳过覆写的文件数
+        let overwrite = || -> std::io::Result<usize> {
+            let mut hard_linked = 0;
+            let mut overwrite_one = |file: &Path, metadata: &fs::Metadata| {
+                if has_other_hard_links(metadata) {
+                    hard_linked += 1;
+                    Ok(())
+                } else {
+                    Self::overwrite_file(file, passes)
+                }
+            };
+            if metadata.is_dir() {
+                for entry in WalkDir::new(path).follow_links(false).min_depth(1) {
+                    let entry = entry.map_err(std::io::Error::other)?;
+                    if entry.file_type().is_file() {
+                        let file_metadata = entry.metadata().map_err(std::io::Error::other)?;
+                        overwrite_one(entry.path(), &file_metadata)?;
+                    }
+                }
+            } else if metadata.is_file() {
+                overwrite_one(path, &metadata)?;
+            }
+            Ok(hard_linked)
+        };
+        let overwrite_and_remove = || -> std::io::Result<usize> {
+            let hard_linked = overwrite()?;
+            Self::remove_path(path)?;
+            Ok(hard_linked)
+        };
+        let hard_linked = match overwrite_and_remove() {
+            Err(error)
+                if error.kind() == std::io::ErrorKind::PermissionDenied
+                    && has_immutable_flags(path) =>
+            {
+                Self::unlock_immutable(path, clear_flags)?;
+                overwrite_and_remove()
+            }
+            result => result,
+        }
+        .map_err(|error| CleanError::from_io(path, &error))?;
+        if hard_linked > 0 {
+            return Err(CleanError::HardLinked {
+                path: path.to_path_buf(),
+                count: hard_linked,
+            });
+        }
+        Ok(())
+    }
+
+    /// 以随机字节覆写文件全部内容并落盘
+    ///
+    /// 随机字节来自 `fastrand`（非密码学安全的伪随机数），覆写只为替换原有内容，不依赖其不可预测性。
+    fn overwrite_file(path: &Path, passes: u32) -> std::io::Result<()> {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let len = fs::metadata(path)?.len();
+        let mut file = fs::OpenOptions::new().write(true).open(path)?;
+        let mut buffer = vec![0u8; SECURE_DELETE_CHUNK_SIZE];
+
+        for _ in 0..passes {
+            file.seek(SeekFrom::Start(0))?;
+            let mut remaining = len;
+            while remaining > 0 {
+                let chunk_len = remaining.min(SECURE_DELETE_CHUNK_SIZE as u64) as usize;
+                fastrand::fill(&mut buffer[..chunk_len]);
+                file.write_all(&buffer[..chunk_len])?;
+                remaining -= chunk_len as u64;
+            }
+            file.sync_data()?;
+        }
+
+        Ok(())
+    }
+
+    /// 将选中的项目移至系统回收站（使用默认清理选项）
     pub fn trash_items(items: &[CleanableEntry]) -> CleanResult {
-        Self::process_items(items, |item| {
+        let (tx, _rx) = mpsc::channel();
+        Self::trash_with_progress(items, CleanOptions::default(), &tx, &AtomicBool::new(false))
+    }
+
+    /// 将选中的项目移至系统回收站，每处理完一项发送进度
+    ///
+    /// 系统回收站 API 不保证并发安全，逐项顺序处理。
+    pub fn trash_with_progress(
+        items: &[CleanableEntry],
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+    ) -> CleanResult {
+        Self::trash_in_range(items, options, tx, cancel, ProgressRange::whole(items))
+    }
+
+    fn trash_in_range(
+        items: &[CleanableEntry],
+        options: CleanOptions,
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+        range: ProgressRange,
+    ) -> CleanResult {
+        let trashed_paths = Mutex::new(Vec::new());
+        let mut result = Self::process_items(items, tx, cancel, false, options, range, |item| {
             if !item.path.exists() {
                 return Ok(false);
             }
+            let mut trashed_paths = trashed_paths.lock().unwrap_or_else(|e| e.into_inner());
             if item.path.is_dir() {
-                Self::trash_dir_contents(&item.path)?;
+                Self::trash_dir_contents(&item.path, &mut trashed_paths)?;
                 return Ok(true);
             }
 
-            trash::delete(&item.path).map_err(|error| error.to_string())?;
+            trash::delete(&item.path)
+                .map_err(|error| CleanError::from_trash(&item.path, &error))?;
+            trashed_paths.push(item.path.clone());
             Ok(true)
-        })
+        });
+        result.trashed_paths = trashed_paths
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner());
+        result
+    }
+
+    /// 从系统回收站恢复指定原始路径的项目，返回恢复的数量
+    ///
+    /// 同一路径被多次移入回收站时恢复最近一次。
+    #[cfg(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    ))]
+    pub fn restore_from_trash(paths: &[PathBuf]) -> Result<usize, String> {
+        let trashed = trash::os_limited::list().map_err(|error| error.to_string())?;
+        Self::restore_latest(trashed, paths)
+    }
+
+    /// 从给定的回收站条目中恢复 `paths` 对应的最近一次删除（便于测试注入回收站目录）
+    #[cfg(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    ))]
+    fn restore_latest(trashed: Vec<trash::TrashItem>, paths: &[PathBuf]) -> Result<usize, String> {
+        let mut latest: std::collections::HashMap<PathBuf, trash::TrashItem> =
+            std::collections::HashMap::new();
+        for trash_item in trashed {
+            let original_path = trash_item.original_path();
+            if !paths.contains(&original_path) {
+                continue;
+            }
+            let is_newer = latest
+                .get(&original_path)
+                .is_none_or(|existing| existing.time_deleted < trash_item.time_deleted);
+            if is_newer {
+                latest.insert(original_path, trash_item);
+            }
+        }
+
+        if latest.is_empty() {
+            return Err("回收站中未找到可恢复的项目".to_string());
+        }
+        let restored_count = latest.len();
+        trash::os_limited::restore_all(latest.into_values()).map_err(|error| match error {
+            trash::Error::RestoreCollision { path, .. } => {
+                format!("恢复失败，目标位置已存在: {}", path.display())
+            }
+            other => other.to_string(),
+        })?;
+        Ok(restored_count)
     }
 
-    fn process_items<F>(items: &[CleanableEntry], mut action: F) -> CleanResult
+    /// 从系统回收站恢复指定原始路径的项目
+    ///
+    /// macOS 回收站不提供恢复 API：在 Finder 中打开回收站并返回提示，由用户手动“放回原处”。
+    #[cfg(not(any(
+        target_os = "windows",
+        all(
+            unix,
+            not(target_os = "macos"),
+            not(target_os = "ios"),
+            not(target_os = "android")
+        )
+    )))]
+    pub fn restore_from_trash(paths: &[PathBuf]) -> Result<usize, String> {
+        let opened = crate::utils::home_dir()
+            .map(|home| home.join(".Trash"))
+            .is_some_and(|trash_path| {
+                std::process::Command::new("open")
+                    .arg(trash_path)
+                    .status()
+                    .is_ok_and(|status| status.success())
+            });
+        let hint = if opened {
+            "已在 Finder 中打开回收站，请手动“放回原处”"
+        } else {
+            "请在 Finder 回收站中手动“放回原处”"
+        };
+        Err(format!(
+            "当前平台不支持自动从回收站恢复（{} 个项目）。{}",
+            paths.len(),
+            hint
+        ))
+    }
+
+    /// 逐项执行清理动作并汇总结果
+    ///
+    /// `parallel` 为 true 时使用 rayon 并行处理；结果按条目原始顺序汇总，
+    /// 保证错误信息与路径一一对应。动作返回暂时性错误时按 `options.delete_retries`
+    /// 的次数退避重试（删除与移至回收站都可重复执行，已处理的部分不会重复计算）；
+    /// 并行处理时重试推迟到全部条目处理完后在当前线程顺序进行，退避等待不占用 rayon 线程。
+    ///
+    /// `options.stop_on_error` 为 true 时，即使 `parallel` 为 true 也逐项顺序处理，任一条目失败后
+    /// 不再开始后续条目，结果中只有这一个错误。被跳过的条目不计入 `processed_paths`，也不视为取消。
+    fn process_items<F>(
+        items: &[CleanableEntry],
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+        parallel: bool,
+        options: CleanOptions,
+        range: ProgressRange,
+        action: F,
+    ) -> CleanResult
     where
-        F: FnMut(&CleanableEntry) -> Result<bool, String>,
+        F: Fn(&CleanableEntry) -> Result<bool, CleanError> + Sync,
     {
+        // 严格模式下并行处理会让已在进行中的条目在首个错误后继续删除
+        let parallel = parallel && !options.stop_on_error;
+        let completed = AtomicUsize::new(range.offset);
+        let failed = AtomicBool::new(false);
+        let inline_retries = if parallel { 0 } else { options.delete_retries };
+        let process_one = |item: &CleanableEntry| {
+            if cancel.load(Ordering::SeqCst) || failed.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            let outcome = retry_transient(inline_retries, action(item), || action(item));
+            if options.stop_on_error && outcome.is_err() {
+                failed.store(true, Ordering::SeqCst);
+            }
+            let _ = tx.send(CleanMessage::Progress {
+                completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                total: range.total,
+                path: item.path.clone(),
+                size: item.size.unwrap_or(0),
+            });
+            Some(outcome)
+        };
+
+        let mut outcomes: Vec<Option<Result<bool, CleanError>>> = if parallel {
+            items.par_iter().map(process_one).collect()
+        } else {
+            items.iter().map(process_one).collect()
+        };
+        if parallel {
+            for (item, outcome) in items.iter().zip(outcomes.iter_mut()) {
+                if let Some(Err(error)) = outcome
+                    && error.is_transient()
+                    && !cancel.load(Ordering::SeqCst)
+                {
+                    let first = Err(error.clone());
+                    *outcome = Some(retry_transient(options.delete_retries, first, || {
+                        action(item)
+                    }));
+                }
+            }
+        }
+
         let mut freed_space = 0u64;
         let mut errors = Vec::new();
-
-        for item in items {
-            match action(item) {
-                Ok(should_add_freed_space) => {
+        let mut processed_paths = Vec::new();
+        let mut cancelled = false;
+
+        for (item, outcome) in items.iter().zip(outcomes) {
+            match outcome {
+                // 严格模式因错误跳过的条目不算取消
+                None => cancelled |= !failed.load(Ordering::SeqCst),
+                Some(Ok(should_add_freed_space)) => {
                     if should_add_freed_space {
                         freed_space += item.size.unwrap_or(0);
                     }
+                    processed_paths.push(item.path.clone());
                 }
-                Err(error_message) => {
-                    errors.push(Self::format_item_error(&item.path, &error_message))
+                Some(Err(error)) => {
+                    errors.push(error);
+                    processed_paths.push(item.path.clone());
                 }
             }
         }
@@ -97,71 +1025,131 @@ impl Cleaner {
             success: errors.is_empty(),
             freed_space,
             errors,
+            trashed_paths: Vec::new(),
+            processed_paths,
+            cancelled,
         }
     }
 
-    fn format_item_error(path: &Path, error_message: &str) -> String {
-        format!("{}: {}", path.display(), error_message)
-    }
-
     /// 将目录内容移至回收站，保留目录结构本身
-    fn trash_dir_contents(path: &Path) -> Result<(), String> {
+    fn trash_dir_contents(path: &Path, trashed_paths: &mut Vec<PathBuf>) -> Result<(), CleanError> {
         let entries: Vec<_> = std::fs::read_dir(path)
-            .map_err(|e| e.to_string())?
+            .map_err(|e| CleanError::from_io(path, &e))?
             .filter_map(|e| e.ok())
             .collect();
 
         let mut errors = Vec::new();
+        let mut all_transient = true;
         for entry in entries {
-            if let Err(e) = trash::delete(entry.path()) {
-                errors.push(format!("{}: {}", entry.path().display(), e));
+            match trash::delete(entry.path()) {
+                Ok(()) => trashed_paths.push(entry.path()),
+                Err(e) => {
+                    all_transient &= CleanError::from_trash(&entry.path(), &e).is_transient();
+                    errors.push(format!("{}: {}", entry.path().display(), e));
+                }
             }
         }
 
         if errors.is_empty() {
             Ok(())
+        } else if all_transient {
+            // 只剩被占用的子项时整体重试，已移走的子项不会再次出现
+            Err(CleanError::Busy {
+                path: path.to_path_buf(),
+            })
         } else {
-            Err(errors.join("; "))
+            Err(CleanError::Io {
+                path: path.to_path_buf(),
+                message: errors.join("; "),
+            })
         }
     }
 
-    /// 模拟删除，统计将要删除的文件数、目录数和大小
-    pub fn dry_run(items: &[CleanableEntry]) -> DryRunResult {
-        let mut total_files = 0usize;
-        let mut total_dirs = 0usize;
-        let mut total_size = 0u64;
-        let mut dry_run_items = Vec::new();
+    /// 估算全部清理后可回收的空间：跳过未通过安全检查的条目，返回 (条目数, 字节数)
+    pub fn reclaimable_estimate(items: &[CleanableEntry], rules: &SafetyRules) -> (usize, u64) {
+        items
+            .iter()
+            .filter(|item| Self::is_safe_to_delete(&item.path, rules))
+            .fold((0, 0), |(count, size), item| {
+                (count + 1, size + item.size.unwrap_or(0))
+            })
+    }
 
+    /// 模拟删除，统计将要删除的文件数、目录数和大小，并区分实际删除与保留/跳过的部分
+    pub fn dry_run(items: &[CleanableEntry], rules: &SafetyRules) -> DryRunResult {
+        let mut result = DryRunResult::default();
         for item in items {
-            let (file_count, dir_count, size) = Self::count_path_contents(&item.path);
-            total_files += file_count;
-            total_dirs += dir_count;
-            total_size += size;
-            dry_run_items.push(DryRunItem {
-                path: item.path.clone(),
-                file_count,
-                dir_count,
-                size,
-            });
+            result.push(Self::dry_run_item(item, rules));
         }
+        result
+    }
 
-        DryRunResult {
-            total_files,
-            total_dirs,
-            total_size,
-            items: dry_run_items,
+    /// 逐项统计并通过通道发送结果，全部完成后发送 `Done`
+    ///
+    /// 接收端已关闭（如确认弹窗被取消）时提前停止遍历。返回成功发送的条目数。
+    pub fn dry_run_streaming(
+        items: &[CleanableEntry],
+        rules: &SafetyRules,
+        job_id: u64,
+        tx: &Sender<DryRunMessage>,
+    ) -> usize {
+        for (sent, item) in items.iter().enumerate() {
+            let item = Self::dry_run_item(item, rules);
+            if tx.send(DryRunMessage::Item { job_id, item }).is_err() {
+                return sent;
+            }
         }
+        let _ = tx.send(DryRunMessage::Done { job_id });
+        items.len()
     }
 
-    /// 统计路径下的文件数、目录数和总大小
-    fn count_path_contents(path: &Path) -> (usize, usize, u64) {
-        if !path.exists() {
-            return (0, 0, 0);
+    /// 统计单个条目的文件数、目录数和总大小
+    fn dry_run_item(item: &CleanableEntry, rules: &SafetyRules) -> DryRunItem {
+        let (file_count, dir_count, size) = Self::count_path_contents(&item.path);
+        // 清理目录时只删除其内容，目录本身占用的空间不会释放
+        let kept_dir_size = if item.path.is_dir() {
+            item.path.metadata().map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        let skipped = !Self::is_safe_to_delete(&item.path, rules);
+        let (removable_size, retained_size) = if skipped {
+            (0, size + kept_dir_size)
+        } else {
+            (size, kept_dir_size)
+        };
+        DryRunItem {
+            path: item.path.clone(),
+            file_count,
+            dir_count,
+            size,
+            removable_size,
+            retained_size,
+            skipped,
         }
+    }
+
+    /// 统计路径下的文件数、目录数和总大小（符号链接按一个文件计，不统计其目标）
+    pub fn count_path_contents(path: &Path) -> (usize, usize, u64) {
+        Self::count_path_contents_until(path, || false).unwrap_or_default()
+    }
 
-        if path.is_file() {
-            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
-            return (1, 0, size);
+    /// 同 `count_path_contents()`，遍历中每项检查 `cancelled`，返回 true 时提前停止并返回 None
+    pub fn count_path_contents_until(
+        path: &Path,
+        cancelled: impl Fn() -> bool,
+    ) -> Option<(usize, usize, u64)> {
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            return Some((0, 0, 0));
+        };
+
+        if !metadata.is_dir() {
+            let size = if metadata.is_file() {
+                metadata.len()
+            } else {
+                0
+            };
+            return Some((1, 0, size));
         }
 
         let mut file_count = 0usize;
@@ -169,6 +1157,9 @@ impl Cleaner {
         let mut size = 0u64;
 
         for entry in WalkDir::new(path).follow_links(false).into_iter() {
+            if cancelled() {
+                return None;
+            }
             let entry = match entry {
                 Ok(e) => e,
                 Err(_) => continue,
@@ -187,22 +1178,26 @@ impl Cleaner {
             }
         }
 
-        (file_count, dir_count, size)
+        Some((file_count, dir_count, size))
     }
 
     /// 删除指定路径（文件或目录）
+    ///
+    /// 不跟随符号链接：路径本身是链接时只删除链接，目录中的链接同样只删除链接本身。
     fn remove_path(path: &Path) -> std::io::Result<()> {
-        if !path.exists() {
-            return Ok(());
-        }
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error),
+        };
 
-        if path.is_dir() {
+        if metadata.is_dir() {
             // 遍历目录内容并删除，保留目录本身
             for entry in fs::read_dir(path)? {
                 let entry = entry?;
                 let entry_path = entry.path();
 
-                if entry_path.is_dir() {
+                if entry.file_type()?.is_dir() {
                     fs::remove_dir_all(&entry_path)?;
                 } else {
                     fs::remove_file(&entry_path)?;
@@ -215,56 +1210,176 @@ impl Cleaner {
         Ok(())
     }
 
-    /// 清空垃圾桶
-    pub fn empty_trash() -> std::io::Result<u64> {
-        let home = directories::UserDirs::new()
-            .map(|d| d.home_dir().to_path_buf())
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "无法获取用户目录"))?;
-
-        let trash_path = home.join(".Trash");
-        let mut freed = 0u64;
-
-        if trash_path.exists() {
-            for entry in fs::read_dir(&trash_path)? {
-                let entry = entry?;
-                let metadata = entry.metadata()?;
-                freed += metadata.len();
-
-                let path = entry.path();
-                if path.is_dir() {
-                    fs::remove_dir_all(&path)?;
-                } else {
-                    fs::remove_file(&path)?;
-                }
+    /// 删除指定路径；因不可变标志 (uchg) 导致权限错误时，按 `clear_flags` 清除标志后重试一次
+    fn remove_path_clearing_flags(path: &Path, clear_flags: bool) -> Result<(), CleanError> {
+        match Self::remove_path(path) {
+            Err(error)
+                if error.kind() == std::io::ErrorKind::PermissionDenied
+                    && has_immutable_flags(path) =>
+            {
+                Self::unlock_immutable(path, clear_flags)?;
+                Self::remove_path(path).map_err(|error| CleanError::from_io(path, &error))
             }
+            result => result.map_err(|error| CleanError::from_io(path, &error)),
         }
-
-        Ok(freed)
     }
 
-    /// 安全检查：确保路径可以安全删除
+    /// 清除路径（含子项）上的不可变标志；未启用 `clear_flags` 时返回 `CleanError::Immutable`
+    fn unlock_immutable(path: &Path, clear_flags: bool) -> Result<(), CleanError> {
+        if !clear_flags {
+            return Err(CleanError::Immutable {
+                path: path.to_path_buf(),
+            });
+        }
+        clear_immutable_flags(path).map_err(|error| CleanError::Io {
+            path: path.to_path_buf(),
+            message: format!("无法清除不可变标志 (uchg): {}", error),
+        })
+    }
+
+    /// 用户回收站目录（~/.Trash）
+    pub fn trash_dir() -> Option<PathBuf> {
+        crate::utils::home_dir().map(|home| home.join(".Trash"))
+    }
+
+    /// 回收站中文件的总大小；回收站目录不存在（或无法获取用户目录）时为 None
+    pub fn trash_size() -> Option<u64> {
+        Self::trash_dir().and_then(|path| Self::existing_dir_size(&path))
+    }
+
+    /// 目录下文件的总大小，路径不是目录时为 None
+    fn existing_dir_size(path: &Path) -> Option<u64> {
+        path.is_dir().then(|| Self::count_path_contents(path).2)
+    }
+
+    /// 清空垃圾桶（永久删除），返回 (释放空间, 删除的项目数)
+    pub fn empty_trash() -> CleanResult {
+        let (tx, _rx) = mpsc::channel();
+        Self::empty_trash_with_progress(&tx, &AtomicBool::new(false))
+    }
+
+    /// 永久清空回收站，每删除一项发送进度；`cancel` 置位后尚未开始的条目被跳过
+    pub fn empty_trash_with_progress(
+        tx: &Sender<CleanMessage>,
+        cancel: &AtomicBool,
+    ) -> CleanResult {
+        match Self::trash_dir() {
+            Some(trash_path) => Self::empty_dir(&trash_path, tx, cancel),
+            None => CleanResult::from_error(CleanError::Io {
+                path: PathBuf::new(),
+                message: "无法获取用户目录".to_string(),
+            }),
+        }
+    }
+
+    /// 永久删除目录下的全部内容，保留目录本身
+    ///
+    /// 单项删除失败时记录错误并继续处理其余条目，`freed_space` 与 `succeeded_count()` 只含已删除的部分。
+    fn empty_dir(path: &Path, tx: &Sender<CleanMessage>, cancel: &AtomicBool) -> CleanResult {
+        let entries: Vec<fs::DirEntry> = match fs::read_dir(path) {
+            Ok(entries) => entries.filter_map(Result::ok).collect(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return CleanResult::from_error(CleanError::from_io(path, &error)),
+        };
+        let items: Vec<CleanableEntry> = entries
+            .into_iter()
+            .map(|entry| {
+                let entry_path = entry.path();
+                let size =
+                    Self::count_path_contents_until(&entry_path, || cancel.load(Ordering::SeqCst))
+                        .map(|(_, _, size)| size);
+                let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+                CleanableEntry {
+                    kind: if is_dir {
+                        EntryKind::Directory
+                    } else {
+                        EntryKind::File
+                    },
+                    category: None,
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    path: entry_path,
+                    size,
+                    modified_at: None,
+                    file_count: None,
+                }
+            })
+            .collect();
+
+        Self::process_items(
+            &items,
+            tx,
+            cancel,
+            true,
+            CleanOptions::default(),
+            ProgressRange::whole(&items),
+            |item| {
+                let removed = if item.kind == EntryKind::Directory {
+                    fs::remove_dir_all(&item.path)
+                } else {
+                    fs::remove_file(&item.path)
+                };
+                removed
+                    .map(|()| true)
+                    .map_err(|error| CleanError::from_io(&item.path, &error))
+            },
+        )
+    }
+
+    /// 安全检查：确保路径可以安全删除
     ///
     /// 使用 canonicalize 解析符号链接，防止通过符号链接绕过安全检查。
-    /// 禁止删除系统关键目录和用户根目录本身。
-    pub fn is_safe_to_delete(path: &Path) -> bool {
+    /// 禁止删除系统关键目录、用户根目录本身以及 `rules.forbidden_extra`、`rules.own_data` 及其子路径；
+    /// 清理目录会清空其下全部内容，因此包含已存在的 `forbidden_extra` 路径的上级目录同样禁止
+    /// （包含自身数据的上级目录允许清理，由 `without_own_data()` 跳过其中的自身数据）；
+    /// 除用户目录与临时目录外，还允许 `rules.allowed_roots`（如外置磁盘上的缓存目录）之下的路径。
+    pub fn is_safe_to_delete(path: &Path, rules: &SafetyRules) -> bool {
         // 规范化路径，解析符号链接
         let canonical = match path.canonicalize() {
             Ok(p) => p,
             Err(_) => return false,
         };
 
-        let path_str = canonical.to_string_lossy();
+        // 允许根目录同样需要规范化；无法解析的根目录直接忽略
+        let canonical_roots: Vec<PathBuf> = rules
+            .allowed_roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+        // 额外禁止路径无法解析时（如尚不存在）按原样拦截其下路径；其中没有可保护的内容，不拦截上级目录
+        let canonical_forbidden: Vec<(PathBuf, bool)> = rules
+            .forbidden_extra
+            .iter()
+            .map(|forbidden| match forbidden.canonicalize() {
+                Ok(resolved) => (resolved, true),
+                Err(_) => (forbidden.clone(), false),
+            })
+            .collect();
+        let home = crate::utils::home_dir();
 
+        if rules.is_own_data(&canonical)
+            || canonical_forbidden.iter().any(|(forbidden, exists)| {
+                canonical.starts_with(forbidden) || (*exists && forbidden.starts_with(&canonical))
+            })
+        {
+            return false;
+        }
+
+        Self::is_allowed_location(&canonical, home.as_deref(), &canonical_roots)
+    }
+
+    /// 判断已规范化的路径是否位于允许清理的位置
+    fn is_allowed_location(
+        canonical: &Path,
+        home: Option<&Path>,
+        allowed_roots: &[PathBuf],
+    ) -> bool {
         // 检查是否为禁止路径
-        for f in FORBIDDEN_PATHS {
-            if path_str == *f {
-                return false;
-            }
+        if Self::is_forbidden(canonical) {
+            return false;
         }
 
         // 确保路径在用户目录下或临时目录下
-        if let Some(home) = directories::UserDirs::new() {
-            let home_path = home.home_dir();
+        if let Some(home_path) = home {
             // 不允许删除用户根目录本身
             if canonical == home_path {
                 return false;
@@ -282,7 +1397,17 @@ impl Cleaner {
             return true;
         }
 
-        false
+        // 配置的允许根目录：禁止路径本身不能作为根目录，也不允许删除根目录本身
+        allowed_roots.iter().any(|root| {
+            !Self::is_forbidden(root) && canonical != root && canonical.starts_with(root)
+        })
+    }
+
+    fn is_forbidden(path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        FORBIDDEN_PATHS
+            .iter()
+            .any(|forbidden| path_str == *forbidden)
     }
 }
 
@@ -292,6 +1417,119 @@ impl Default for Cleaner {
     }
 }
 
+/// 首次执行结果为暂时性错误时，最多退避重试 `retries` 次，返回最后一次的结果
+fn retry_transient<T>(
+    retries: u32,
+    first: Result<T, CleanError>,
+    mut action: impl FnMut() -> Result<T, CleanError>,
+) -> Result<T, CleanError> {
+    let mut outcome = first;
+    for attempt in 1..=retries {
+        match outcome {
+            Err(error) if error.is_transient() => {
+                std::thread::sleep(DELETE_RETRY_BASE_DELAY * attempt);
+                outcome = action();
+            }
+            _ => break,
+        }
+    }
+    outcome
+}
+
+/// 文件是否还有其他硬链接（非 Unix 平台无法判断，视为没有）
+fn has_other_hard_links(metadata: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.nlink() > 1
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        false
+    }
+}
+
+/// 路径实际占用的磁盘空间（按已分配块统计，与 `df` 的变化一致；不跟随符号链接，路径不存在时为 0）
+fn allocated_size(path: &Path) -> u64 {
+    #[cfg(unix)]
+    use std::os::unix::fs::MetadataExt;
+
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|meta| {
+            #[cfg(unix)]
+            {
+                meta.blocks() * 512
+            }
+            #[cfg(not(unix))]
+            {
+                meta.len()
+            }
+        })
+        .sum()
+}
+
+/// macOS 上会阻止删除的文件标志（uchg / uappnd）
+#[cfg(target_os = "macos")]
+const IMMUTABLE_FLAGS: u32 = libc::UF_IMMUTABLE | libc::UF_APPEND;
+
+/// 路径本身或其子项是否带有不可变标志
+#[cfg(target_os = "macos")]
+fn has_immutable_flags(path: &Path) -> bool {
+    use std::os::macos::fs::MetadataExt;
+
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .any(|metadata| metadata.st_flags() & IMMUTABLE_FLAGS != 0)
+}
+
+/// 通过 chflags 清除路径本身及其子项上的不可变标志
+///
+/// chflags 会跟随符号链接，因此跳过链接本身，避免修改链接目标。
+#[cfg(target_os = "macos")]
+fn clear_immutable_flags(path: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::macos::fs::MetadataExt;
+    use std::os::unix::ffi::OsStrExt;
+
+    for entry in WalkDir::new(path).follow_links(false) {
+        let entry = entry.map_err(std::io::Error::other)?;
+        if entry.file_type().is_symlink() {
+            continue;
+        }
+        let flags = entry.metadata().map_err(std::io::Error::other)?.st_flags();
+        if flags & IMMUTABLE_FLAGS == 0 {
+            continue;
+        }
+
+        let c_path = CString::new(entry.path().as_os_str().as_bytes())?;
+        // SAFETY: c_path 是以 NUL 结尾的有效路径，调用期间保持存活
+        let status = unsafe { libc::chflags(c_path.as_ptr(), flags & !IMMUTABLE_FLAGS) };
+        if status != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// 非 macOS 平台没有 BSD 文件标志
+#[cfg(not(target_os = "macos"))]
+fn has_immutable_flags(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clear_immutable_flags(_path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,28 +1545,36 @@ mod tests {
             name: "item".to_string(),
             size,
             modified_at: None,
+            file_count: None,
         }
     }
 
     #[test]
     fn is_safe_to_delete_rejects_forbidden_paths() {
         for path in FORBIDDEN_PATHS {
-            assert!(!Cleaner::is_safe_to_delete(Path::new(path)));
+            assert!(!Cleaner::is_safe_to_delete(
+                Path::new(path),
+                &SafetyRules::default()
+            ));
         }
     }
 
     #[test]
     fn is_safe_to_delete_rejects_user_home_directory() {
         if let Some(home) = directories::UserDirs::new() {
-            assert!(!Cleaner::is_safe_to_delete(home.home_dir()));
+            assert!(!Cleaner::is_safe_to_delete(
+                home.home_dir(),
+                &SafetyRules::default()
+            ));
         }
     }
 
     #[test]
     fn is_safe_to_delete_rejects_nonexistent_paths() {
-        assert!(!Cleaner::is_safe_to_delete(Path::new(
-            "/tmp/vac-nonexistent-path-12345"
-        )));
+        assert!(!Cleaner::is_safe_to_delete(
+            Path::new("/tmp/vac-nonexistent-path-12345"),
+            &SafetyRules::default()
+        ));
     }
 
     #[test]
@@ -337,7 +1583,86 @@ mod tests {
             .prefix("vac-test-")
             .tempdir_in("/tmp")
             .expect("create temp dir");
-        assert!(Cleaner::is_safe_to_delete(dir.path()));
+        assert!(Cleaner::is_safe_to_delete(
+            dir.path(),
+            &SafetyRules::default()
+        ));
+    }
+
+    #[test]
+    fn is_allowed_location_accepts_paths_under_allowed_external_root() {
+        let allowed_roots = vec![PathBuf::from("/Volumes/Dev")];
+
+        assert!(Cleaner::is_allowed_location(
+            Path::new("/Volumes/Dev/project/target"),
+            None,
+            &allowed_roots
+        ));
+        // 根目录本身和其他外置卷仍被拒绝
+        assert!(!Cleaner::is_allowed_location(
+            Path::new("/Volumes/Dev"),
+            None,
+            &allowed_roots
+        ));
+        assert!(!Cleaner::is_allowed_location(
+            Path::new("/Volumes/Other/cache"),
+            None,
+            &allowed_roots
+        ));
+    }
+
+    #[test]
+    fn is_safe_to_delete_ignores_forbidden_allowed_roots() {
+        let rules = SafetyRules {
+            allowed_roots: vec![PathBuf::from("/usr"), PathBuf::from("/")],
+            ..SafetyRules::default()
+        };
+
+        assert!(!Cleaner::is_safe_to_delete(Path::new("/usr"), &rules));
+        assert!(!Cleaner::is_safe_to_delete(Path::new("/usr/bin"), &rules));
+    }
+
+    #[test]
+    fn is_safe_to_delete_rejects_forbidden_extra_and_children() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-forbidden-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let protected = dir.path().join("Dropbox");
+        let protected_child = protected.join("photos");
+        let unrelated = dir.path().join("cache");
+        fs::create_dir_all(&protected_child).expect("create protected dirs");
+        fs::create_dir(&unrelated).expect("create unrelated dir");
+
+        let rules = SafetyRules {
+            forbidden_extra: vec![protected.clone()],
+            ..SafetyRules::default()
+        };
+
+        assert!(!Cleaner::is_safe_to_delete(&protected, &rules));
+        assert!(!Cleaner::is_safe_to_delete(&protected_child, &rules));
+        assert!(Cleaner::is_safe_to_delete(&unrelated, &rules));
+    }
+
+    #[test]
+    fn is_safe_to_delete_rejects_ancestor_of_forbidden_extra() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-forbidden-parent-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let documents = dir.path().join("Documents");
+        let taxes = documents.join("taxes");
+        let sibling = documents.join("drafts");
+        fs::create_dir_all(&taxes).expect("create protected dir");
+        fs::create_dir(&sibling).expect("create sibling dir");
+
+        let rules = SafetyRules {
+            forbidden_extra: vec![taxes.clone(), dir.path().join("missing")],
+            ..SafetyRules::default()
+        };
+
+        assert!(!Cleaner::is_safe_to_delete(&documents, &rules));
+        assert!(Cleaner::is_safe_to_delete(&sibling, &rules));
     }
 
     #[test]
@@ -358,7 +1683,7 @@ mod tests {
         let file_item = item(file_path.clone(), Some(5));
         let dir_item = item(dir_path.clone(), Some(5));
 
-        let result = Cleaner::clean(&[file_item, dir_item]);
+        let result = Cleaner::clean(&[file_item, dir_item], CleanOptions::default());
 
         assert!(result.success);
         assert!(!file_path.exists());
@@ -366,6 +1691,303 @@ mod tests {
         assert_eq!(fs::read_dir(&dir_path).unwrap().count(), 0);
     }
 
+    #[test]
+    fn clean_with_progress_reports_each_item() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-progress-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|index| dir.path().join(format!("file-{index}.txt")))
+            .collect();
+        for path in &paths {
+            fs::write(path, b"data").expect("write file");
+        }
+        let items: Vec<CleanableEntry> = paths.iter().map(|p| item(p.clone(), Some(4))).collect();
+
+        let (tx, rx) = mpsc::channel();
+        let result = Cleaner::clean_with_progress(
+            &items,
+            CleanOptions::default(),
+            &tx,
+            &AtomicBool::new(false),
+        );
+
+        assert!(result.success);
+        assert!(!result.cancelled);
+        assert_eq!(result.processed_paths.len(), 3);
+        assert_eq!(result.freed_space, 12);
+        let mut completed: Vec<(usize, usize)> = rx
+            .try_iter()
+            .map(|message| match message {
+                CleanMessage::Progress {
+                    completed, total, ..
+                } => (completed, total),
+                CleanMessage::Done(_) => panic!("unexpected done message"),
+            })
+            .collect();
+        completed.sort_unstable();
+        assert_eq!(completed, vec![(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[test]
+    fn clean_with_progress_stops_when_cancelled() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-cancel-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let file_path = dir.path().join("keep.txt");
+        fs::write(&file_path, b"data").expect("write file");
+
+        let (tx, rx) = mpsc::channel();
+        let result = Cleaner::clean_with_progress(
+            &[item(file_path.clone(), Some(4))],
+            CleanOptions::default(),
+            &tx,
+            &AtomicBool::new(true),
+        );
+
+        assert!(result.cancelled);
+        assert!(result.processed_paths.is_empty());
+        assert_eq!(result.freed_space, 0);
+        assert!(file_path.exists());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn clean_removes_many_directories_in_parallel() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-parallel-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let dir_paths: Vec<PathBuf> = (0..16)
+            .map(|index| {
+                let dir_path = dir.path().join(format!("cache-{index}"));
+                fs::create_dir(&dir_path).expect("create dir");
+                for file_index in 0..4 {
+                    fs::write(dir_path.join(format!("{file_index}.bin")), b"data")
+                        .expect("write file");
+                }
+                dir_path
+            })
+            .collect();
+        let items: Vec<CleanableEntry> = dir_paths
+            .iter()
+            .map(|p| item(p.clone(), Some(16)))
+            .collect();
+
+        let result = Cleaner::clean(&items, CleanOptions::default());
+
+        assert!(result.success);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.freed_space, 16 * 16);
+        assert_eq!(result.processed_paths, dir_paths);
+        for dir_path in &dir_paths {
+            assert_eq!(fs::read_dir(dir_path).unwrap().count(), 0);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clean_reports_errors_paired_with_failing_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-errors-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let ok_dir = dir.path().join("ok");
+        fs::create_dir(&ok_dir).expect("create dir");
+        fs::write(ok_dir.join("file.txt"), b"data").expect("write file");
+
+        // 只读父目录中的文件无法删除
+        let locked_dir = dir.path().join("locked");
+        fs::create_dir(&locked_dir).expect("create dir");
+        let locked_file = locked_dir.join("file.txt");
+        fs::write(&locked_file, b"data").expect("write file");
+        fs::set_permissions(&locked_dir, fs::Permissions::from_mode(0o555))
+            .expect("set permissions");
+
+        // root 用户不受权限限制，无法构造删除失败
+        if fs::remove_file(&locked_file).is_ok() {
+            return;
+        }
+
+        let result = Cleaner::clean(
+            &[
+                item(ok_dir.clone(), Some(4)),
+                item(locked_dir.clone(), Some(4)),
+            ],
+            CleanOptions::default(),
+        );
+        fs::set_permissions(&locked_dir, fs::Permissions::from_mode(0o755))
+            .expect("restore permissions");
+
+        assert!(!result.success);
+        assert_eq!(result.freed_space, 4);
+        assert_eq!(result.errors.len(), 1);
+        assert!(matches!(
+            &result.errors[0],
+            CleanError::PermissionDenied { path } if path == &locked_dir
+        ));
+    }
+
+    #[test]
+    fn secure_delete_overwrites_and_removes_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-secure-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let file_path = dir.path().join("secret.txt");
+        fs::write(&file_path, b"top secret").expect("write file");
+
+        Cleaner::secure_delete(&file_path, SECURE_DELETE_PASSES, false).expect("secure delete");
+
+        assert!(!file_path.exists());
+    }
+
+    #[test]
+    fn secure_delete_unlinks_hard_linked_files_without_overwriting() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-secure-link-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let folder = dir.path().join("folder");
+        fs::create_dir(&folder).expect("create dir");
+        fs::write(folder.join("shared"), b"keep me").expect("write file");
+        fs::write(folder.join("own"), b"secret").expect("write file");
+        let other_link = dir.path().join("other-link");
+        fs::hard_link(folder.join("shared"), &other_link).expect("hard link");
+
+        let result = Cleaner::secure_delete(&folder, 1, false);
+
+        assert_eq!(
+            result,
+            Err(CleanError::HardLinked {
+                path: folder.clone(),
+                count: 1
+            })
+        );
+        assert!(!folder.join("shared").exists());
+        assert!(!folder.join("own").exists());
+        assert_eq!(fs::read(&other_link).expect("read"), b"keep me");
+    }
+
+    #[test]
+    fn secure_clean_keeps_dir_and_removes_nested_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-secure-dir-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let dir_path = dir.path().join("folder");
+        fs::create_dir_all(dir_path.join("nested")).expect("create dirs");
+        fs::write(dir_path.join("a.txt"), b"aaaa").expect("write file");
+        fs::write(dir_path.join("nested").join("b.txt"), b"bbbb").expect("write file");
+
+        let result = Cleaner::secure_clean(
+            &[item(dir_path.clone(), Some(8))],
+            1,
+            CleanOptions::default(),
+        );
+
+        assert!(result.success);
+        assert_eq!(result.freed_space, 8);
+        assert!(dir_path.exists());
+        assert_eq!(fs::read_dir(&dir_path).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn clean_error_from_io_classifies_kinds() {
+        let path = Path::new("/tmp/vac-error");
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let read_only = std::io::Error::from(std::io::ErrorKind::ReadOnlyFilesystem);
+        let other = std::io::Error::other("boom");
+
+        assert!(matches!(
+            CleanError::from_io(path, &denied),
+            CleanError::PermissionDenied { .. }
+        ));
+        assert!(matches!(
+            CleanError::from_io(path, &missing),
+            CleanError::NotFound { .. }
+        ));
+        assert!(matches!(
+            CleanError::from_io(path, &read_only),
+            CleanError::ReadOnly { .. }
+        ));
+        let busy = CleanError::from_io(
+            path,
+            &std::io::Error::from(std::io::ErrorKind::ResourceBusy),
+        );
+        assert!(busy.is_transient());
+        assert!(!CleanError::from_io(path, &denied).is_transient());
+        assert_eq!(
+            CleanError::from_io(path, &other),
+            CleanError::Io {
+                path: path.to_path_buf(),
+                message: "boom".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn clean_error_display_and_serialize_include_path_and_kind() {
+        let error = CleanError::PermissionDenied {
+            path: PathBuf::from("/tmp/locked"),
+        };
+        assert_eq!(error.to_string(), "/tmp/locked: 权限不足");
+
+        let json = serde_json::to_value(&error).expect("serialize error");
+        assert_eq!(json["kind"], "permission_denied");
+        assert_eq!(json["path"], "/tmp/locked");
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn clean_clears_uchg_flag_only_when_enabled() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-uchg-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let file_path = dir.path().join("locked.txt");
+        fs::write(&file_path, b"data").expect("write file");
+        let status = std::process::Command::new("chflags")
+            .arg("uchg")
+            .arg(&file_path)
+            .status()
+            .expect("run chflags");
+        assert!(status.success());
+        assert!(has_immutable_flags(&file_path));
+
+        let result = Cleaner::clean(&[item(file_path.clone(), Some(4))], CleanOptions::default());
+        assert!(!result.success);
+        assert_eq!(
+            result.errors,
+            vec![CleanError::Immutable {
+                path: file_path.clone()
+            }]
+        );
+        assert!(file_path.exists());
+
+        let result = Cleaner::clean(
+            &[item(file_path.clone(), Some(4))],
+            CleanOptions {
+                clear_flags: true,
+                ..CleanOptions::default()
+            },
+        );
+        assert!(result.success);
+        assert!(!file_path.exists());
+    }
+
     #[test]
     fn trash_items_moves_files_to_trash() {
         let dir = tempfile::Builder::new()
@@ -383,10 +2005,12 @@ mod tests {
             name: "trash_me.txt".to_string(),
             size: Some(10),
             modified_at: None,
+            file_count: None,
         };
 
         let result = Cleaner::trash_items(&[file_item]);
         assert!(result.success);
+        assert_eq!(result.trashed_paths, vec![file_path.clone()]);
         assert!(!file_path.exists());
     }
 
@@ -407,6 +2031,7 @@ mod tests {
             name: "test-dir".to_string(),
             size: Some(5),
             modified_at: None,
+            file_count: None,
         };
 
         let result = Cleaner::trash_items(&[dir_item]);
@@ -416,6 +2041,49 @@ mod tests {
         assert!(!file_a.exists());
     }
 
+    #[cfg(all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    ))]
+    #[test]
+    fn restore_latest_restores_most_recent_deletion() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-restore-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        // 在临时目录中按 freedesktop 规范构造回收站，不触碰用户的真实回收站
+        let trash_dir = dir.path().join("Trash");
+        fs::create_dir_all(trash_dir.join("files")).expect("create files dir");
+        fs::create_dir_all(trash_dir.join("info")).expect("create info dir");
+        let original_parent = dir.path().join("origin");
+        let trashed_item = |name_in_trash: &str, content: &[u8], time_deleted: i64| {
+            fs::write(trash_dir.join("files").join(name_in_trash), content).expect("write file");
+            let info_path = trash_dir
+                .join("info")
+                .join(format!("{name_in_trash}.trashinfo"));
+            fs::write(&info_path, "[Trash Info]\n").expect("write info");
+            trash::TrashItem {
+                id: info_path.into_os_string(),
+                name: "restore_me.txt".into(),
+                original_parent: original_parent.clone(),
+                time_deleted,
+            }
+        };
+        let trashed = vec![
+            trashed_item("restore_me.txt", b"old", 1),
+            trashed_item("restore_me.2.txt", b"new", 2),
+        ];
+
+        let file_path = original_parent.join("restore_me.txt");
+        let restored =
+            Cleaner::restore_latest(trashed, std::slice::from_ref(&file_path)).expect("restore");
+        assert_eq!(restored, 1);
+        assert_eq!(fs::read(&file_path).expect("read restored"), b"new");
+        assert!(trash_dir.join("files/restore_me.txt").exists());
+    }
+
     #[test]
     fn trash_items_skips_nonexistent_paths() {
         let item = CleanableEntry {
@@ -425,6 +2093,7 @@ mod tests {
             name: "nonexistent".to_string(),
             size: Some(0),
             modified_at: None,
+            file_count: None,
         };
 
         let result = Cleaner::trash_items(&[item]);
@@ -432,6 +2101,29 @@ mod tests {
         assert_eq!(result.freed_space, 0);
     }
 
+    #[test]
+    fn empty_dir_removes_contents_and_reports_freed_space() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-empty-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        fs::write(dir.path().join("a.txt"), b"hello").expect("write file a");
+        let sub_dir = dir.path().join("sub");
+        fs::create_dir(&sub_dir).expect("create sub dir");
+        fs::write(sub_dir.join("b.txt"), vec![0u8; 10]).expect("write file b");
+
+        let (tx, rx) = mpsc::channel();
+        let result = Cleaner::empty_dir(dir.path(), &tx, &AtomicBool::new(false));
+        drop(tx);
+        assert!(result.success);
+        assert_eq!(result.freed_space, 15);
+        assert_eq!(result.succeeded_count(), 2);
+        assert_eq!(rx.iter().count(), 2);
+        assert!(dir.path().exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn dry_run_counts_correctly() {
         let dir = tempfile::Builder::new()
@@ -458,9 +2150,10 @@ mod tests {
             name: "test".to_string(),
             size: Some(20),
             modified_at: None,
+            file_count: None,
         };
 
-        let result = Cleaner::dry_run(&[dir_item]);
+        let result = Cleaner::dry_run(&[dir_item], &SafetyRules::default());
 
         assert_eq!(result.total_files, 3);
         assert_eq!(result.total_dirs, 1);
@@ -468,5 +2161,561 @@ mod tests {
         assert_eq!(result.items.len(), 1);
         assert_eq!(result.items[0].file_count, 3);
         assert_eq!(result.items[0].dir_count, 1);
+        // 目录本身被保留，内容全部计入实际删除
+        assert_eq!(result.removable_size, 20);
+        assert_eq!(
+            result.retained_size,
+            dir.path().metadata().expect("dir metadata").len()
+        );
+        assert_eq!(result.skipped_count, 0);
+    }
+
+    #[test]
+    fn trash_policy_uses_categories_and_falls_back_for_uncategorized() {
+        let mut downloads = item(PathBuf::from("/tmp/dl"), Some(1));
+        downloads.category = Some(ItemCategory::Downloads);
+        let mut cache = item(PathBuf::from("/tmp/cache"), Some(2));
+        cache.category = Some(ItemCategory::AppCache);
+        let plain = item(PathBuf::from("/tmp/plain"), Some(3));
+
+        let policy = TrashPolicy {
+            default_trash: true,
+            categories: vec![ItemCategory::Downloads],
+        };
+        assert!(policy.should_trash(&downloads));
+        assert!(!policy.should_trash(&cache));
+        assert!(policy.should_trash(&plain));
+
+        let (to_trash, to_delete) = policy.split(&[downloads, cache.clone(), plain]);
+        assert_eq!(to_trash.len(), 2);
+        assert_eq!(to_delete.len(), 1);
+        assert_eq!(to_delete[0].path, cache.path);
+
+        assert!(!TrashPolicy::uniform(false).should_trash(&to_trash[0]));
+    }
+
+    #[test]
+    fn clean_with_policy_trashes_and_deletes_in_one_result() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-policy-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let cache_path = dir.path().join("cache.bin");
+        let download_path = dir.path().join("download.zip");
+        fs::write(&cache_path, b"cache").expect("write cache");
+        fs::write(&download_path, b"download").expect("write download");
+
+        let mut cache = item(cache_path.clone(), Some(5));
+        cache.category = Some(ItemCategory::AppCache);
+        let mut download = item(download_path.clone(), Some(8));
+        download.category = Some(ItemCategory::Downloads);
+        let policy = TrashPolicy {
+            default_trash: false,
+            categories: vec![ItemCategory::Downloads],
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let result = Cleaner::clean_with_policy_progress(
+            &[download, cache],
+            &policy,
+            CleanOptions::default(),
+            &tx,
+            &AtomicBool::new(false),
+        );
+        drop(tx);
+
+        assert!(result.success);
+        assert_eq!(result.freed_space, 13);
+        assert_eq!(result.trashed_paths, vec![download_path.clone()]);
+        assert_eq!(result.processed_paths.len(), 2);
+        assert!(!cache_path.exists());
+        assert!(!download_path.exists());
+
+        let progress: Vec<(usize, usize)> = rx
+            .iter()
+            .filter_map(|message| match message {
+                CleanMessage::Progress {
+                    completed, total, ..
+                } => Some((completed, total)),
+                CleanMessage::Done(_) => None,
+            })
+            .collect();
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn clean_result_merge_combines_both_groups() {
+        let failed = CleanResult {
+            success: false,
+            freed_space: 1,
+            errors: vec![CleanError::NotFound {
+                path: PathBuf::from("/tmp/a"),
+            }],
+            trashed_paths: Vec::new(),
+            processed_paths: vec![PathBuf::from("/tmp/a")],
+            cancelled: false,
+        };
+        let trashed = CleanResult {
+            success: true,
+            freed_space: 2,
+            errors: Vec::new(),
+            trashed_paths: vec![PathBuf::from("/tmp/b")],
+            processed_paths: vec![PathBuf::from("/tmp/b")],
+            cancelled: true,
+        };
+
+        let merged = failed.merge(trashed);
+        assert!(!merged.success);
+        assert!(merged.cancelled);
+        assert_eq!(merged.freed_space, 3);
+        assert_eq!(merged.errors.len(), 1);
+        assert_eq!(merged.trashed_paths, vec![PathBuf::from("/tmp/b")]);
+        assert_eq!(merged.processed_paths.len(), 2);
+    }
+
+    #[test]
+    fn dry_run_streaming_sends_each_item_then_done() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-dry-stream-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let file_path = dir.path().join("a.txt");
+        fs::write(&file_path, b"abcd").expect("write file");
+        let items = [
+            item(file_path.clone(), Some(4)),
+            item(dir.path().join("missing"), None),
+        ];
+
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(
+            Cleaner::dry_run_streaming(&items, &SafetyRules::default(), 7, &tx),
+            2
+        );
+        drop(tx);
+
+        let messages: Vec<DryRunMessage> = rx.iter().collect();
+        assert_eq!(messages.len(), 3);
+        assert!(messages.iter().all(|message| message.job_id() == 7));
+        match &messages[0] {
+            DryRunMessage::Item { item, .. } => {
+                assert_eq!(item.path, file_path);
+                assert_eq!((item.file_count, item.size), (1, 4));
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+        assert!(matches!(messages[2], DryRunMessage::Done { .. }));
+    }
+
+    #[test]
+    fn dry_run_streaming_stops_when_receiver_is_dropped() {
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+        let items = [
+            item(PathBuf::from("/tmp/path-does-not-exist-a"), None),
+            item(PathBuf::from("/tmp/path-does-not-exist-b"), None),
+        ];
+        // 第一次发送失败后直接返回，不再统计后续条目
+        assert_eq!(
+            Cleaner::dry_run_streaming(&items, &SafetyRules::default(), 1, &tx),
+            0
+        );
+    }
+
+    #[test]
+    fn reclaimable_estimate_skips_unsafe_entries() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-reclaimable-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let cache = dir.path().join("cache");
+        fs::write(&cache, b"cache").expect("write file");
+        let items = [
+            item(cache, Some(700)),
+            item(PathBuf::from("/usr"), Some(5_000)),
+            item(dir.path().join("missing"), Some(30)),
+        ];
+        assert_eq!(
+            Cleaner::reclaimable_estimate(&items, &SafetyRules::default()),
+            (1, 700)
+        );
+    }
+
+    #[test]
+    fn dry_run_reports_unsafe_items_as_skipped() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-dry-skip-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let file_path = dir.path().join("cache.bin");
+        fs::write(&file_path, vec![0u8; 8]).expect("write file");
+        let forbidden = dir.path().join("keep");
+        fs::create_dir(&forbidden).expect("create dir");
+        fs::write(forbidden.join("notes.txt"), vec![0u8; 6]).expect("write file");
+        let rules = SafetyRules {
+            allowed_roots: Vec::new(),
+            forbidden_extra: vec![forbidden.clone()],
+            own_data: Vec::new(),
+        };
+
+        let kept_dir_size = forbidden.metadata().expect("dir metadata").len();
+        let result = Cleaner::dry_run(&[item(file_path, None), item(forbidden, None)], &rules);
+
+        assert_eq!(result.total_size, 14);
+        assert_eq!(result.removable_size, 8);
+        assert_eq!(result.retained_size, 6 + kept_dir_size);
+        assert_eq!(result.skipped_count, 1);
+        assert!(!result.items[0].skipped);
+        assert_eq!(result.items[0].retained_size, 0);
+        assert!(result.items[1].skipped);
+        assert_eq!(result.items[1].removable_size, 0);
+    }
+
+    #[test]
+    fn measured_clean_reports_actual_freed_bytes_instead_of_stale_size() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-measure-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let tree = dir.path().join("tree");
+        fs::create_dir_all(tree.join("nested")).expect("create tree");
+        fs::write(tree.join("a.bin"), vec![1u8; 64 * 1024]).expect("write a");
+        fs::write(tree.join("nested/b.bin"), vec![2u8; 128 * 1024]).expect("write b");
+        let before = allocated_size(&tree);
+
+        let items = [item(tree.clone(), Some(1))];
+        let result = Cleaner::with_measured_freed(&items, true, || {
+            Cleaner::clean(&items, CleanOptions::default())
+        });
+
+        assert!(result.success);
+        assert_eq!(result.freed_space, before - allocated_size(&tree));
+        assert!(result.freed_space >= 192 * 1024);
+        assert_eq!(
+            Cleaner::with_measured_freed(&items, false, || Cleaner::clean(
+                &items,
+                CleanOptions::default()
+            ))
+            .freed_space,
+            1
+        );
+    }
+
+    #[test]
+    fn process_items_retries_transient_errors_only() {
+        let (tx, _rx) = mpsc::channel();
+        let items = vec![
+            item(PathBuf::from("/tmp/busy"), Some(5)),
+            item(PathBuf::from("/tmp/denied"), Some(7)),
+        ];
+        let run = |parallel: bool, delete_retries: u32| {
+            let attempts = Mutex::new(std::collections::HashMap::<PathBuf, u32>::new());
+            let result = Cleaner::process_items(
+                &items,
+                &tx,
+                &AtomicBool::new(false),
+                parallel,
+                CleanOptions {
+                    delete_retries,
+                    ..CleanOptions::default()
+                },
+                ProgressRange::whole(&items),
+                |item| {
+                    let mut attempts = attempts.lock().unwrap();
+                    let count = attempts.entry(item.path.clone()).or_default();
+                    *count += 1;
+                    let path = item.path.clone();
+                    match item.path.to_str() {
+                        // 第一次被占用，重试后成功
+                        Some("/tmp/busy") if *count == 1 => Err(CleanError::Busy { path }),
+                        Some("/tmp/busy") => Ok(true),
+                        _ => Err(CleanError::PermissionDenied { path }),
+                    }
+                },
+            );
+            (result, attempts.into_inner().unwrap())
+        };
+
+        for parallel in [false, true] {
+            let (result, attempts) = run(parallel, DEFAULT_DELETE_RETRIES);
+            assert_eq!(attempts[Path::new("/tmp/busy")], 2);
+            assert_eq!(attempts[Path::new("/tmp/denied")], 1);
+            assert_eq!(result.freed_space, 5);
+            assert_eq!(
+                result.errors,
+                vec![CleanError::PermissionDenied {
+                    path: PathBuf::from("/tmp/denied")
+                }]
+            );
+        }
+
+        let (result, attempts) = run(true, 0);
+        assert_eq!(attempts[Path::new("/tmp/busy")], 1);
+        assert_eq!(result.errors.len(), 2);
+
+        let mut config = AppConfig::default();
+        config.safety.delete_retries = Some(u32::MAX);
+        assert_eq!(
+            CleanOptions::from_config(&config).delete_retries,
+            MAX_DELETE_RETRIES
+        );
+    }
+
+    #[test]
+    fn existing_dir_size_sums_files_and_ignores_missing_dirs() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-trash-size-")
+            .tempdir_in("/tmp")
+            .expect("tempdir");
+        fs::create_dir(dir.path().join("nested")).expect("mkdir");
+        fs::write(dir.path().join("a"), b"abc").expect("write");
+        fs::write(dir.path().join("nested/b"), b"12345").expect("write");
+
+        assert_eq!(Cleaner::existing_dir_size(dir.path()), Some(8));
+        assert_eq!(
+            Cleaner::existing_dir_size(&dir.path().join("missing")),
+            None
+        );
+        assert_eq!(Cleaner::existing_dir_size(&dir.path().join("a")), None);
+    }
+
+    #[test]
+    fn pruning_removes_empty_subdirs_but_keeps_root_and_remaining_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-clean-prune-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let root = dir.path().join("cache");
+        fs::create_dir_all(root.join("a/b/c")).expect("create nested");
+        fs::create_dir_all(root.join("kept")).expect("create kept");
+        fs::write(root.join("a/b/c/file"), b"x").expect("write file");
+        fs::write(root.join("a/top"), b"y").expect("write file");
+        fs::write(root.join("kept/locked"), b"z").expect("write file");
+
+        // 模拟部分清理：文件被删除（kept/locked 删除失败），目录结构残留
+        let run = || {
+            fs::remove_file(root.join("a/b/c/file")).expect("remove");
+            fs::remove_file(root.join("a/top")).expect("remove");
+            CleanResult {
+                success: false,
+                freed_space: 2,
+                errors: Vec::new(),
+                trashed_paths: Vec::new(),
+                processed_paths: vec![root.clone()],
+                cancelled: false,
+            }
+        };
+        let result = Cleaner::with_pruned_empty_dirs(true, run);
+
+        assert_eq!(result.freed_space, 2);
+        assert!(root.is_dir());
+        assert!(!root.join("a").exists());
+        assert!(root.join("kept/locked").exists());
+
+        fs::create_dir_all(root.join("empty/deeper")).expect("create empty");
+        let result =
+            Cleaner::with_pruned_empty_dirs(false, || Cleaner::clean(&[], CleanOptions::default()));
+        assert!(result.success);
+        assert!(root.join("empty/deeper").exists());
+        assert_eq!(Cleaner::prune_empty_dirs(&root), 2);
+        assert!(root.is_dir());
+    }
+
+    #[test]
+    fn process_items_stops_after_first_error_only_in_strict_mode() {
+        let (tx, _rx) = mpsc::channel();
+        let items: Vec<CleanableEntry> = (0..8)
+            .map(|index| item(PathBuf::from(format!("/tmp/strict-{index}")), Some(1)))
+            .collect();
+        // 奇数序号的条目失败
+        let run = |stop_on_error: bool| {
+            let attempted = Mutex::new(Vec::new());
+            let result = Cleaner::process_items(
+                &items,
+                &tx,
+                &AtomicBool::new(false),
+                true,
+                CleanOptions {
+                    stop_on_error,
+                    ..CleanOptions::default()
+                },
+                ProgressRange::whole(&items),
+                |item| {
+                    attempted.lock().unwrap().push(item.path.clone());
+                    let index: u32 = item.path.to_string_lossy()["/tmp/strict-".len()..]
+                        .parse()
+                        .unwrap();
+                    if index % 2 == 1 {
+                        Err(CleanError::PermissionDenied {
+                            path: item.path.clone(),
+                        })
+                    } else {
+                        Ok(true)
+                    }
+                },
+            );
+            (result, attempted.into_inner().unwrap().len())
+        };
+
+        let (strict, attempted) = run(true);
+        assert_eq!(attempted, 2);
+        assert!(!strict.success);
+        assert!(!strict.cancelled);
+        assert_eq!(strict.errors.len(), 1);
+        assert_eq!(strict.processed_paths.len(), 2);
+        assert_eq!(strict.freed_space, 1);
+
+        let (lenient, attempted) = run(false);
+        assert_eq!(attempted, 8);
+        assert_eq!(lenient.errors.len(), 4);
+        assert_eq!(lenient.freed_space, 4);
+    }
+
+    #[test]
+    fn is_safe_to_delete_refuses_vac_own_config_dir() {
+        let home = tempfile::Builder::new()
+            .prefix("vac-home-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let config_dir = home.path().join(".config").join("vac");
+        let config_file = config_dir.join("config.toml");
+        let other_config = home.path().join(".config").join("other");
+        fs::create_dir_all(&config_dir).expect("create config dir");
+        fs::write(&config_file, b"").expect("write config");
+        fs::create_dir_all(&other_config).expect("create other dir");
+
+        let rules = SafetyRules {
+            own_data: SafetyRules::vac_data_dirs(home.path()),
+            ..SafetyRules::default()
+        };
+
+        assert!(!Cleaner::is_safe_to_delete(&config_dir, &rules));
+        assert!(!Cleaner::is_safe_to_delete(&config_file, &rules));
+        assert!(rules.is_own_data(&config_file));
+        assert!(Cleaner::is_safe_to_delete(&other_config, &rules));
+        assert!(!rules.is_own_data(&other_config));
+
+        // ~/.config 本身不是 VAC 的数据：允许清理，但清理时跳过其中的 vac 目录
+        let config_root = home.path().join(".config");
+        assert!(Cleaner::is_safe_to_delete(&config_root, &rules));
+        assert!(!rules.is_own_data(&config_root));
+        let parent = CleanableEntry {
+            kind: EntryKind::Directory,
+            ..item(config_root.clone(), None)
+        };
+        let expanded = Cleaner::without_own_data(std::slice::from_ref(&parent), &rules);
+        let paths: Vec<_> = expanded.iter().map(|entry| entry.path.clone()).collect();
+        assert_eq!(paths, std::slice::from_ref(&other_config));
+        let result = Cleaner::clean(&expanded, CleanOptions::default());
+        assert!(result.success);
+        assert!(config_file.exists());
+        // ~/.cache/vac 尚不存在时，~/.cache 中没有需要保护的内容
+        let cache_root = home.path().join(".cache");
+        fs::create_dir(&cache_root).expect("create cache dir");
+        assert!(Cleaner::is_safe_to_delete(&cache_root, &rules));
+
+        let default_config_dir = AppConfig::default_config_path()
+            .parent()
+            .expect("config dir")
+            .to_path_buf();
+        assert!(
+            SafetyRules::from_config(&AppConfig::default())
+                .own_data
+                .contains(&default_config_dir)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clean_symlink_to_dir_removes_only_the_link() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = dir.path().join("target");
+        fs::create_dir(&target).expect("create target");
+        fs::write(target.join("keep.txt"), b"keep").expect("write file");
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).expect("create symlink");
+
+        let result = Cleaner::clean(&[item(link.clone(), Some(0))], CleanOptions::default());
+
+        assert!(result.success);
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert_eq!(fs::read(target.join("keep.txt")).expect("read"), b"keep");
+    }
+
+    #[test]
+    fn safety_rules_protect_explicit_config_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-explicit-config-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let dotfiles = dir.path().join("dotfiles");
+        let config_file = dotfiles.join("vac.toml");
+        let sibling = dotfiles.join("notes");
+        fs::create_dir_all(&sibling).expect("create dirs");
+        fs::write(&config_file, b"").expect("write config");
+
+        let config = AppConfig::load_from(&config_file).expect("load config");
+        let rules = SafetyRules::from_config(&config);
+
+        assert!(!Cleaner::is_safe_to_delete(&config_file, &rules));
+        assert!(rules.is_own_data(&config_file));
+        assert!(!rules.is_own_data(&dotfiles));
+        assert!(Cleaner::is_safe_to_delete(&sibling, &rules));
+
+        // 清理所在目录时只跳过配置文件本身
+        let parent = CleanableEntry {
+            kind: EntryKind::Directory,
+            ..item(dotfiles.clone(), None)
+        };
+        let expanded = Cleaner::without_own_data(&[parent], &rules);
+        let paths: Vec<_> = expanded.iter().map(|entry| entry.path.clone()).collect();
+        assert_eq!(paths, [sibling]);
+    }
+
+    #[test]
+    fn without_nested_drops_descendants_and_duplicates() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-nested-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let parent = dir.path().join("parent");
+        let child = parent.join("child");
+        fs::create_dir_all(&child).expect("create dirs");
+        fs::write(child.join("a.bin"), vec![0u8; 4]).expect("write file");
+        fs::write(parent.join("b.bin"), vec![0u8; 6]).expect("write file");
+        let sibling = dir.path().join("parent-sibling");
+        fs::write(&sibling, b"xy").expect("write sibling");
+
+        let items = vec![
+            item(child.clone(), Some(4)),
+            item(parent.clone(), Some(10)),
+            item(sibling.clone(), Some(2)),
+            item(parent.clone(), Some(10)),
+        ];
+        let unique = Cleaner::without_nested(&items);
+        let paths: Vec<_> = unique.iter().map(|item| item.path.clone()).collect();
+        assert_eq!(paths, [parent.clone(), sibling.clone()]);
+
+        let result = Cleaner::clean(&unique, CleanOptions::default());
+        assert!(result.success);
+        assert_eq!(result.freed_space, 12);
+        assert_eq!(fs::read_dir(&parent).expect("read parent").count(), 0);
+    }
+
+    #[test]
+    fn count_path_contents_until_stops_when_cancelled() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-count-cancel-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        fs::write(dir.path().join("a.txt"), b"abc").expect("write file");
+
+        assert_eq!(
+            Cleaner::count_path_contents_until(dir.path(), || false),
+            Some((1, 0, 3))
+        );
+        assert_eq!(
+            Cleaner::count_path_contents_until(dir.path(), || true),
+            None
+        );
     }
 }
@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use serde::{Deserialize, Serialize};
 
-use crate::utils::expand_tilde;
+use crate::app::ItemCategory;
+use crate::utils::expand_path;
 
 /// VAC - macOS 磁盘清理工具
 ///
@@ -10,33 +12,142 @@ use crate::utils::expand_tilde;
 #[derive(Parser, Debug)]
 #[command(name = "vac", version, about, long_about = None)]
 pub struct Cli {
-    /// 执行扫描（非交互模式）。可选值: preset（预设目录）、home（主目录）、或指定路径
+    /// 执行扫描（非交互模式）。可选值: preset（预设目录）、home（主目录）、或指定路径；可重复传入，结果合并
     #[arg(long, value_name = "MODE_OR_PATH")]
-    pub scan: Option<ScanTarget>,
+    pub scan: Vec<ScanTarget>,
 
     /// 仅模拟删除，不执行实际清理（需配合 --clean 使用）
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
 
-    /// 执行清理（清理扫描结果中的所有项目）
+    /// 执行清理（清理扫描结果中的所有项目）。需同时传入 --yes，或在终端中输入 y 确认
     #[arg(long, default_value_t = false)]
     pub clean: bool,
 
+    /// 不扫描，直接清理指定的文件或目录（可传入多个路径），清理前逐一做删除安全检查；可配合 --dry-run、--trash、--yes
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 1..,
+        value_parser = parse_expanded_path,
+        conflicts_with_all = ["scan", "clean", "empty_trash"]
+    )]
+    pub delete: Vec<PathBuf>,
+
+    /// 保留策略：--delete 或 --scan <路径> 的每个目录只清理按修改时间最新 N 个之外的子项，最新的 N 个始终保留（--clean 也不会删除）
+    #[arg(long, value_name = "N")]
+    pub keep_newest: Option<usize>,
+
+    /// 跳过 --clean / --delete / --empty-trash 的确认提示，直接执行（脚本中使用时必须提供）
+    #[arg(short = 'y', long, default_value_t = false)]
+    pub yes: bool,
+
     /// 将结果输出到指定文件（支持 .json 格式）
     #[arg(long, value_name = "FILE")]
     pub output: Option<PathBuf>,
 
+    /// 输出格式: text（终端可读文本）/ json（将 JSON 报告打印到 stdout）/ ndjson（逐条流式输出）/ csv（表格，仅用于 --scan）
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
     /// 排序方式: name / size / time
     #[arg(long, value_name = "ORDER", default_value = "size")]
     pub sort: String,
 
+    /// 预设扫描只包含指定类别，多个类别以逗号分隔（如 cargo,npm,xcode，仅用于 --scan preset）
+    #[arg(long, value_name = "NAME", value_delimiter = ',', value_parser = parse_category)]
+    pub category: Vec<ItemCategory>,
+
+    /// 预设扫描排除指定类别，多个类别以逗号分隔（如 downloads,trash）；与 --category 同时使用时先包含再排除
+    #[arg(long, value_name = "NAME", value_delimiter = ',', value_parser = parse_category)]
+    pub exclude_category: Vec<ItemCategory>,
+
+    /// 仅保留大小不小于该阈值的条目，支持 100M、2G、512KiB 等写法（大小未知的条目会被排除）
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub min_size: Option<u64>,
+
+    /// 仅保留按 --sort 排序后的前 N 个条目（输出与清理均只作用于这些条目）；默认按大小排序时为最大的 N 项，搭配 --sort name / time 时则不是最大的 N 项
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
     /// 使用回收站而非永久删除（覆盖配置文件设置）
     #[arg(long, default_value_t = false)]
     pub trash: bool,
+
+    /// 安全擦除：删除前以随机数据覆写文件（较慢且不可恢复，优先于回收站）
+    #[arg(long, default_value_t = false)]
+    pub secure: bool,
+
+    /// 安全模式：清理一律移至回收站，禁止永久删除、安全擦除与清空回收站（同 safety.force_trash）
+    #[arg(long, default_value_t = false, conflicts_with_all = ["secure", "empty_trash"])]
+    pub safe: bool,
+
+    /// 严格模式：任一条目清理失败后立即停止，不再处理后续条目（同 safety.stop_on_error）
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+
+    /// 不使用扫描缓存，强制重新计算所有目录大小
+    #[arg(long, default_value_t = false)]
+    pub no_cache: bool,
+
+    /// 清空系统回收站（永久删除，可单独使用或与 --scan 组合）。需同时传入 --yes，或在终端中输入 y 确认
+    #[arg(long, default_value_t = false)]
+    pub empty_trash: bool,
+
+    /// 使用指定的配置文件（优先于 VAC_CONFIG 环境变量，文件必须存在）
+    #[arg(long, value_name = "FILE", value_parser = parse_expanded_path)]
+    pub config: Option<PathBuf>,
+
+    /// 列出预设扫描的目标类别与路径（标出是否存在）后退出，不计算大小；可配合 --category / --exclude-category / --format json
+    #[arg(long, default_value_t = false)]
+    pub list_targets: bool,
+
+    /// 生成带注释的默认配置文件后退出（写入 --config / VAC_CONFIG 指定的路径，默认 ~/.config/vac/config.toml）
+    #[arg(long, default_value_t = false)]
+    pub init_config: bool,
+
+    /// 与 --init-config 配合使用，覆盖已存在的配置文件
+    #[arg(long, default_value_t = false, requires = "init_config")]
+    pub force: bool,
+
+    /// 静默模式：stderr 只输出错误，不显示进度与提示（非交互模式）
+    #[arg(short = 'q', long, default_value_t = false, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// 详细模式：额外在 stderr 逐行输出正在扫描的路径（非交互模式）
+    #[arg(short = 'v', long, default_value_t = false)]
+    pub verbose: bool,
+}
+
+/// 非交互模式 stderr 输出级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum LogLevel {
+    /// 只输出错误
+    Quiet,
+    /// 进度与提示信息
+    #[default]
+    Normal,
+    /// 另外逐行输出正在扫描的路径
+    Verbose,
+}
+
+/// 非交互模式的输出格式
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// 终端可读文本
+    #[default]
+    Text,
+    /// 完整 JSON 报告（输出到 stdout）
+    Json,
+    /// 每行一个 JSON 对象：扫描中逐条输出条目，最后输出汇总行
+    Ndjson,
+    /// CSV 表格（含表头，UTF-8），每行一个条目
+    Csv,
 }
 
 /// 扫描目标类型
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ScanTarget {
     /// 扫描预设可清理目录
     Preset,
@@ -46,6 +157,25 @@ pub enum ScanTarget {
     Path(PathBuf),
 }
 
+impl ScanTarget {
+    /// 目标是否仍可扫描（指定路径需存在且为目录）
+    pub fn is_available(&self) -> bool {
+        match self {
+            ScanTarget::Preset | ScanTarget::Home => true,
+            ScanTarget::Path(path) => path.is_dir(),
+        }
+    }
+
+    /// 界面中显示的目标名称
+    pub fn label(&self) -> String {
+        match self {
+            ScanTarget::Preset => "预设目录".to_string(),
+            ScanTarget::Home => "主目录".to_string(),
+            ScanTarget::Path(path) => path.display().to_string(),
+        }
+    }
+}
+
 impl std::str::FromStr for ScanTarget {
     type Err = String;
 
@@ -54,17 +184,88 @@ impl std::str::FromStr for ScanTarget {
             "preset" => Ok(ScanTarget::Preset),
             "home" => Ok(ScanTarget::Home),
             other => {
-                let path = PathBuf::from(expand_tilde(other));
+                let path = PathBuf::from(expand_path(other));
                 Ok(ScanTarget::Path(path))
             }
         }
     }
 }
 
+/// 展开路径参数中的 `~` 与环境变量
+fn parse_expanded_path(raw: &str) -> Result<PathBuf, String> {
+    Ok(PathBuf::from(expand_path(raw)))
+}
+
+/// 解析类别标识，未知标识时列出所有可用标识
+fn parse_category(raw: &str) -> Result<ItemCategory, String> {
+    ItemCategory::from_slug(raw).ok_or_else(|| {
+        let valid: Vec<&str> = ItemCategory::ALL
+            .iter()
+            .map(|category| category.slug())
+            .collect();
+        format!("未知类别 `{raw}`，可用类别: {}", valid.join(", "))
+    })
+}
+
+/// 解析人类可读的大小字符串（如 `100M`、`2G`），返回字节数
+fn parse_size(raw: &str) -> Result<u64, String> {
+    raw.trim()
+        .parse::<bytesize::ByteSize>()
+        .map(|size| size.as_u64())
+        .map_err(|err| format!("无效的大小 `{raw}`: {err}"))
+}
+
 impl Cli {
-    /// 判断是否为非交互模式（传入了 --scan 参数）
+    /// 判断是否为非交互模式（传入了 --scan 或 --empty-trash 参数）
     pub fn is_non_interactive(&self) -> bool {
-        self.scan.is_some()
+        !self.scan.is_empty() || self.empty_trash || !self.delete.is_empty()
+    }
+
+    /// 检查 clap 无法按取值声明的参数冲突：CSV 输出只支持扫描报告
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if self.format == OutputFormat::Csv && self.scan.is_empty() {
+            return Err(Self::command().error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--format csv 只能与 --scan 一起使用（--delete、--list-targets 与 --empty-trash 不支持 CSV 输出）",
+            ));
+        }
+        if self.keep_newest.is_some() {
+            if self.delete.is_empty() && self.scan.is_empty() {
+                return Err(Self::command().error(
+                    clap::error::ErrorKind::MissingRequiredArgument,
+                    "--keep-newest 需要配合 --delete 或 --scan <路径> 使用",
+                ));
+            }
+            if self
+                .scan
+                .iter()
+                .any(|target| !matches!(target, ScanTarget::Path(_)))
+            {
+                return Err(Self::command().error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "--keep-newest 只适用于 --scan <路径>，不能与 --scan preset / home 一起使用",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 是否请求了会删除文件的操作（--clean / --delete / --empty-trash）
+    ///
+    /// 这些操作依赖配置文件中的安全设置，配置无效时不能回退到默认配置执行。
+    pub fn modifies_files(&self) -> bool {
+        self.clean || self.empty_trash || !self.delete.is_empty()
+    }
+
+    /// 由 --quiet / --verbose 得到的输出级别
+    pub fn log_level(&self) -> LogLevel {
+        if self.quiet {
+            LogLevel::Quiet
+        } else if self.verbose {
+            LogLevel::Verbose
+        } else {
+            LogLevel::Normal
+        }
     }
 }
 
@@ -116,7 +317,149 @@ mod tests {
     fn cli_parse_scan_preset() {
         let cli = Cli::parse_from(["vac", "--scan", "preset"]);
         assert!(cli.is_non_interactive());
-        assert!(matches!(cli.scan, Some(ScanTarget::Preset)));
+        assert!(matches!(cli.scan.as_slice(), [ScanTarget::Preset]));
+    }
+
+    #[test]
+    fn cli_parse_format_defaults_to_text() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset"]);
+        assert_eq!(cli.format, OutputFormat::Text);
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--format", "json"]);
+        assert_eq!(cli.format, OutputFormat::Json);
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--format", "ndjson"]);
+        assert_eq!(cli.format, OutputFormat::Ndjson);
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--format", "csv"]);
+        assert_eq!(cli.format, OutputFormat::Csv);
+        assert!(cli.validate().is_ok());
+
+        for args in [
+            vec!["vac", "--delete", "/tmp/a", "--format", "csv"],
+            vec!["vac", "--list-targets", "--format", "csv"],
+            vec!["vac", "--empty-trash", "--format", "csv"],
+        ] {
+            let err = Cli::parse_from(args)
+                .validate()
+                .expect_err("csv needs --scan");
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn cli_parse_init_config() {
+        let cli = Cli::parse_from(["vac", "--init-config"]);
+        assert!(cli.init_config);
+        assert!(!cli.force);
+
+        let cli = Cli::parse_from(["vac", "--init-config", "--force"]);
+        assert!(cli.force);
+
+        assert!(Cli::try_parse_from(["vac", "--force"]).is_err());
+    }
+
+    #[test]
+    fn cli_parse_config_path() {
+        let cli = Cli::parse_from(["vac"]);
+        assert!(cli.config.is_none());
+
+        let cli = Cli::parse_from(["vac", "--config", "/tmp/profile.toml"]);
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/profile.toml")));
+    }
+
+    #[test]
+    fn cli_parse_yes() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--clean"]);
+        assert!(!cli.yes);
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--clean", "--yes"]);
+        assert!(cli.yes);
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--clean", "-y"]);
+        assert!(cli.yes);
+    }
+
+    #[test]
+    fn cli_parse_top() {
+        let cli = Cli::parse_from(["vac", "--scan", "home"]);
+        assert_eq!(cli.top, None);
+
+        let cli = Cli::parse_from(["vac", "--scan", "home", "--top", "10"]);
+        assert_eq!(cli.top, Some(10));
+
+        assert!(Cli::try_parse_from(["vac", "--scan", "home", "--top", "-1"]).is_err());
+    }
+
+    #[test]
+    fn cli_parse_category_list() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset"]);
+        assert!(cli.category.is_empty());
+
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--category", "cargo,npm"]);
+        assert_eq!(
+            cli.category,
+            vec![ItemCategory::CargoCache, ItemCategory::NpmCache]
+        );
+    }
+
+    #[test]
+    fn cli_parse_exclude_category_list() {
+        let cli = Cli::parse_from([
+            "vac",
+            "--scan",
+            "preset",
+            "--category",
+            "cargo,npm",
+            "--exclude-category",
+            "npm",
+        ]);
+        assert_eq!(cli.exclude_category, vec![ItemCategory::NpmCache]);
+
+        let err = Cli::try_parse_from(["vac", "--scan", "preset", "--exclude-category", "bogus"])
+            .expect_err("unknown category");
+        assert!(err.to_string().contains("cargo"));
+    }
+
+    #[test]
+    fn cli_parse_log_level() {
+        let cli = Cli::parse_from(["vac", "--scan", "home"]);
+        assert_eq!(cli.log_level(), LogLevel::Normal);
+        let cli = Cli::parse_from(["vac", "--scan", "home", "-q"]);
+        assert_eq!(cli.log_level(), LogLevel::Quiet);
+        let cli = Cli::parse_from(["vac", "--scan", "home", "--verbose"]);
+        assert_eq!(cli.log_level(), LogLevel::Verbose);
+        assert!(Cli::try_parse_from(["vac", "--scan", "home", "-q", "-v"]).is_err());
+    }
+
+    #[test]
+    fn cli_parse_rejects_unknown_category() {
+        let err = Cli::try_parse_from(["vac", "--scan", "preset", "--category", "bogus"])
+            .expect_err("unknown category");
+        assert!(err.to_string().contains("cargo"));
+    }
+
+    #[test]
+    fn cli_parse_min_size() {
+        let cli = Cli::parse_from(["vac", "--scan", "home", "--min-size", "100M"]);
+        assert_eq!(cli.min_size, Some(100_000_000));
+
+        let cli = Cli::parse_from(["vac", "--scan", "home", "--min-size", "2GiB"]);
+        assert_eq!(cli.min_size, Some(2 * 1024 * 1024 * 1024));
+
+        let cli = Cli::parse_from(["vac", "--scan", "home", "--min-size", "4096"]);
+        assert_eq!(cli.min_size, Some(4096));
+    }
+
+    #[test]
+    fn cli_parse_rejects_malformed_min_size() {
+        assert!(Cli::try_parse_from(["vac", "--scan", "home", "--min-size", "abc"]).is_err());
+        assert!(Cli::try_parse_from(["vac", "--scan", "home", "--min-size", "10XB"]).is_err());
+    }
+
+    #[test]
+    fn cli_parse_rejects_unknown_format() {
+        assert!(Cli::try_parse_from(["vac", "--scan", "preset", "--format", "xml"]).is_err());
     }
 
     #[test]
@@ -144,9 +487,107 @@ mod tests {
         assert!(cli.clean);
     }
 
+    #[test]
+    fn cli_parse_no_cache_flag() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--no-cache"]);
+        assert!(cli.no_cache);
+        let cli = Cli::parse_from(["vac"]);
+        assert!(!cli.no_cache);
+    }
+
+    #[test]
+    fn cli_parse_secure_flag() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--clean", "--secure"]);
+        assert!(cli.secure);
+        assert!(!Cli::parse_from(["vac"]).secure);
+    }
+
+    #[test]
+    fn cli_parse_safe_flag_conflicts_with_permanent_options() {
+        let cli = Cli::parse_from(["vac", "--scan", "preset", "--clean", "--safe"]);
+        assert!(cli.safe);
+        assert!(!Cli::parse_from(["vac"]).safe);
+        assert!(Cli::try_parse_from(["vac", "--scan", "preset", "--safe", "--secure"]).is_err());
+        assert!(Cli::try_parse_from(["vac", "--safe", "--empty-trash"]).is_err());
+    }
+
+    #[test]
+    fn cli_parse_list_targets_flag() {
+        let cli = Cli::parse_from(["vac", "--list-targets", "--format", "json"]);
+        assert!(cli.list_targets);
+        assert_eq!(cli.format, OutputFormat::Json);
+        assert!(!Cli::parse_from(["vac"]).list_targets);
+    }
+
+    #[test]
+    fn cli_parse_empty_trash_is_non_interactive() {
+        let cli = Cli::parse_from(["vac", "--empty-trash"]);
+        assert!(cli.empty_trash);
+        assert!(cli.scan.is_empty());
+        assert!(cli.is_non_interactive());
+        assert!(cli.modifies_files());
+        assert!(!Cli::parse_from(["vac", "--scan", "preset"]).modifies_files());
+        assert!(Cli::parse_from(["vac", "--scan", "preset", "--clean"]).modifies_files());
+    }
+
     #[test]
     fn cli_default_sort_is_size() {
         let cli = Cli::parse_from(["vac"]);
         assert_eq!(cli.sort, "size");
     }
+
+    #[test]
+    fn cli_parse_delete_paths_conflict_with_scan() {
+        let cli = Cli::try_parse_from(["vac", "--delete", "/tmp/a", "/tmp/b", "--dry-run"])
+            .expect("parse should succeed");
+        assert_eq!(
+            cli.delete,
+            vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")]
+        );
+        assert!(cli.dry_run);
+        assert!(cli.is_non_interactive());
+
+        for conflicting in [["--scan", "preset"], ["--clean", "--yes"]] {
+            let mut args = vec!["vac", "--delete", "/tmp/a"];
+            args.extend(conflicting);
+            let err = Cli::try_parse_from(args).expect_err("--delete conflicts");
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn cli_parse_keep_newest_requires_delete_or_path_scan() {
+        let parse =
+            |args: &[&str]| Cli::try_parse_from(args).and_then(|cli| cli.validate().map(|()| cli));
+        let cli = parse(&["vac", "--delete", "/tmp/builds", "--keep-newest", "5"])
+            .expect("parse should succeed");
+        assert_eq!(cli.keep_newest, Some(5));
+        let cli = parse(&[
+            "vac",
+            "--scan",
+            "/tmp/builds",
+            "--clean",
+            "--keep-newest",
+            "5",
+        ])
+        .expect("--keep-newest works with a path scan");
+        assert_eq!(cli.keep_newest, Some(5));
+
+        let err = parse(&["vac", "--keep-newest", "5"])
+            .expect_err("--keep-newest requires --delete or --scan");
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        let err = parse(&["vac", "--scan", "preset", "--keep-newest", "5"])
+            .expect_err("--keep-newest needs a path target");
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn cli_parse_repeated_scan_collects_targets() {
+        let cli = Cli::parse_from(["vac", "--scan", "/tmp/a", "--scan", "home"]);
+        assert_eq!(
+            cli.scan,
+            vec![ScanTarget::Path(PathBuf::from("/tmp/a")), ScanTarget::Home]
+        );
+        assert!(cli.is_non_interactive());
+    }
 }
@@ -1,9 +1,156 @@
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
-use crate::utils::expand_tilde;
+use crate::app::ItemCategory;
+use crate::scanner::SizeUnits;
+use crate::utils::expand_path;
+
+/// `vac --init-config` 写入的带注释默认配置
+pub const DEFAULT_CONFIG_TEMPLATE: &str = r##"# VAC 配置文件
+# 所有字段均可省略，省略时使用下方所示的默认值。
+# 路径支持 ~、~user 与 $VAR / ${VAR} 环境变量展开。
+
+[scan]
+# 预设扫描（s）时额外扫描的目录，统一显示为“自定义目标”
+extra_targets = []
+# 计算目录大小时是否跟随符号链接（被多处链接的目标会重复计入）
+follow_symlinks = false
+# 并行计算目录大小的线程数上限，机械硬盘或低核心机器上可调小以减少 I/O 争用（未设置时自动）
+# max_concurrency = 2
+# 快速列表：进入子目录时不计算各子目录大小，高亮或选中时再按需计算（TUI 中按 F 切换）
+lazy_sizes = false
+# 预设扫描（s）时跳过的类别（如 ["downloads", "trash"]），也可在 TUI 中按 , 切换
+disabled_categories = []
+
+# 带名称的自定义扫描目标，可重复添加，每个目标在统计中单独成类
+# [[scan.custom]]
+# name = "My Projects"
+# path = "~/dev"
+
+[ui]
+# 默认排序方式: "name" / "size" / "time"（未设置时按大小排序）
+# default_sort = "size"
+# 是否启用鼠标（点击选择、滚轮移动）
+mouse = true
+# 是否在列表每行显示相对大小条
+show_bars = true
+# 上下移动到列表首尾时是否回绕到另一端（false 时停在首/末项）
+wrap_navigation = true
+# 有已选项目时按 q 是否先确认再退出
+confirm_quit = true
+# 是否以相对时间（如“3 天前”）显示修改时间
+relative_time = false
+# 修改时间的显示格式，支持 %Y %m %d %H %M %S %%（未设置时为 YYYY-MM-DD）
+# date_format = "%Y/%m/%d %H:%M"
+# 大小显示单位: "binary"（KiB/MiB）/ "decimal"（kB/MB，与 Finder 一致）
+size_units = "binary"
+# 书签：常用扫描路径，按 b 打开列表
+bookmarks = []
+
+[ui.theme]
+# 预设主题: "default" / "dracula" / "solarized" / "mono"
+# preset = "default"
+# 逐项覆盖颜色，颜色名（如 "cyan"）或十六进制（如 "#89b4fa"）
+# primary = "cyan"
+# secondary = "blue"
+# accent = "magenta"
+# warning = "yellow"
+# danger = "red"
+# success = "green"
+# text = "white"
+# text_dim = "gray"
+# bg = "black"
+# bg_highlight = "#313244"
+
+[safety]
+# 是否移至系统回收站而非永久删除
+move_to_trash = false
+# 按类别移至回收站（如 ["downloads", "trash"]），其余带类别的条目永久删除；为空时统一按 move_to_trash 处理
+trash_categories = []
+# 扫描完成后自动选中修改时间早于该天数的条目（未设置时不自动选中）
+# auto_select_older_than_days = 90
+# 打开清理确认弹窗时直接显示 dry-run 预览（文件数 / 目录数 / 大小）
+confirm_dry_run_first = false
+# 永久删除超过该大小时须输入 DELETE 才能确认（如 "5G"，未设置时不要求）
+# confirm_word_above = "5G"
+# 安全模式：清理一律移至回收站，禁止永久删除、安全擦除与清空回收站（优先于以下所有删除方式）
+force_trash = false
+# 是否在删除前以随机数据覆写文件（较慢且不可恢复，优先于 move_to_trash）
+secure_delete = false
+# 删除遇到 macOS 不可变标志 (uchg) 时是否自动清除后重试
+clear_flags = false
+# 清理后重新统计实际释放的磁盘空间（与 df 的变化一致，较慢；回收站模式在清空回收站前不释放空间）
+measure_freed_space = false
+# 清理后删除已清理目录下残留的空子目录（保留被清理的目录本身）
+prune_empty_dirs = false
+# 任一条目清理失败后立即停止，不再处理后续条目（同 --strict）
+stop_on_error = false
+# 删除遇到“资源被占用”等暂时性错误时的重试次数（每次等待更久，0 为不重试；权限不足等错误不重试）
+delete_retries = 2
+# 除用户目录与临时目录外允许清理的根目录
+allowed_roots = []
+# 在内置禁止路径之外额外禁止清理的路径，及其下所有内容
+forbidden_extra = []
+"##;
+
+/// 指定配置文件路径的环境变量（优先级低于 `--config`）
+pub const CONFIG_ENV_VAR: &str = "VAC_CONFIG";
+
+/// 配置文件位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    pub path: PathBuf,
+    /// 是否通过 `--config` 或 `VAC_CONFIG` 显式指定（显式指定的文件必须存在）
+    pub explicit: bool,
+}
+
+/// 配置文件加载错误（默认路径下文件缺失不视为错误）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// 显式指定的配置文件不存在
+    NotFound { path: PathBuf },
+    /// 配置文件存在但无法读取
+    Read { path: PathBuf, message: String },
+    /// 配置文件不是合法的 TOML 或字段类型不匹配
+    Parse {
+        path: PathBuf,
+        /// 出错位置的行号（从 1 开始，无法定位时为 None）
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound { path } => write!(f, "指定的配置文件不存在: {}", path.display()),
+            Self::Read { path, message } => {
+                write!(f, "无法读取配置文件 {}: {}", path.display(), message)
+            }
+            Self::Parse {
+                path,
+                line: Some(line),
+                message,
+            } => write!(
+                f,
+                "配置文件 {} 第 {} 行解析失败: {}",
+                path.display(),
+                line,
+                message
+            ),
+            Self::Parse {
+                path,
+                line: None,
+                message,
+            } => write!(f, "配置文件 {} 解析失败: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
 
 /// 应用配置
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -17,22 +164,135 @@ pub struct AppConfig {
     /// 安全相关配置
     #[serde(default)]
     pub safety: SafetyConfig,
+    /// 加载该配置的文件路径（不从文件读取；使用内置默认配置时为 None）
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
 }
 
 /// 扫描配置
 #[derive(Debug, Deserialize, Default, Clone)]
 pub struct ScanConfig {
-    /// 额外扫描目标路径（支持 ~ 表示主目录）
+    /// 额外扫描目标路径（支持 ~、~user 与环境变量）
     #[serde(default)]
     pub extra_targets: Vec<String>,
+    /// 带名称的自定义扫描目标（`[[scan.custom]]`），每个目标在统计中单独成类
+    #[serde(default)]
+    pub custom: Vec<CustomTarget>,
+    /// 计算目录大小时是否跟随符号链接（默认 false；开启后被多处链接的目标会重复计入）
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// 并行计算目录大小的线程数上限（未设置时使用 rayon 全局线程池）
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// 列出子目录时是否跳过大小计算，改为高亮或选中时按需计算（默认 false）
+    #[serde(default)]
+    pub lazy_sizes: bool,
+    /// 预设扫描时跳过的类别标识（如 "downloads"），TUI 设置面板修改后写回配置文件
+    #[serde(default)]
+    pub disabled_categories: Vec<String>,
+}
+
+/// 带名称的自定义扫描目标
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct CustomTarget {
+    /// 显示名称（列表与分类统计中使用）
+    pub name: String,
+    /// 目标路径（支持 ~、~user 与环境变量）
+    pub path: String,
 }
 
 /// UI 配置
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct UiConfig {
     /// 默认排序方式: "name" / "size" / "time"
     #[serde(default)]
     pub default_sort: Option<String>,
+    /// 颜色主题
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// 是否启用鼠标（点击选择、滚轮移动；默认 true，关闭后可使用终端原生的选中复制）
+    #[serde(default = "default_true")]
+    pub mouse: bool,
+    /// 是否在列表每行显示相对大小条（默认 true）
+    #[serde(default = "default_true")]
+    pub show_bars: bool,
+    /// 上下移动到列表首尾时是否回绕到另一端（默认 true，false 时停在首/末项）
+    #[serde(default = "default_true")]
+    pub wrap_navigation: bool,
+    /// 有已选项目时按 q 是否先弹出确认（默认 true，无已选项目时总是直接退出）
+    #[serde(default = "default_true")]
+    pub confirm_quit: bool,
+    /// 列表中是否以相对时间（如 “3 天前”）显示修改时间（默认 false，显示 YYYY-MM-DD）
+    #[serde(default)]
+    pub relative_time: bool,
+    /// 修改时间的显示格式（支持 %Y %m %d %H %M %S %%，无效时回退到默认布局）
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// 大小显示单位: "binary"（KiB/MiB，默认）/ "decimal"（kB/MB，与 Finder 一致）
+    #[serde(default)]
+    pub size_units: SizeUnits,
+    /// 书签：常用扫描路径（支持 ~、~user 与环境变量），按 b 打开列表
+    #[serde(default)]
+    pub bookmarks: Vec<String>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            default_sort: None,
+            theme: ThemeConfig::default(),
+            mouse: default_true(),
+            show_bars: default_true(),
+            wrap_navigation: default_true(),
+            confirm_quit: default_true(),
+            relative_time: false,
+            date_format: None,
+            size_units: SizeUnits::default(),
+            bookmarks: Vec::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 颜色主题配置：选择预设并可逐项覆盖颜色（颜色名如 "cyan"，或十六进制如 "#89b4fa"）
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+pub struct ThemeConfig {
+    /// 预设主题: "default" / "dracula" / "solarized" / "mono"
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// 主色（标题、边框、选中项）
+    #[serde(default)]
+    pub primary: Option<String>,
+    /// 次要色
+    #[serde(default)]
+    pub secondary: Option<String>,
+    /// 强调色
+    #[serde(default)]
+    pub accent: Option<String>,
+    /// 警告色
+    #[serde(default)]
+    pub warning: Option<String>,
+    /// 危险操作色
+    #[serde(default)]
+    pub danger: Option<String>,
+    /// 成功提示色
+    #[serde(default)]
+    pub success: Option<String>,
+    /// 正文文字色
+    #[serde(default)]
+    pub text: Option<String>,
+    /// 次要文字色
+    #[serde(default)]
+    pub text_dim: Option<String>,
+    /// 背景色
+    #[serde(default)]
+    pub bg: Option<String>,
+    /// 高亮背景色
+    #[serde(default)]
+    pub bg_highlight: Option<String>,
 }
 
 /// 安全相关配置
@@ -41,40 +301,176 @@ pub struct SafetyConfig {
     /// 是否移至系统回收站而非永久删除（默认 false）
     #[serde(default)]
     pub move_to_trash: bool,
+    /// 安全模式：清理一律移至回收站，禁止永久删除、安全擦除与清空回收站（默认 false，优先于其他删除设置）
+    #[serde(default)]
+    pub force_trash: bool,
+    /// 是否在删除前以随机数据覆写文件（默认 false，优先于 move_to_trash）
+    #[serde(default)]
+    pub secure_delete: bool,
+    /// 删除遇到 macOS 不可变标志 (uchg) 时是否自动清除后重试（默认 false）
+    #[serde(default)]
+    pub clear_flags: bool,
+    /// 清理后重新统计实际释放的磁盘空间，而非累加扫描时的大小（默认 false，较慢）
+    #[serde(default)]
+    pub measure_freed_space: bool,
+    /// 清理后自底向上删除已清理目录下残留的空子目录（默认 false，目录本身保留）
+    #[serde(default)]
+    pub prune_empty_dirs: bool,
+    /// 任一条目清理失败后不再开始后续条目（默认 false，继续处理并在最后汇总错误）
+    #[serde(default)]
+    pub stop_on_error: bool,
+    /// 删除遇到暂时性错误（资源被占用）时的重试次数（未设置时为 `DEFAULT_DELETE_RETRIES`）
+    #[serde(default)]
+    pub delete_retries: Option<u32>,
+    /// 除用户目录与临时目录外允许清理的根目录（支持 ~、~user 与环境变量）
+    #[serde(default)]
+    pub allowed_roots: Vec<String>,
+    /// 在内置禁止路径之外额外禁止清理的路径，及其下所有内容（支持 ~、~user 与环境变量）
+    #[serde(default)]
+    pub forbidden_extra: Vec<String>,
+    /// 移至回收站的类别标识（如 "downloads"），其余带类别的条目永久删除；为空时统一按 move_to_trash 处理
+    #[serde(default)]
+    pub trash_categories: Vec<String>,
+    /// 扫描完成后自动选中修改时间早于该天数的条目（未设置时不自动选中）
+    #[serde(default)]
+    pub auto_select_older_than_days: Option<u64>,
+    /// 打开清理确认弹窗时直接显示 dry-run 预览（默认 false）
+    #[serde(default)]
+    pub confirm_dry_run_first: bool,
+    /// 永久删除总大小超过该值时须输入确认词（如 "5G"，未设置时不要求）
+    #[serde(default)]
+    pub confirm_word_above: Option<String>,
 }
 
 impl AppConfig {
-    /// 从 ~/.config/vac/config.toml 加载配置，失败时返回默认配置
+    /// 从 `config_location(None)` 所指文件加载配置，失败时返回默认配置
     pub fn load() -> Self {
-        let config_path = Self::config_path();
-        if !config_path.exists() {
-            return Self::default();
+        Self::load_result(None).unwrap_or_default()
+    }
+
+    /// 从 `config_location(cli_path)` 所指文件加载配置
+    ///
+    /// 默认路径下文件不存在时返回默认配置；显式指定的文件不存在，或文件无法读取、解析时返回错误，
+    /// 由调用方决定如何提示。
+    pub fn load_result(cli_path: Option<&Path>) -> Result<Self, ConfigError> {
+        Self::load_location(&Self::config_location(cli_path))
+    }
+
+    /// 从指定位置加载配置（规则同 `load_result`）
+    pub fn load_location(location: &ConfigLocation) -> Result<Self, ConfigError> {
+        if location.explicit && !location.path.exists() {
+            return Err(ConfigError::NotFound {
+                path: location.path.clone(),
+            });
         }
-        match fs::read_to_string(&config_path) {
-            Ok(content) => toml::from_str(&content).unwrap_or_default(),
-            Err(_) => Self::default(),
+        Self::load_from(&location.path)
+    }
+
+    /// 从指定位置加载配置；文件无效时回退到默认配置并一并返回错误，供入口自行提示或拒绝继续
+    ///
+    /// 显式指定的配置文件不存在时直接返回错误，不回退到默认配置。
+    pub fn load_or_default(
+        location: &ConfigLocation,
+    ) -> Result<(Self, Option<ConfigError>), ConfigError> {
+        match Self::load_location(location) {
+            Ok(config) => Ok((config, None)),
+            Err(err @ ConfigError::NotFound { .. }) => Err(err),
+            Err(err) => Ok((Self::default().with_source_path(&location.path), Some(err))),
         }
     }
 
-    /// 配置文件路径
-    fn config_path() -> PathBuf {
-        directories::UserDirs::new()
-            .map(|dirs| {
-                dirs.home_dir()
-                    .join(".config")
-                    .join("vac")
-                    .join("config.toml")
-            })
+    /// 从指定文件加载配置（规则同 `load_result`）
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default().with_source_path(path));
+            }
+            Err(err) => {
+                return Err(ConfigError::Read {
+                    path: path.to_path_buf(),
+                    message: err.to_string(),
+                });
+            }
+        };
+        let config = toml::from_str::<Self>(&content).map_err(|err| ConfigError::Parse {
+            path: path.to_path_buf(),
+            line: err
+                .span()
+                .map(|span| content[..span.start].matches('\n').count() + 1),
+            message: err.message().to_string(),
+        })?;
+        Ok(config.with_source_path(path))
+    }
+
+    /// 记录配置文件路径（VAC 据此保护该文件不被清理）
+    pub fn with_source_path(self, path: &Path) -> Self {
+        Self {
+            source_path: Some(path.to_path_buf()),
+            ..self
+        }
+    }
+
+    /// 将带注释的默认配置写入指定文件（自动创建父目录）
+    ///
+    /// 文件已存在且未指定 `force` 时返回 `AlreadyExists` 错误，不覆盖原文件。
+    pub fn write_default_config(path: &Path, force: bool) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true);
+        if force {
+            options.create(true).truncate(true);
+        } else {
+            options.create_new(true);
+        }
+        let mut file = options.open(path)?;
+        std::io::Write::write_all(&mut file, DEFAULT_CONFIG_TEMPLATE.as_bytes())
+    }
+
+    /// 配置文件位置：`--config` 优先，其次 `VAC_CONFIG` 环境变量，最后为默认路径
+    pub fn config_location(cli_path: Option<&Path>) -> ConfigLocation {
+        Self::config_location_with(cli_path, std::env::var_os(CONFIG_ENV_VAR))
+    }
+
+    /// 按给定的命令行参数与环境变量值确定配置文件位置（空的环境变量视为未设置）
+    pub fn config_location_with(
+        cli_path: Option<&Path>,
+        env_value: Option<OsString>,
+    ) -> ConfigLocation {
+        if let Some(path) = cli_path {
+            return ConfigLocation {
+                path: path.to_path_buf(),
+                explicit: true,
+            };
+        }
+        match env_value.filter(|value| !value.is_empty()) {
+            Some(value) => ConfigLocation {
+                path: PathBuf::from(expand_path(&value.to_string_lossy())),
+                explicit: true,
+            },
+            None => ConfigLocation {
+                path: Self::default_config_path(),
+                explicit: false,
+            },
+        }
+    }
+
+    /// 默认配置文件路径（~/.config/vac/config.toml）
+    pub fn default_config_path() -> PathBuf {
+        crate::utils::home_dir()
+            .map(|home| home.join(".config").join("vac").join("config.toml"))
             .unwrap_or_else(|| PathBuf::from(".config/vac/config.toml"))
     }
 
-    /// 获取展开后的额外扫描目标路径（~ 展开为主目录，过滤不存在的路径）
+    /// 获取展开后的额外扫描目标路径（展开 ~ 与环境变量，过滤不存在的路径）
     pub fn expanded_extra_targets(&self) -> Vec<PathBuf> {
         self.scan
             .extra_targets
             .iter()
             .filter_map(|raw_path| {
-                let expanded = PathBuf::from(expand_tilde(raw_path));
+                let expanded = PathBuf::from(expand_path(raw_path));
                 if expanded.exists() {
                     Some(expanded)
                 } else {
@@ -83,6 +479,142 @@ impl AppConfig {
             })
             .collect()
     }
+
+    /// 获取展开后的带名称自定义扫描目标 `(名称, 路径)`（过滤不存在的路径）
+    pub fn expanded_custom_targets(&self) -> Vec<(String, PathBuf)> {
+        self.scan
+            .custom
+            .iter()
+            .filter_map(|target| {
+                let expanded = PathBuf::from(expand_path(&target.path));
+                expanded.exists().then(|| (target.name.clone(), expanded))
+            })
+            .collect()
+    }
+
+    /// 获取展开后的书签路径（保留不存在的路径，由界面标记为不可用）
+    pub fn expanded_bookmarks(&self) -> Vec<PathBuf> {
+        self.ui
+            .bookmarks
+            .iter()
+            .map(|raw_path| PathBuf::from(expand_path(raw_path)))
+            .collect()
+    }
+
+    /// 获取展开后的允许清理根目录（展开 ~ 与环境变量）
+    pub fn expanded_allowed_roots(&self) -> Vec<PathBuf> {
+        self.safety
+            .allowed_roots
+            .iter()
+            .map(|raw_path| PathBuf::from(expand_path(raw_path)))
+            .collect()
+    }
+
+    /// 获取展开后的额外禁止清理路径（展开 ~ 与环境变量）
+    pub fn expanded_forbidden_extra(&self) -> Vec<PathBuf> {
+        self.safety
+            .forbidden_extra
+            .iter()
+            .map(|raw_path| PathBuf::from(expand_path(raw_path)))
+            .collect()
+    }
+
+    /// 解析 `safety.trash_categories`（忽略无法识别的类别标识）
+    pub fn trash_categories(&self) -> Vec<ItemCategory> {
+        self.safety
+            .trash_categories
+            .iter()
+            .filter_map(|slug| ItemCategory::from_slug(slug))
+            .collect()
+    }
+
+    /// 解析 `scan.disabled_categories`（忽略无法识别的类别标识）
+    pub fn disabled_categories(&self) -> Vec<ItemCategory> {
+        self.scan
+            .disabled_categories
+            .iter()
+            .filter_map(|slug| ItemCategory::from_slug(slug))
+            .collect()
+    }
+
+    /// 将 `scan.disabled_categories` 写回配置文件（自动创建父目录）
+    ///
+    /// 只替换该数组，其余内容（包括注释与排版）原样保留；原数组中无法识别的类别标识也会保留。
+    /// 文件无法解析时返回错误，不覆盖原文件。
+    pub fn save_disabled_categories(
+        path: &Path,
+        categories: &[ItemCategory],
+    ) -> std::io::Result<()> {
+        let invalid_data =
+            |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+        let mut document = match fs::read_to_string(path) {
+            Ok(content) => content
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|err| invalid_data(err.message().to_string()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => toml_edit::DocumentMut::new(),
+            Err(err) => return Err(err),
+        };
+        let scan = document
+            .entry("scan")
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| invalid_data("scan 不是表".to_string()))?;
+
+        let mut slugs = toml_edit::Array::new();
+        if let Some(existing) = scan
+            .get("disabled_categories")
+            .and_then(|item| item.as_array())
+        {
+            for value in existing.iter() {
+                if let Some(slug) = value.as_str()
+                    && ItemCategory::from_slug(slug).is_none()
+                {
+                    slugs.push(slug);
+                }
+            }
+        }
+        for category in categories {
+            slugs.push(category.slug());
+        }
+        match scan
+            .get_mut("disabled_categories")
+            .and_then(|item| item.as_array_mut())
+        {
+            Some(existing) => {
+                let decor = existing.decor().clone();
+                *existing = slugs;
+                *existing.decor_mut() = decor;
+            }
+            None => {
+                scan.insert("disabled_categories", toml_edit::value(slugs));
+            }
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, document.to_string())
+    }
+
+    /// 解析 `safety.confirm_word_above` 为字节数（未设置时返回 None）
+    ///
+    /// 无法解析时返回 `Some(0)`：每次永久删除都须输入确认词，而不是关闭该保护。
+    pub fn confirm_word_threshold(&self) -> Option<u64> {
+        let raw = self.safety.confirm_word_above.as_deref()?;
+        Some(
+            raw.trim()
+                .parse::<bytesize::ByteSize>()
+                .map_or(0, |size| size.as_u64()),
+        )
+    }
+
+    /// `safety.confirm_word_above` 已设置但无法解析时返回原值，供入口提示
+    pub fn invalid_confirm_word_above(&self) -> Option<&str> {
+        self.safety
+            .confirm_word_above
+            .as_deref()
+            .filter(|raw| raw.trim().parse::<bytesize::ByteSize>().is_err())
+    }
 }
 
 #[cfg(test)]
@@ -136,9 +668,15 @@ default_sort = "time"
                     "/tmp".to_string(),
                     "/nonexistent_vac_path_12345".to_string(),
                 ],
+                custom: Vec::new(),
+                follow_symlinks: false,
+                max_concurrency: None,
+                lazy_sizes: false,
+                disabled_categories: Vec::new(),
             },
             ui: UiConfig::default(),
             safety: SafetyConfig::default(),
+            source_path: None,
         };
         let expanded = config.expanded_extra_targets();
         assert_eq!(expanded.len(), 1);
@@ -161,6 +699,54 @@ move_to_trash = true
         assert!(config.safety.move_to_trash);
     }
 
+    #[test]
+    fn parse_safety_config_secure_delete() {
+        let toml_str = r#"
+[safety]
+secure_delete = true
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        assert!(config.safety.secure_delete);
+        assert!(!config.safety.move_to_trash);
+        assert!(!config.safety.clear_flags);
+    }
+
+    #[test]
+    fn parse_safety_config_clear_flags() {
+        let toml_str = r#"
+[safety]
+clear_flags = true
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        assert!(config.safety.clear_flags);
+    }
+
+    #[test]
+    fn parse_safety_config_allowed_roots_expands_tilde() {
+        let toml_str = r#"
+[safety]
+allowed_roots = ["/Volumes/Dev", "~/external"]
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        let roots = config.expanded_allowed_roots();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0], PathBuf::from("/Volumes/Dev"));
+        assert!(!roots[1].to_string_lossy().starts_with('~'));
+    }
+
+    #[test]
+    fn parse_safety_config_forbidden_extra_expands_tilde() {
+        let toml_str = r#"
+[safety]
+forbidden_extra = ["~/Dropbox", "~/Documents"]
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        let forbidden = config.expanded_forbidden_extra();
+        assert_eq!(forbidden.len(), 2);
+        assert!(forbidden[0].ends_with("Dropbox"));
+        assert!(!forbidden[0].to_string_lossy().starts_with('~'));
+    }
+
     #[test]
     fn parse_full_config_with_safety() {
         let toml_str = r#"
@@ -179,6 +765,82 @@ move_to_trash = true
         assert!(config.safety.move_to_trash);
     }
 
+    #[test]
+    fn parse_ui_theme_config() {
+        let toml_str = r##"
+[ui.theme]
+preset = "dracula"
+primary = "#89b4fa"
+"##;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        assert_eq!(config.ui.theme.preset.as_deref(), Some("dracula"));
+        assert_eq!(config.ui.theme.primary.as_deref(), Some("#89b4fa"));
+        assert!(config.ui.theme.accent.is_none());
+    }
+
+    #[test]
+    fn parse_ui_mouse_defaults_to_enabled() {
+        assert!(AppConfig::default().ui.mouse);
+
+        let config: AppConfig = toml::from_str("[ui]\ndefault_sort = \"size\"\n").expect("parse");
+        assert!(config.ui.mouse);
+
+        let config: AppConfig = toml::from_str("[ui]\nmouse = false\n").expect("parse");
+        assert!(!config.ui.mouse);
+    }
+
+    #[test]
+    fn parse_ui_show_bars() {
+        assert!(AppConfig::default().ui.show_bars);
+
+        let config: AppConfig = toml::from_str("[ui]\nshow_bars = false\n").expect("parse");
+        assert!(!config.ui.show_bars);
+        assert!(config.ui.mouse);
+    }
+
+    #[test]
+    fn parse_ui_relative_time() {
+        assert!(!AppConfig::default().ui.relative_time);
+
+        let config: AppConfig = toml::from_str("[ui]\nrelative_time = true\n").expect("parse");
+        assert!(config.ui.relative_time);
+    }
+
+    #[test]
+    fn parse_ui_size_units() {
+        assert_eq!(AppConfig::default().ui.size_units, SizeUnits::Binary);
+
+        let config: AppConfig = toml::from_str("[ui]\nsize_units = \"decimal\"\n").expect("parse");
+        assert_eq!(config.ui.size_units, SizeUnits::Decimal);
+        assert!(toml::from_str::<AppConfig>("[ui]\nsize_units = \"metric\"\n").is_err());
+    }
+
+    #[test]
+    fn parse_ui_bookmarks_expands_paths() {
+        assert!(AppConfig::default().expanded_bookmarks().is_empty());
+
+        let config: AppConfig =
+            toml::from_str("[ui]\nbookmarks = [\"/tmp/projects\", \"$HOME/work\"]\n")
+                .expect("parse");
+        let home = std::env::var("HOME").expect("HOME set");
+        assert_eq!(
+            config.expanded_bookmarks(),
+            vec![
+                PathBuf::from("/tmp/projects"),
+                PathBuf::from(format!("{home}/work"))
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ui_date_format() {
+        assert!(AppConfig::default().ui.date_format.is_none());
+
+        let config: AppConfig =
+            toml::from_str("[ui]\ndate_format = \"%Y/%m/%d %H:%M\"\n").expect("parse");
+        assert_eq!(config.ui.date_format.as_deref(), Some("%Y/%m/%d %H:%M"));
+    }
+
     #[test]
     fn parse_toml_without_safety_uses_default() {
         let toml_str = r#"
@@ -188,4 +850,266 @@ extra_targets = []
         let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
         assert!(!config.safety.move_to_trash);
     }
+
+    #[test]
+    fn parse_named_custom_targets_alongside_extra_targets() {
+        let toml_str = r#"
+[scan]
+extra_targets = ["/tmp"]
+
+[[scan.custom]]
+name = "My Projects"
+path = "/tmp"
+
+[[scan.custom]]
+name = "Gone"
+path = "/nonexistent_vac_path_12345"
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        assert_eq!(config.scan.extra_targets, vec!["/tmp".to_string()]);
+        assert_eq!(
+            config.scan.custom[0],
+            CustomTarget {
+                name: "My Projects".to_string(),
+                path: "/tmp".to_string(),
+            }
+        );
+        assert_eq!(
+            config.expanded_custom_targets(),
+            vec![("My Projects".to_string(), PathBuf::from("/tmp"))]
+        );
+    }
+
+    #[test]
+    fn load_from_missing_file_uses_defaults() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let config = AppConfig::load_from(&dir.path().join("config.toml")).expect("defaults");
+        assert!(config.scan.extra_targets.is_empty());
+        assert!(config.ui.mouse);
+    }
+
+    #[test]
+    fn load_from_malformed_toml_returns_error() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[ui]\nmouse = false\ndefault_sort = \"size\n").expect("write config");
+
+        let err = AppConfig::load_from(&path).expect_err("malformed toml");
+        match &err {
+            ConfigError::Parse { line, .. } => assert_eq!(*line, Some(3)),
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().contains("第 3 行"));
+    }
+
+    #[test]
+    fn load_from_rejects_mistyped_values() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[ui]\nmouse = \"no\"\n").expect("write config");
+
+        assert!(matches!(
+            AppConfig::load_from(&path),
+            Err(ConfigError::Parse { line: Some(2), .. })
+        ));
+    }
+
+    #[test]
+    fn default_config_template_round_trips_to_defaults() {
+        let config: AppConfig = toml::from_str(DEFAULT_CONFIG_TEMPLATE).expect("parse template");
+        let defaults = AppConfig::default();
+        assert_eq!(config.scan.extra_targets, defaults.scan.extra_targets);
+        assert!(config.scan.custom.is_empty());
+        assert_eq!(config.scan.follow_symlinks, defaults.scan.follow_symlinks);
+        assert_eq!(config.scan.max_concurrency, defaults.scan.max_concurrency);
+        assert_eq!(config.scan.lazy_sizes, defaults.scan.lazy_sizes);
+        assert_eq!(config.ui.default_sort, defaults.ui.default_sort);
+        assert_eq!(config.ui.mouse, defaults.ui.mouse);
+        assert_eq!(config.ui.show_bars, defaults.ui.show_bars);
+        assert_eq!(config.ui.wrap_navigation, defaults.ui.wrap_navigation);
+        assert_eq!(config.ui.confirm_quit, defaults.ui.confirm_quit);
+        assert_eq!(config.ui.relative_time, defaults.ui.relative_time);
+        assert_eq!(config.ui.date_format, defaults.ui.date_format);
+        assert_eq!(config.ui.size_units, defaults.ui.size_units);
+        assert_eq!(config.ui.theme, defaults.ui.theme);
+        assert_eq!(config.safety.move_to_trash, defaults.safety.move_to_trash);
+        assert_eq!(config.safety.secure_delete, defaults.safety.secure_delete);
+        assert_eq!(config.safety.force_trash, defaults.safety.force_trash);
+        assert_eq!(config.safety.clear_flags, defaults.safety.clear_flags);
+        assert_eq!(
+            config.safety.measure_freed_space,
+            defaults.safety.measure_freed_space
+        );
+        assert_eq!(
+            config.safety.trash_categories,
+            defaults.safety.trash_categories
+        );
+        assert_eq!(
+            config.safety.confirm_dry_run_first,
+            defaults.safety.confirm_dry_run_first
+        );
+        assert_eq!(
+            config.safety.confirm_word_above,
+            defaults.safety.confirm_word_above
+        );
+    }
+
+    #[test]
+    fn write_default_config_refuses_to_overwrite_without_force() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("vac").join("config.toml");
+
+        AppConfig::write_default_config(&path, false).expect("write config");
+        assert_eq!(
+            fs::read_to_string(&path).expect("read config"),
+            DEFAULT_CONFIG_TEMPLATE
+        );
+
+        fs::write(&path, "[ui]\nmouse = false\n").expect("edit config");
+        let err = AppConfig::write_default_config(&path, false).expect_err("existing file");
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert!(!AppConfig::load_from(&path).expect("load").ui.mouse);
+
+        AppConfig::write_default_config(&path, true).expect("force overwrite");
+        assert!(AppConfig::load_from(&path).expect("load").ui.mouse);
+    }
+
+    #[test]
+    fn config_location_prefers_flag_then_env_then_default() {
+        let flag = PathBuf::from("/tmp/flag.toml");
+        let env = Some(OsString::from("/tmp/env.toml"));
+
+        let location = AppConfig::config_location_with(Some(&flag), env.clone());
+        assert_eq!(
+            location,
+            ConfigLocation {
+                path: flag.clone(),
+                explicit: true,
+            }
+        );
+
+        let location = AppConfig::config_location_with(None, env);
+        assert_eq!(location.path, PathBuf::from("/tmp/env.toml"));
+        assert!(location.explicit);
+
+        for unset in [None, Some(OsString::new())] {
+            let location = AppConfig::config_location_with(None, unset);
+            assert_eq!(location.path, AppConfig::default_config_path());
+            assert!(!location.explicit);
+        }
+    }
+
+    #[test]
+    fn load_location_requires_explicit_file_to_exist() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("profile.toml");
+
+        let implicit = ConfigLocation {
+            path: path.clone(),
+            explicit: false,
+        };
+        assert!(AppConfig::load_location(&implicit).is_ok());
+
+        let explicit = ConfigLocation {
+            path: path.clone(),
+            explicit: true,
+        };
+        assert_eq!(
+            AppConfig::load_location(&explicit).expect_err("missing profile"),
+            ConfigError::NotFound { path: path.clone() }
+        );
+
+        fs::write(&path, "[ui]\nmouse = false\n").expect("write profile");
+        assert!(!AppConfig::load_location(&explicit).expect("load").ui.mouse);
+    }
+
+    #[test]
+    fn parse_trash_categories_ignores_unknown_slugs() {
+        let toml_str = r#"
+[safety]
+move_to_trash = false
+trash_categories = ["downloads", "Trash", "bogus"]
+"#;
+        let config: AppConfig = toml::from_str(toml_str).expect("parse toml");
+        assert_eq!(
+            config.trash_categories(),
+            vec![ItemCategory::Downloads, ItemCategory::Trash]
+        );
+        assert!(AppConfig::default().trash_categories().is_empty());
+    }
+
+    #[test]
+    fn parse_confirm_word_threshold_with_bytesize() {
+        let config: AppConfig =
+            toml::from_str("[safety]\nconfirm_word_above = \"5G\"\n").expect("parse toml");
+        assert_eq!(config.confirm_word_threshold(), Some(5_000_000_000));
+
+        assert_eq!(config.invalid_confirm_word_above(), None);
+        assert_eq!(AppConfig::default().confirm_word_threshold(), None);
+
+        // 无法解析的阈值按 0 处理（总是要求确认词），其他安全设置照常生效
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[safety]\nforce_trash = true\nforbidden_extra = [\"/tmp/keep\"]\nconfirm_word_above = \"lots\"\n",
+        )
+        .expect("write config");
+        let location = ConfigLocation {
+            path: path.clone(),
+            explicit: true,
+        };
+        let (config, error) = AppConfig::load_or_default(&location).expect("load config");
+        assert_eq!(error, None);
+        assert!(config.safety.force_trash);
+        assert_eq!(config.safety.forbidden_extra, vec!["/tmp/keep".to_string()]);
+        assert_eq!(config.confirm_word_threshold(), Some(0));
+        assert_eq!(config.invalid_confirm_word_above(), Some("lots"));
+
+        // 其他字段无效时整体回退到默认配置并返回错误
+        fs::write(&path, "[safety]\nforce_trash = 1\n").expect("write config");
+        let (config, error) = AppConfig::load_or_default(&location).expect("load config");
+        assert!(matches!(error, Some(ConfigError::Parse { .. })));
+        assert!(!config.safety.force_trash);
+    }
+
+    #[test]
+    fn save_disabled_categories_round_trips_and_keeps_other_keys() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("vac/config.toml");
+        AppConfig::save_disabled_categories(&path, &[ItemCategory::Downloads])
+            .expect("save to missing file");
+        assert_eq!(
+            AppConfig::load_from(&path)
+                .expect("load")
+                .disabled_categories(),
+            vec![ItemCategory::Downloads]
+        );
+
+        fs::write(
+            &path,
+            "# 扫描配置\n[scan]\nextra_targets = [\"/tmp\"] # 额外目标\ndisabled_categories = [\"bogus\", \"downloads\"]\n\n[ui]\nmouse = false\n",
+        )
+        .expect("write config");
+        AppConfig::save_disabled_categories(&path, &[ItemCategory::Trash, ItemCategory::Logs])
+            .expect("save");
+        let content = fs::read_to_string(&path).expect("read");
+        assert!(content.contains("# 扫描配置\n[scan]\n"));
+        assert!(content.contains("# 额外目标"));
+        let config = AppConfig::load_from(&path).expect("load");
+        assert_eq!(
+            config.scan.disabled_categories,
+            vec!["bogus".to_string(), "trash".to_string(), "logs".to_string()]
+        );
+        assert_eq!(
+            config.disabled_categories(),
+            vec![ItemCategory::Trash, ItemCategory::Logs]
+        );
+        assert_eq!(config.scan.extra_targets, vec!["/tmp".to_string()]);
+        assert!(!config.ui.mouse);
+
+        fs::write(&path, "[scan\n").expect("write broken config");
+        assert!(AppConfig::save_disabled_categories(&path, &[]).is_err());
+        assert_eq!(fs::read_to_string(&path).expect("read"), "[scan\n");
+    }
 }
@@ -2,7 +2,9 @@ pub mod app;
 pub mod cleaner;
 pub mod cli;
 pub mod config;
+pub mod report;
 pub mod scanner;
+pub mod state;
 pub mod ui;
 pub mod utils;
 
@@ -1,48 +1,218 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::{self, Receiver};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
 use clap::Parser;
 use color_eyre::Result;
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
-
-use vac::app::{App, CleanableEntry, EntryKind, Mode, SortOrder, sort_entries_by};
-use vac::cleaner::Cleaner;
-use vac::cli::Cli;
-use vac::config::AppConfig;
-use vac::scanner::{ScanKind, ScanMessage, Scanner, format_size, scanner_from_config};
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseEvent, MouseEventKind,
+};
+use ratatui::crossterm::execute;
+
+use vac::app::{
+    App, CleanableEntry, EntryKind, ItemCategory, Mode, RETENTION_KEEP, SortOrder, sort_entries_by,
+    with_nested_root_sizes,
+};
+use vac::cleaner::{
+    CleanError, CleanMessage, CleanOptions, CleanResult, Cleaner, DryRunMessage,
+    SECURE_DELETE_PASSES, SafetyRules, TrashPolicy,
+};
+use vac::cli::{Cli, ScanTarget};
+use vac::config::{AppConfig, ConfigError, ConfigLocation};
+use vac::report::{
+    CleanReport, DeleteReport, DryRunReport, DryRunReportItem, EmptyTrashReport, NdjsonLine,
+    NdjsonSummary, REPORT_SCHEMA_VERSION, ReportCategory, ReportEntry, ScanReport,
+    ScanTargetReport, ScanTargetsReport,
+};
+use vac::scanner::{
+    PeekResult, ScanKind, ScanMessage, Scanner, aggregate_by_category, format_size, peek_dir,
+    scanner_from_config, set_size_units,
+};
+use vac::state::SessionState;
 use vac::ui;
-use vac::utils::format_time;
+use vac::utils::{HOME_ENV_VAR, csv_escape, format_time_with, overlaps_any};
 
 const POLL_INTERVAL_SCANNING_MS: u64 = 16;
 const POLL_INTERVAL_IDLE_MS: u64 = 100;
 const SCAN_JOB_ID_BLOCKING: u64 = 1;
 const SCAN_INIT_ERROR_MESSAGE: &str = "无法初始化扫描器";
 const REPORT_SEPARATOR_WIDTH: usize = 70;
+const PEEK_ENTRY_LIMIT: usize = 50;
+/// 帮助/确认弹窗中 PageUp/PageDown 一次滚动的行数
+const POPUP_PAGE_SCROLL: usize = 10;
+
+/// 非交互模式退出码：清理或清空回收站有部分失败
+const EXIT_PARTIAL_FAILURE: i32 = 1;
+/// 非交互模式退出码：参数、扫描或输出出错（与 clap 参数错误的退出码一致）
+const EXIT_ERROR: i32 = 2;
+
+/// 启动时检查用户主目录：扫描目标、回收站、安全规则与默认配置路径都依赖它
+fn check_home_dir() -> std::result::Result<(), String> {
+    match vac::utils::home_dir() {
+        Some(home) if home.is_dir() => Ok(()),
+        Some(home) => Err(format!(
+            "用户主目录不存在: {}\n请将 {} 环境变量设置为一个存在的绝对路径后重试，例如: {}=/path/to/home vac",
+            home.display(),
+            HOME_ENV_VAR,
+            HOME_ENV_VAR
+        )),
+        None => Err(format!(
+            "无法确定用户主目录\n请将 {} 环境变量设置为一个存在的绝对路径后重试，例如: {}=/path/to/home vac",
+            HOME_ENV_VAR, HOME_ENV_VAR
+        )),
+    }
+}
 
 fn main() -> Result<()> {
     color_eyre::install()?;
 
     let cli = Cli::parse();
+    if let Err(err) = cli.validate() {
+        err.exit();
+    }
+
+    if let Err(message) = check_home_dir() {
+        eprintln!("错误: {message}");
+        std::process::exit(EXIT_ERROR);
+    }
+
+    let config_location = AppConfig::config_location(cli.config.as_deref());
+
+    if cli.init_config {
+        let path = config_location.path;
+        match AppConfig::write_default_config(&path, cli.force) {
+            Ok(()) => {
+                println!("已写入配置文件: {}", path.display());
+                return Ok(());
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                eprintln!(
+                    "错误: 配置文件已存在: {}（使用 --force 覆盖）",
+                    path.display()
+                );
+                std::process::exit(EXIT_ERROR);
+            }
+            Err(err) => {
+                eprintln!("错误: 无法写入配置文件 {}: {}", path.display(), err);
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    if cli.list_targets {
+        if let Err(err) = run_list_targets(&cli, &config_location) {
+            eprintln!("错误: {err:?}");
+            std::process::exit(EXIT_ERROR);
+        }
+        return Ok(());
+    }
 
     if cli.is_non_interactive() {
-        return run_non_interactive(cli);
+        match run_non_interactive(cli, &config_location) {
+            Ok(true) => return Ok(()),
+            Ok(false) => std::process::exit(EXIT_PARTIAL_FAILURE),
+            Err(err) => {
+                eprintln!("错误: {err:?}");
+                std::process::exit(EXIT_ERROR);
+            }
+        }
     }
 
+    let (config, config_error) = match AppConfig::load_or_default(&config_location) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("错误: {}", err);
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+    set_size_units(config.ui.size_units);
     let mut terminal = ratatui::init();
-    let result = run_tui(&mut terminal);
+    if config.ui.mouse {
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+        // ratatui::init() 的 panic hook 只恢复终端模式，需额外关闭鼠标捕获
+        let restore_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = execute!(std::io::stdout(), DisableMouseCapture);
+            restore_hook(info);
+        }));
+    }
+    let result = run_tui(
+        &mut terminal,
+        &cli,
+        &config,
+        &config_location.path,
+        config_error,
+    );
 
+    if config.ui.mouse {
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    }
     ratatui::restore();
     result
 }
 
-fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
-    let config = AppConfig::load();
-    let mut app = App::with_config(&config);
+/// 后台清理任务
+struct CleanJob {
+    rx: Receiver<CleanMessage>,
+    cancel: Arc<AtomicBool>,
+    items: Vec<CleanableEntry>,
+    /// 是否为清空回收站（完成后不重新扫描当前视图）
+    empty_trash: bool,
+}
+
+fn run_tui(
+    terminal: &mut ratatui::DefaultTerminal,
+    cli: &Cli,
+    config: &AppConfig,
+    config_path: &Path,
+    config_error: Option<ConfigError>,
+) -> Result<()> {
+    let mut app = App::with_config(config);
+    if let Some(raw) = config.invalid_confirm_word_above() {
+        app.notice = Some(format!(
+            "⚠ confirm_word_above 无法解析为大小: {:?}，所有永久删除都须输入确认词",
+            raw
+        ));
+    }
+    if let Some(err) = config_error {
+        // 默认配置会丢失用户的安全设置（禁止路径、安全模式等），修复前只允许移至回收站
+        app.force_trash = true;
+        app.notice = Some(format!(
+            "⚠ {}，已使用默认配置并进入安全模式（仅移至回收站），请修复后重启",
+            err
+        ));
+    }
+    app.use_scan_cache = !cli.no_cache;
+    app.use_secure_delete |= cli.secure;
+    app.clean_options.stop_on_error |= cli.strict;
+    app.force_trash |= cli.safe;
+    app.last_scan = SessionState::load().valid_last_scan();
     let mut scan_rx: Option<Receiver<ScanMessage>> = None;
+    let mut peek_rx: Option<Receiver<Result<PeekResult, String>>> = None;
+    let mut retention_rx: Option<Receiver<RetentionResult>> = None;
+    let mut detail_rx: Option<Receiver<(PathBuf, usize, usize)>> = None;
+    // 详情统计的代数；新的请求使进行中的统计提前停止
+    let detail_generation = Arc::new(AtomicU64::new(0));
+    let mut dry_run_rx: Option<Receiver<DryRunMessage>> = None;
+    let mut clean_job: Option<CleanJob> = None;
     let cancel_generation = Arc::new(AtomicU64::new(0));
+    // 首次复制时创建；X11 等平台上剪贴板内容随该对象存活，因此保留到退出
+    let mut clipboard: Option<arboard::Clipboard> = None;
+    // 树形视图展开目录的扫描共用一个通道；关闭树形视图时递增代数以丢弃未完成的扫描
+    let (tree_tx, tree_rx) = mpsc::channel::<ScanMessage>();
+    let tree_generation = Arc::new(AtomicU64::new(0));
+    let mut tree_pending = 0usize;
+    // 快速列表按需计算目录大小的结果通道，按扫描代数过滤过期结果
+    let (lazy_size_tx, lazy_size_rx) = mpsc::channel::<ScanMessage>();
+    let mut lazy_size_pending = 0usize;
+    // 回收站大小在启动、清理与清空回收站后于后台重新统计
+    let mut trash_size_rx = Some(spawn_trash_size_thread());
 
     loop {
         terminal.draw(|frame| ui::render(frame, &mut app))?;
@@ -56,7 +226,7 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
 
                 match msg {
                     ScanMessage::Progress { progress, path, .. } => {
-                        app.scan_progress = progress;
+                        app.record_scan_progress(progress, Instant::now());
                         app.current_scan_path = path;
                     }
                     ScanMessage::RootItem { entry, .. } => {
@@ -73,13 +243,27 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                             ScanKind::Root | ScanKind::DiskScan => app.sort_root_entries(),
                             ScanKind::ListDir => app.sort_dir_entries(),
                         }
+                        app.prune_stale_selections();
+                        app.restore_pending_cursor();
+                        let auto_selected = app.auto_select_stale_entries(SystemTime::now());
+                        if auto_selected > 0 {
+                            app.notice = Some(format!("自动选中 {} 个陈旧项目", auto_selected));
+                        }
                         app.finish_scan();
+                        app.refresh_disk_usage();
+                        if let Some(target) = app.complete_scan_target() {
+                            let _ = SessionState {
+                                last_scan: Some(target),
+                            }
+                            .save();
+                        }
                         scan_rx = None;
                         break;
                     }
                     ScanMessage::Error { message, .. } => {
                         app.set_error(message);
                         app.finish_scan();
+                        app.pending_scan_target = None;
                         scan_rx = None;
                         break;
                     }
@@ -87,13 +271,159 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
             }
         }
 
-        let poll_timeout = if scan_rx.is_some() {
+        // 处理清理进度
+        if let Some(job) = &clean_job {
+            let mut finished = None;
+            while let Ok(msg) = job.rx.try_recv() {
+                match msg {
+                    CleanMessage::Progress {
+                        completed,
+                        total,
+                        path,
+                        size,
+                    } => app.update_clean_progress(completed, total, &path, size),
+                    CleanMessage::Done(result) => {
+                        finished = Some(result);
+                        break;
+                    }
+                }
+            }
+            if let Some(result) = finished
+                && let Some(job) = clean_job.take()
+            {
+                if job.empty_trash {
+                    finish_empty_trash(&mut app, result);
+                } else if let Some(rx) =
+                    finish_clean(&mut app, &job.items, result, &cancel_generation, config)
+                {
+                    scan_rx = Some(rx);
+                }
+                trash_size_rx = Some(spawn_trash_size_thread());
+            }
+        }
+
+        // 回收站大小统计结果
+        if let Some(rx) = &trash_size_rx
+            && let Ok(size) = rx.try_recv()
+        {
+            app.trash_usage = size;
+            trash_size_rx = None;
+        }
+
+        // 处理目录预览结果
+        if let Some(rx) = &peek_rx
+            && let Ok(result) = rx.try_recv()
+        {
+            app.apply_peek_result(result);
+            peek_rx = None;
+        }
+
+        // 详情面板：按需统计高亮目录的内容
+        if let Some(path) = app.take_detail_request() {
+            detail_rx = Some(spawn_detail_thread(path, &detail_generation));
+        }
+        if let Some(rx) = &detail_rx
+            && let Ok((path, files, dirs)) = rx.try_recv()
+        {
+            app.apply_detail_counts(path, files, dirs);
+            detail_rx = None;
+        }
+
+        // 处理保留策略结果
+        if let Some(rx) = &retention_rx
+            && let Ok((dir, result)) = rx.try_recv()
+        {
+            app.apply_retention(&dir, result);
+            retention_rx = None;
+        }
+
+        // 启动新的 dry-run 计算并逐项接收结果（过期结果由 App 丢弃）
+        if let Some((job_id, items)) = app.take_dry_run_request() {
+            dry_run_rx = Some(spawn_dry_run_thread(
+                job_id,
+                items,
+                app.safety_rules.clone(),
+            ));
+        }
+        if let Some(rx) = &dry_run_rx {
+            while let Ok(msg) = rx.try_recv() {
+                app.apply_dry_run_message(msg);
+            }
+            // 计算完成或弹窗已关闭：丢弃接收端，未完成的工作线程随之停止
+            if !app.dry_run_in_progress() {
+                dry_run_rx = None;
+            }
+        }
+
+        // 处理树形视图展开目录的子项
+        let mut tree_updated = false;
+        while let Ok(msg) = tree_rx.try_recv() {
+            let is_current = msg.job_id() == tree_generation.load(Ordering::SeqCst);
+            match msg {
+                ScanMessage::DirEntry { entry, .. } if is_current => {
+                    app.apply_tree_child(entry);
+                    tree_updated = true;
+                }
+                ScanMessage::DirEntrySize { path, size, .. } if is_current => {
+                    app.apply_tree_child_size(&path, size);
+                }
+                ScanMessage::Done { .. } => tree_pending = tree_pending.saturating_sub(1),
+                ScanMessage::Error { message, .. } => {
+                    tree_pending = tree_pending.saturating_sub(1);
+                    if is_current {
+                        app.set_error(message);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if tree_updated {
+            app.rebuild_tree();
+        }
+
+        // 快速列表：为高亮或选中的目录按需计算大小（R 重新计算的结果也经此通道回填）
+        let lazy_requests = app.take_lazy_size_requests();
+        if !lazy_requests.is_empty() {
+            spawn_lazy_size_thread(
+                app.scan_generation,
+                lazy_requests,
+                lazy_size_tx.clone(),
+                &cancel_generation,
+                app.follow_symlinks,
+                app.scan_max_concurrency,
+            );
+            lazy_size_pending += 1;
+        }
+        while let Ok(msg) = lazy_size_rx.try_recv() {
+            match msg {
+                ScanMessage::DirEntrySize { job_id, path, size } => {
+                    // 已离开该目录时仍用结果校正已选大小
+                    if job_id == app.scan_generation {
+                        app.apply_entry_size(&path, size);
+                    } else {
+                        app.apply_selection_size(&path, size);
+                    }
+                }
+                ScanMessage::Done { .. } => lazy_size_pending = lazy_size_pending.saturating_sub(1),
+                _ => {}
+            }
+        }
+
+        let poll_timeout = if scan_rx.is_some()
+            || peek_rx.is_some()
+            || retention_rx.is_some()
+            || detail_rx.is_some()
+            || dry_run_rx.is_some()
+            || clean_job.is_some()
+            || tree_pending > 0
+            || lazy_size_pending > 0
+        {
             Duration::from_millis(POLL_INTERVAL_SCANNING_MS)
         } else {
             Duration::from_millis(POLL_INTERVAL_IDLE_MS)
         };
         if event::poll(poll_timeout)?
-            && let Event::Key(key) = event::read()?
+            && let Some(key) = read_key_event(&mut app)?
         {
             if key.kind != KeyEventKind::Press {
                 continue;
@@ -108,24 +438,114 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                 continue;
             }
 
-            // 帮助界面任意键关闭
+            // 帮助界面：j/k/PgUp/PgDn 滚动，其他任意键关闭
             if app.mode == Mode::Help {
-                app.toggle_help();
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.help_scroll = app.help_scroll.saturating_add(1);
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.help_scroll = app.help_scroll.saturating_sub(1);
+                    }
+                    KeyCode::PageDown => {
+                        app.help_scroll = app.help_scroll.saturating_add(POPUP_PAGE_SCROLL);
+                    }
+                    KeyCode::PageUp => {
+                        app.help_scroll = app.help_scroll.saturating_sub(POPUP_PAGE_SCROLL);
+                    }
+                    _ => app.toggle_help(),
+                }
                 continue;
             }
 
-            // 统计面板任意键关闭
+            // 统计面板：Tab 切换数据范围，其他键关闭
             if app.mode == Mode::Stats {
-                app.toggle_stats();
+                if key.code == KeyCode::Tab {
+                    app.toggle_stats_scope();
+                } else {
+                    app.toggle_stats();
+                }
+                continue;
+            }
+
+            // 书签列表：j/k 移动，Enter 扫描选中书签，Esc/b/q 关闭
+            if app.mode == Mode::Bookmarks {
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => app.bookmark_next(),
+                    KeyCode::Char('k') | KeyCode::Up => app.bookmark_previous(),
+                    KeyCode::Enter => {
+                        if let Some(path) = app.select_bookmark() {
+                            scan_rx = start_target_scan(
+                                &mut app,
+                                ScanTarget::Path(path),
+                                &cancel_generation,
+                                config,
+                            );
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => {
+                        app.close_bookmarks();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // 扫描类别设置：j/k 移动，Space/Enter 切换，Esc/,/q 关闭
+            if app.mode == Mode::Settings {
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => app.settings_next(),
+                    KeyCode::Char('k') | KeyCode::Up => app.settings_previous(),
+                    KeyCode::Char(' ') | KeyCode::Enter => app.toggle_settings_category(),
+                    KeyCode::Esc | KeyCode::Char(',') | KeyCode::Char('q') => {
+                        app.close_settings();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // 目录预览任意键关闭
+            if app.mode == Mode::Peek {
+                app.close_peek();
+                peek_rx = None;
+                continue;
+            }
+
+            // 确认清空回收站
+            if app.mode == Mode::ConfirmEmptyTrash {
+                match key.code {
+                    KeyCode::Enter => clean_job = Some(start_empty_trash(&mut app)),
+                    KeyCode::Esc => app.mode = Mode::Normal,
+                    _ => {}
+                }
+                continue;
+            }
+
+            // 确认退出：仅 y 退出，其余按键取消
+            if app.mode == Mode::ConfirmQuit {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => app.quit(),
+                    _ => app.cancel_quit(),
+                }
                 continue;
             }
 
             // 确认删除界面
             if app.mode == Mode::Confirm {
-                if let Some(rx) =
-                    handle_confirm_mode(&mut app, key.code, &cancel_generation, &config)
+                if let Some(job) = handle_confirm_mode(&mut app, key.code) {
+                    clean_job = Some(job);
+                }
+                continue;
+            }
+
+            // 清理中仅允许取消（在下一项开始前停止）
+            if app.mode == Mode::Cleaning {
+                if key.code == KeyCode::Esc
+                    && let Some(job) = &clean_job
                 {
-                    scan_rx = Some(rx);
+                    job.cancel.store(true, Ordering::SeqCst);
+                    app.clean_cancel_requested = true;
                 }
                 continue;
             }
@@ -135,8 +555,18 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                 match key.code {
                     KeyCode::Esc => app.cancel_input(),
                     KeyCode::Enter => {
+                        let appends = std::mem::take(&mut app.input_appends);
                         if let Some(path) = app.confirm_input() {
-                            scan_rx = start_disk_scan(&mut app, path, &cancel_generation);
+                            scan_rx = if appends {
+                                start_disk_scan(&mut app, path, &cancel_generation, true)
+                            } else {
+                                start_target_scan(
+                                    &mut app,
+                                    ScanTarget::Path(path),
+                                    &cancel_generation,
+                                    config,
+                                )
+                            };
                         }
                     }
                     KeyCode::Tab => app.input_tab_complete(),
@@ -154,6 +584,17 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                     KeyCode::Esc => app.cancel_search(),
                     KeyCode::Enter => app.confirm_search(),
                     KeyCode::Backspace => app.search_backspace(),
+                    KeyCode::Up => app.search_history_prev(),
+                    KeyCode::Down => app.search_history_next(),
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.toggle_search_regex();
+                    }
+                    KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.toggle_search_jump();
+                    }
+                    KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.select_search_matches();
+                    }
                     KeyCode::Char(c) => app.search_char(c),
                     _ => {}
                 }
@@ -164,7 +605,7 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
             if app.mode == Mode::Scanning {
                 match key.code {
                     KeyCode::Esc => cancel_scan(&mut app, &cancel_generation, &mut scan_rx),
-                    KeyCode::Char('q') => app.quit(),
+                    KeyCode::Char('q') => app.request_quit(),
                     _ => {}
                 }
                 continue;
@@ -172,6 +613,7 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
 
             // 清除上次清理结果通知
             app.last_clean_result = None;
+            app.notice = None;
 
             // 扫描中按 Esc 可取消
             if app.scan_in_progress && key.code == KeyCode::Esc {
@@ -179,17 +621,41 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                 continue;
             }
 
+            // 数字前缀：之后的 g / G 跳到对应行，其他按键丢弃前缀
+            if let KeyCode::Char(c) = key.code
+                && let Some(digit) = c.to_digit(10)
+                && key.modifiers.is_empty()
+            {
+                app.push_count_digit(digit);
+                continue;
+            }
+            let count = app.pending_count.take();
+
             match key.code {
-                KeyCode::Char('q') => app.quit(),
+                KeyCode::Char('q') => app.request_quit(),
                 KeyCode::Char('?') => app.toggle_help(),
                 KeyCode::Char('s') => {
-                    scan_rx = start_root_scan(&mut app, &cancel_generation, &config);
+                    scan_rx =
+                        start_target_scan(&mut app, ScanTarget::Preset, &cancel_generation, config);
                 }
                 KeyCode::Char('S') => {
                     // Shift+S: 扫描主目录
-                    if let Some(scanner) = scanner_from_config(&config) {
-                        let home = scanner.home_dir().clone();
-                        scan_rx = start_disk_scan(&mut app, home, &cancel_generation);
+                    scan_rx =
+                        start_target_scan(&mut app, ScanTarget::Home, &cancel_generation, config);
+                }
+                KeyCode::Char('b') => app.open_bookmarks(),
+                KeyCode::Char(',') => app.open_settings(),
+                KeyCode::Char('r') => {
+                    // 重新扫描上次目标（路径已不存在时忽略）
+                    match app.last_scan.clone().filter(ScanTarget::is_available) {
+                        Some(target) => {
+                            scan_rx =
+                                start_target_scan(&mut app, target, &cancel_generation, config);
+                        }
+                        None => {
+                            app.last_scan = None;
+                            app.notice = Some("没有可恢复的上次扫描".to_string());
+                        }
                     }
                 }
                 KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -203,13 +669,24 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                 KeyCode::Char('d') => {
                     app.start_input();
                 }
+                KeyCode::Char('+') => {
+                    app.start_append_input();
+                }
                 KeyCode::Char('o') => {
                     app.toggle_sort_order();
                 }
                 KeyCode::Down | KeyCode::Char('j') => app.next(),
                 KeyCode::Up | KeyCode::Char('k') => app.previous(),
-                KeyCode::Char('g') => app.first(),
-                KeyCode::Char('G') => app.last(),
+                KeyCode::Char('n') => app.next_selected(),
+                KeyCode::Char('N') => app.prev_selected(),
+                KeyCode::Char('g') => match count {
+                    Some(line) => app.jump_to_line(line),
+                    None => app.first(),
+                },
+                KeyCode::Char('G') => match count {
+                    Some(line) => app.jump_to_line(line),
+                    None => app.last(),
+                },
                 KeyCode::PageDown => {
                     let h = app.visible_height;
                     app.page_down(h);
@@ -220,9 +697,96 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                 }
                 KeyCode::Char('/') => app.start_search(),
                 KeyCode::Char('t') => app.toggle_stats(),
+                KeyCode::Char('y') => copy_current_path(&mut app, &mut clipboard),
+                KeyCode::Char('O') => reveal_current_entry(&mut app),
+                KeyCode::Char('h') => app.toggle_hide_current(),
+                KeyCode::Char('m') => app.cycle_size_floor(),
+                KeyCode::Char('H') => {
+                    // 子目录中的隐藏条目已从列表移除，重新列出才能显示
+                    if app.toggle_show_hidden().is_some()
+                        && let Some(rx) = rescan_current_view(&mut app, &cancel_generation, config)
+                    {
+                        scan_rx = Some(rx);
+                    }
+                }
+                KeyCode::Char('F') => app.toggle_lazy_sizes(),
+                KeyCode::Char('R') => {
+                    if let Some(path) = app.start_recompute() {
+                        spawn_lazy_size_thread(
+                            app.scan_generation,
+                            vec![path],
+                            lazy_size_tx.clone(),
+                            &cancel_generation,
+                            app.follow_symlinks,
+                            app.scan_max_concurrency,
+                        );
+                        lazy_size_pending += 1;
+                    }
+                }
+                KeyCode::Char('T') => {
+                    if app.tree_mode {
+                        tree_generation.fetch_add(1, Ordering::SeqCst);
+                    }
+                    app.toggle_tree_mode();
+                }
+                KeyCode::Char('p') => {
+                    if let Some(path) = app.start_peek() {
+                        peek_rx = Some(spawn_peek_thread(path));
+                    }
+                }
+                KeyCode::Char('D') => app.toggle_details(),
+                KeyCode::Char('<') => app.resize_details(false),
+                KeyCode::Char('>') => app.resize_details(true),
+                KeyCode::Char('K') if retention_rx.is_none() => {
+                    if let Some(path) = app.start_retention() {
+                        retention_rx = Some(spawn_retention_thread(
+                            path,
+                            app.follow_symlinks,
+                            app.scan_max_concurrency,
+                        ));
+                    }
+                }
+                KeyCode::Char(' ') | KeyCode::Enter if app.select_anchor.is_some() => {
+                    app.select_range();
+                }
+                KeyCode::Esc if app.select_anchor.is_some() => app.clear_select_anchor(),
+                KeyCode::Char('v') => app.toggle_select_anchor(),
                 KeyCode::Char(' ') => app.toggle_selected(),
                 KeyCode::Char('a') => app.toggle_all(),
+                KeyCode::Char('A') => app.select_search_matches(),
+                KeyCode::Char('i') => app.invert_selection(),
                 KeyCode::Char('c') => app.enter_confirm_mode(),
+                KeyCode::Char('x') => app.enter_single_confirm_mode(),
+                KeyCode::Char('e') => match Cleaner::trash_dir().filter(|path| path.is_dir()) {
+                    Some(trash) => {
+                        scan_rx = start_target_scan(
+                            &mut app,
+                            ScanTarget::Path(trash),
+                            &cancel_generation,
+                            config,
+                        );
+                    }
+                    None => app.notice = Some("回收站目录不存在".to_string()),
+                },
+                KeyCode::Char('E') => app.enter_empty_trash_confirm(),
+                KeyCode::Char('u') => {
+                    if let Some(rx) = undo_last_trash(&mut app, &cancel_generation, config) {
+                        scan_rx = Some(rx);
+                        trash_size_rx = Some(spawn_trash_size_thread());
+                    }
+                }
+                KeyCode::Enter if app.tree_mode => {
+                    if let Some(path) = app.toggle_expand() {
+                        spawn_tree_scan(
+                            path,
+                            tree_tx.clone(),
+                            &tree_generation,
+                            app.follow_symlinks,
+                            app.scan_max_concurrency,
+                        );
+                        tree_pending += 1;
+                    }
+                }
                 KeyCode::Enter => {
                     let target = app.current_entry().and_then(|e| {
                         if e.kind == EntryKind::Directory {
@@ -234,20 +798,21 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
                     if let Some(target) = target {
                         let selected_index = app.list_state.selected();
                         app.navigation
-                            .enter(target.clone(), app.entries.clone(), selected_index);
+                            .enter(target.clone(), app.view_entries(), selected_index);
                         scan_rx = start_dir_scan(&mut app, target, &cancel_generation);
+                    } else {
+                        // 文件无法进入，改为在 Finder 中显示
+                        reveal_current_entry(&mut app);
                     }
                 }
-                KeyCode::Backspace | KeyCode::Esc => {
-                    if app.navigation.current_path.is_some() {
-                        if app.scan_in_progress {
-                            cancel_scan(&mut app, &cancel_generation, &mut scan_rx);
-                        }
-                        if let Some((cached_entries, selected_index)) = app.navigation.back() {
-                            app.restore_cached_dir_entries(cached_entries, selected_index);
-                        } else {
-                            app.restore_root_entries();
-                        }
+                KeyCode::Backspace | KeyCode::Esc if app.navigation.current_path.is_some() => {
+                    if app.scan_in_progress {
+                        cancel_scan(&mut app, &cancel_generation, &mut scan_rx);
+                    }
+                    if let Some((cached_entries, selected_index)) = app.navigation.back() {
+                        app.restore_cached_dir_entries(cached_entries, selected_index);
+                    } else {
+                        app.restore_root_entries();
                     }
                 }
                 _ => {}
@@ -259,9 +824,41 @@ fn run_tui(terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
         }
     }
 
+    // 设置面板修改过扫描类别时写回配置文件
+    if app.settings_changed() {
+        AppConfig::save_disabled_categories(config_path, &app.disabled_categories).map_err(
+            |err| color_eyre::eyre::eyre!("保存配置失败 ({}): {}", config_path.display(), err),
+        )?;
+    }
+
     Ok(())
 }
 
+/// 读取一个终端事件：鼠标事件就地处理，按键事件交给调用方
+fn read_key_event(app: &mut App) -> Result<Option<KeyEvent>> {
+    match event::read()? {
+        Event::Key(key) => Ok(Some(key)),
+        Event::Mouse(mouse) => {
+            handle_mouse_event(app, mouse);
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 鼠标仅在普通模式下生效：左键点击定位/切换选中，滚轮上下移动
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent) {
+    if app.mode != Mode::Normal || app.error_message.is_some() {
+        return;
+    }
+    match mouse.kind {
+        MouseEventKind::Down(event::MouseButton::Left) => app.click_list(mouse.column, mouse.row),
+        MouseEventKind::ScrollDown => app.next(),
+        MouseEventKind::ScrollUp => app.previous(),
+        _ => {}
+    }
+}
+
 fn bump_generation(app: &mut App, cancel_generation: &Arc<AtomicU64>) -> u64 {
     app.scan_generation = app.scan_generation.wrapping_add(1);
     cancel_generation.store(app.scan_generation, Ordering::SeqCst);
@@ -274,14 +871,59 @@ fn cancel_scan(
     scan_rx: &mut Option<Receiver<ScanMessage>>,
 ) {
     bump_generation(app, cancel_generation);
+    app.pending_scan_target = None;
+    app.appending_scan = false;
     app.scan_in_progress = false;
     if app.mode == Mode::Scanning {
         app.mode = Mode::Normal;
     }
     app.scan_progress = 0;
+    app.scan_started_at = None;
     *scan_rx = None;
 }
 
+/// 在后台列出树形视图中展开目录的子项，结果发送到树形视图通道
+fn spawn_tree_scan(
+    path: PathBuf,
+    tx: mpsc::Sender<ScanMessage>,
+    tree_generation: &Arc<AtomicU64>,
+    follow_symlinks: bool,
+    max_concurrency: Option<usize>,
+) {
+    let job_id = tree_generation.load(Ordering::SeqCst);
+    let generation = Arc::clone(tree_generation);
+    thread::spawn(move || {
+        if let Some(mut scanner) = Scanner::new() {
+            scanner.set_follow_symlinks(follow_symlinks);
+            scanner.set_max_concurrency(max_concurrency);
+            scanner.scan_dir_listing(job_id, path, tx, generation);
+        } else {
+            send_scan_init_error(job_id, &tx);
+        }
+    });
+}
+
+/// 在后台为快速列表中的目录按需计算大小，沿用当前扫描代数以便导航后自动取消
+fn spawn_lazy_size_thread(
+    job_id: u64,
+    dir_paths: Vec<PathBuf>,
+    tx: mpsc::Sender<ScanMessage>,
+    cancel_generation: &Arc<AtomicU64>,
+    follow_symlinks: bool,
+    max_concurrency: Option<usize>,
+) {
+    let generation = Arc::clone(cancel_generation);
+    thread::spawn(move || {
+        if let Some(mut scanner) = Scanner::new() {
+            scanner.set_follow_symlinks(follow_symlinks);
+            scanner.set_max_concurrency(max_concurrency);
+            scanner.measure_dirs(job_id, dir_paths, tx, generation);
+        } else {
+            let _ = tx.send(ScanMessage::Done { job_id });
+        }
+    });
+}
+
 fn send_scan_init_error(job_id: u64, tx: &mpsc::Sender<ScanMessage>) {
     let _ = tx.send(ScanMessage::Error {
         job_id,
@@ -303,44 +945,216 @@ where
     rx
 }
 
-fn handle_confirm_mode(
-    app: &mut App,
-    key: KeyCode,
-    cancel_generation: &Arc<AtomicU64>,
-    config: &AppConfig,
-) -> Option<Receiver<ScanMessage>> {
+/// 在后台线程逐项计算 dry-run 预览，避免大量选中项阻塞界面
+fn spawn_dry_run_thread(
+    job_id: u64,
+    items: Vec<CleanableEntry>,
+    rules: SafetyRules,
+) -> Receiver<DryRunMessage> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        Cleaner::dry_run_streaming(&items, &rules, job_id, &tx);
+    });
+    rx
+}
+
+/// 将高亮条目的绝对路径复制到系统剪贴板，失败时通过错误弹窗提示
+fn copy_current_path(app: &mut App, clipboard: &mut Option<arboard::Clipboard>) {
+    let Some(path) = app.current_absolute_path() else {
+        return;
+    };
+    let result = match clipboard {
+        Some(clipboard) => Ok(clipboard),
+        None => arboard::Clipboard::new().map(|created| clipboard.insert(created)),
+    }
+    .and_then(|clipboard| clipboard.set_text(path.display().to_string()));
+    match result {
+        Ok(()) => app.notice = Some("已复制路径".to_string()),
+        Err(err) => app.set_error(format!("无法复制到剪贴板: {}", err)),
+    }
+}
+
+/// 在 Finder（其他平台为默认文件管理器）中显示高亮条目，失败时通过错误弹窗提示
+fn reveal_current_entry(app: &mut App) {
+    let Some(entry) = app.current_entry() else {
+        return;
+    };
+    let path = entry.path.clone();
+    let is_dir = entry.kind == EntryKind::Directory;
+    let status = vac::utils::reveal_command(&path, is_dir)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    match status {
+        Ok(status) if status.success() => {
+            app.notice = Some(format!("已在 Finder 中显示: {}", path.display()));
+        }
+        Ok(status) => app.set_error(format!(
+            "无法在 Finder 中显示 {}（退出码 {}）",
+            path.display(),
+            status.code().unwrap_or(-1)
+        )),
+        Err(err) => app.set_error(format!("无法在 Finder 中显示 {}: {}", path.display(), err)),
+    }
+}
+
+/// 后台统计回收站大小（回收站目录不存在时结果为 None）
+fn spawn_trash_size_thread() -> Receiver<Option<u64>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(Cleaner::trash_size());
+    });
+    rx
+}
+
+fn spawn_peek_thread(path: std::path::PathBuf) -> Receiver<Result<PeekResult, String>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = peek_dir(&path, PEEK_ENTRY_LIMIT)
+            .map_err(|err| format!("无法读取目录 {}: {}", path.display(), err));
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// 在后台统计详情面板中目录的文件数与子目录数
+///
+/// 每次请求递增 `detail_generation`，被取代的统计在遍历中发现代数变化后提前停止，不再发送结果。
+fn spawn_detail_thread(
+    path: PathBuf,
+    detail_generation: &Arc<AtomicU64>,
+) -> Receiver<(PathBuf, usize, usize)> {
+    let (tx, rx) = mpsc::channel();
+    let job_id = detail_generation
+        .fetch_add(1, Ordering::SeqCst)
+        .wrapping_add(1);
+    let generation = Arc::clone(detail_generation);
+    thread::spawn(move || {
+        let counts = Cleaner::count_path_contents_until(&path, || {
+            generation.load(Ordering::SeqCst) != job_id
+        });
+        if let Some((files, dirs, _)) = counts {
+            let _ = tx.send((path, files, dirs));
+        }
+    });
+    rx
+}
+
+/// 保留策略结果：`(目录, 最新 RETENTION_KEEP 个之外的子项)`
+type RetentionResult = (PathBuf, Result<Vec<CleanableEntry>, String>);
+
+/// 在后台按修改时间列出目录子项，计算「保留最新 N 个」之外可清理的条目
+fn spawn_retention_thread(
+    path: PathBuf,
+    follow_symlinks: bool,
+    max_concurrency: Option<usize>,
+) -> Receiver<RetentionResult> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = match Scanner::new() {
+            Some(mut scanner) => {
+                scanner.set_follow_symlinks(follow_symlinks);
+                scanner.set_max_concurrency(max_concurrency);
+                scanner
+                    .retention_excess(&path, RETENTION_KEEP)
+                    .map_err(|err| format!("无法读取目录 {}: {}", path.display(), err))
+            }
+            None => Err(SCAN_INIT_ERROR_MESSAGE.to_string()),
+        };
+        let _ = tx.send((path, result));
+    });
+    rx
+}
+
+fn handle_confirm_mode(app: &mut App, key: KeyCode) -> Option<CleanJob> {
+    // 列表可能在上次按键后变化（如 dry-run 结果到达），先修正滚动偏移
+    app.clamp_confirm_scroll_to_view();
+    // 需要输入确认词时，字符键用于输入，Tab 切换 dry-run 视图
+    if app.confirm_word_input.is_some() {
+        match key {
+            KeyCode::Char(c) => {
+                app.confirm_word_char(c);
+                return None;
+            }
+            KeyCode::Backspace => {
+                app.confirm_word_backspace();
+                return None;
+            }
+            KeyCode::Tab => {
+                app.toggle_dry_run();
+                return None;
+            }
+            _ => {}
+        }
+    }
     match key {
+        KeyCode::Enter if !app.confirm_armed() => None,
         KeyCode::Enter => {
-            let rx = execute_clean(app, cancel_generation, config);
             app.mode = Mode::Normal;
-            rx
+            let job = execute_clean(app);
+            app.confirm_target = None;
+            app.confirm_blocked.clear();
+            app.confirm_word_input = None;
+            job
         }
         KeyCode::Esc => {
             app.cancel_confirm();
             None
         }
         KeyCode::Char('d') => {
-            if app.dry_run_active {
-                app.dry_run_active = false;
-            } else {
-                let selected_items = app.get_selected_items();
-                app.dry_run_result = Some(Cleaner::dry_run(&selected_items));
-                app.dry_run_active = true;
-            }
+            app.toggle_dry_run();
             None
         }
         KeyCode::Char('j') | KeyCode::Down => {
-            app.confirm_scroll = app.confirm_scroll.saturating_add(1);
+            app.scroll_confirm_down(1);
             None
         }
         KeyCode::Char('k') | KeyCode::Up => {
-            app.confirm_scroll = app.confirm_scroll.saturating_sub(1);
+            app.scroll_confirm_up(1);
+            None
+        }
+        KeyCode::PageDown => {
+            let page = app.confirm_visible_height;
+            app.scroll_confirm_down(page);
+            None
+        }
+        KeyCode::PageUp => {
+            let page = app.confirm_visible_height;
+            app.scroll_confirm_up(page);
+            None
+        }
+        KeyCode::Char('g') => {
+            app.scroll_confirm_top();
+            None
+        }
+        KeyCode::Char('G') => {
+            app.scroll_confirm_bottom();
             None
         }
         _ => None,
     }
 }
 
+/// 按扫描目标启动预设或磁盘扫描，并在完成后记为上次扫描目标
+fn start_target_scan(
+    app: &mut App,
+    target: ScanTarget,
+    cancel_generation: &Arc<AtomicU64>,
+    config: &AppConfig,
+) -> Option<Receiver<ScanMessage>> {
+    let rx = match &target {
+        ScanTarget::Preset => start_root_scan(app, cancel_generation, config),
+        ScanTarget::Home => {
+            let home = scanner_from_config(config)?.home_dir().clone();
+            start_disk_scan(app, home, cancel_generation, false)
+        }
+        ScanTarget::Path(path) => start_disk_scan(app, path.clone(), cancel_generation, false),
+    };
+    app.pending_scan_target = Some(target);
+    rx
+}
+
 fn start_root_scan(
     app: &mut App,
     cancel_generation: &Arc<AtomicU64>,
@@ -351,17 +1165,30 @@ fn start_root_scan(
     app.scan_in_progress = true;
     app.mode = Mode::Scanning;
     app.scan_progress = 0;
+    app.start_scan_clock(Instant::now());
     app.current_scan_path = "准备扫描...".to_string();
     app.navigation.reset_root();
     app.clear_entries();
     app.clear_root_entries();
 
     let extra_targets = config.expanded_extra_targets();
+    let custom_targets = config.expanded_custom_targets();
+    let disabled_categories = app.disabled_categories.clone();
+    let use_scan_cache = app.use_scan_cache;
+    let follow_symlinks = app.follow_symlinks;
+    let max_concurrency = app.scan_max_concurrency;
     let rx = spawn_scan_thread(
         cancel_generation,
         job_id,
         move |scan_job_id, tx, cancel_clone| {
-            if let Some(scanner) = Scanner::with_extra_targets(extra_targets) {
+            if let Some(mut scanner) = Scanner::with_extra_targets(extra_targets) {
+                scanner.set_custom_targets(custom_targets);
+                scanner.set_excluded_categories(disabled_categories);
+                scanner.set_follow_symlinks(follow_symlinks);
+                scanner.set_max_concurrency(max_concurrency);
+                if use_scan_cache {
+                    scanner.load_cache();
+                }
                 scanner.scan_root_with_progress(scan_job_id, tx, cancel_clone);
             } else {
                 send_scan_init_error(scan_job_id, &tx);
@@ -379,17 +1206,26 @@ fn start_dir_scan(
 ) -> Option<Receiver<ScanMessage>> {
     let job_id = bump_generation(app, cancel_generation);
     app.scan_kind = ScanKind::ListDir;
+    app.pending_scan_target = None;
     app.scan_in_progress = true;
     app.mode = Mode::Normal;
     app.scan_progress = 0;
+    app.start_scan_clock(Instant::now());
     app.current_scan_path = path.display().to_string();
     app.clear_entries();
+    app.lazy_listing = app.lazy_sizes;
 
+    let follow_symlinks = app.follow_symlinks;
+    let max_concurrency = app.scan_max_concurrency;
+    let lazy_sizes = app.lazy_sizes;
     let rx = spawn_scan_thread(
         cancel_generation,
         job_id,
         move |scan_job_id, tx, cancel_clone| {
-            if let Some(scanner) = Scanner::new() {
+            if let Some(mut scanner) = Scanner::new() {
+                scanner.set_follow_symlinks(follow_symlinks);
+                scanner.set_max_concurrency(max_concurrency);
+                scanner.set_lazy_sizes(lazy_sizes);
                 scanner.scan_dir_listing(scan_job_id, path, tx, cancel_clone);
             } else {
                 send_scan_init_error(scan_job_id, &tx);
@@ -400,26 +1236,38 @@ fn start_dir_scan(
     Some(rx)
 }
 
+/// 扫描指定路径的顶层条目；`append` 为 true 时追加到已有根结果（不记为上次扫描目标）
 fn start_disk_scan(
     app: &mut App,
     path: std::path::PathBuf,
     cancel_generation: &Arc<AtomicU64>,
+    append: bool,
 ) -> Option<Receiver<ScanMessage>> {
     let job_id = bump_generation(app, cancel_generation);
     app.scan_kind = ScanKind::DiskScan;
     app.scan_in_progress = true;
     app.mode = Mode::Scanning;
     app.scan_progress = 0;
+    app.start_scan_clock(Instant::now());
     app.current_scan_path = format!("扫描: {}", path.display());
-    app.navigation.reset_root();
-    app.clear_entries();
-    app.clear_root_entries();
+    if append {
+        app.pending_scan_target = None;
+        app.begin_append_scan();
+    } else {
+        app.navigation.reset_root();
+        app.clear_entries();
+        app.clear_root_entries();
+    }
 
+    let follow_symlinks = app.follow_symlinks;
+    let max_concurrency = app.scan_max_concurrency;
     let rx = spawn_scan_thread(
         cancel_generation,
         job_id,
         move |scan_job_id, tx, cancel_clone| {
-            if let Some(scanner) = Scanner::new() {
+            if let Some(mut scanner) = Scanner::new() {
+                scanner.set_follow_symlinks(follow_symlinks);
+                scanner.set_max_concurrency(max_concurrency);
                 scanner.scan_disk_with_progress(scan_job_id, path, tx, cancel_clone);
             } else {
                 send_scan_init_error(scan_job_id, &tx);
@@ -430,129 +1278,313 @@ fn start_disk_scan(
     Some(rx)
 }
 
-fn execute_clean(
-    app: &mut App,
-    cancel_generation: &Arc<AtomicU64>,
-    config: &AppConfig,
-) -> Option<Receiver<ScanMessage>> {
-    let selected_items = app.get_selected_items();
+fn execute_clean(app: &mut App) -> Option<CleanJob> {
+    // 进入确认模式时已标记为不安全的项目直接跳过
+    let selected_items = app.confirm_cleanable_items();
+    // 位于其他选中目录之下的条目随上级一起清理，不单独派发；包含 VAC 自身数据的目录只清理其余子项
+    let dispatched_items =
+        Cleaner::without_own_data(&Cleaner::without_nested(&selected_items), &app.safety_rules);
 
     if selected_items.is_empty() {
+        if !app.confirm_blocked.is_empty() {
+            app.set_error("所选项目均被安全规则阻止，未执行清理".to_string());
+        }
         return None;
     }
 
-    // 安全检查
+    // 执行前再次安全检查（确认期间路径可能发生变化）
     for item in &selected_items {
-        if !Cleaner::is_safe_to_delete(&item.path) {
-            app.set_error(format!("不安全的路径: {}", item.path.display()));
+        if !Cleaner::is_safe_to_delete(&item.path, &app.safety_rules) {
+            app.set_error(
+                CleanError::Unsafe {
+                    path: item.path.clone(),
+                }
+                .to_string(),
+            );
             return None;
         }
     }
 
-    let item_count = selected_items.len();
-    let result = if config.safety.move_to_trash {
-        Cleaner::trash_items(&selected_items)
-    } else {
-        Cleaner::clean(&selected_items)
-    };
+    let (tx, rx) = mpsc::channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_clone = Arc::clone(&cancel);
+    let trash_policy = app.trash_policy();
+    let secure_delete = app.secure_delete_active();
+    let clean_options = app.clean_options;
+    let measure_freed_space = app.measure_freed_space;
+    let prune_empty_dirs = app.prune_empty_dirs;
+    let items = dispatched_items.clone();
+    thread::spawn(move || {
+        let result = Cleaner::with_measured_freed(&items, measure_freed_space, || {
+            Cleaner::with_pruned_empty_dirs(prune_empty_dirs, || {
+                if secure_delete {
+                    Cleaner::secure_clean_with_progress(
+                        &items,
+                        SECURE_DELETE_PASSES,
+                        clean_options,
+                        &tx,
+                        &cancel_clone,
+                    )
+                } else {
+                    Cleaner::clean_with_policy_progress(
+                        &items,
+                        &trash_policy,
+                        clean_options,
+                        &tx,
+                        &cancel_clone,
+                    )
+                }
+            })
+        });
+        let _ = tx.send(CleanMessage::Done(result));
+    });
 
-    if result.success {
+    app.start_cleaning(
+        dispatched_items.len(),
+        dispatched_items.iter().filter_map(|item| item.size).sum(),
+    );
+    Some(CleanJob {
+        rx,
+        cancel,
+        items: selected_items,
+        empty_trash: false,
+    })
+}
+
+/// 在后台清空回收站，进度显示与取消沿用清理任务
+fn start_empty_trash(app: &mut App) -> CleanJob {
+    let (tx, rx) = mpsc::channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_clone = Arc::clone(&cancel);
+    thread::spawn(move || {
+        let result = Cleaner::empty_trash_with_progress(&tx, &cancel_clone);
+        let _ = tx.send(CleanMessage::Done(result));
+    });
+    app.start_emptying_trash();
+    CleanJob {
+        rx,
+        cancel,
+        items: Vec::new(),
+        empty_trash: true,
+    }
+}
+
+/// 处理清空回收站结果：部分失败或取消时仍报告已删除的部分
+fn finish_empty_trash(app: &mut App, result: CleanResult) {
+    app.finish_cleaning();
+    let item_count = result.succeeded_count();
+    if result.cancelled {
+        app.notice = Some(format!(
+            "已取消清空回收站：已删除 {} 项，释放 {}",
+            item_count,
+            format_size(result.freed_space)
+        ));
+    } else {
         app.last_clean_result = Some((result.freed_space, item_count));
-        app.clear_selections();
+    }
+    if !result.success {
+        app.set_clean_errors(result.errors);
+    }
+}
 
-        if let Some(path) = app.navigation.current_path.clone() {
-            start_dir_scan(app, path, cancel_generation)
-        } else {
-            start_root_scan(app, cancel_generation, config)
-        }
+/// 处理后台清理结果：更新选中状态、记录回收站批次并重新扫描
+fn finish_clean(
+    app: &mut App,
+    items: &[CleanableEntry],
+    result: CleanResult,
+    cancel_generation: &Arc<AtomicU64>,
+    config: &AppConfig,
+) -> Option<Receiver<ScanMessage>> {
+    app.finish_cleaning();
+
+    if !result.trashed_paths.is_empty() {
+        app.last_trashed_batch = result.trashed_paths;
+    }
+
+    // 随已处理的上级目录一起清理的选中项同样视为已处理
+    let processed_paths: HashSet<&Path> = result
+        .processed_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .collect();
+    let processed_items: Vec<CleanableEntry> = items
+        .iter()
+        .filter(|item| {
+            item.path
+                .ancestors()
+                .any(|path| processed_paths.contains(path))
+        })
+        .cloned()
+        .collect();
+    if result.cancelled {
+        app.notice = Some(format!(
+            "已取消清理：已处理 {}/{} 项，释放 {}",
+            processed_items.len(),
+            items.len(),
+            format_size(result.freed_space)
+        ));
+    } else {
+        app.last_clean_result = Some((result.freed_space, processed_items.len()));
+    }
+
+    if result.success {
+        app.deselect_cleaned(&processed_items);
+        rescan_current_view(app, cancel_generation, config)
     } else {
-        let error_msg = result.errors.join("\n");
-        app.set_error(format!("部分清理失败:\n{}", error_msg));
+        app.set_clean_errors(result.errors);
         None
     }
 }
 
-// ── 非交互模式 ──────────────────────────────────────────────
-use vac::cli::ScanTarget;
-
-/// 非交互模式的扫描结果条目（用于 JSON 输出）
-#[derive(serde::Serialize)]
-struct ReportEntry {
-    path: String,
-    name: String,
-    kind: String,
-    size: Option<u64>,
-    size_display: String,
-    modified_at: Option<String>,
-}
-
-/// 非交互模式的 dry-run 条目（用于 JSON 输出）
-#[derive(serde::Serialize)]
-struct DryRunReportItem {
-    path: String,
-    file_count: usize,
-    dir_count: usize,
-    size: u64,
-    size_display: String,
-}
-
-/// 非交互模式的清理结果（用于 JSON 输出）
-#[derive(serde::Serialize)]
-struct CleanReport {
-    success: bool,
-    freed_space: u64,
-    freed_space_display: String,
-    item_count: usize,
-    use_trash: bool,
-    errors: Vec<String>,
-}
-
-/// 非交互模式的完整报告（用于 JSON 输出）
-#[derive(serde::Serialize)]
-struct ScanReport {
-    scan_target: String,
-    sort_order: String,
-    total_items: usize,
-    total_size: u64,
-    total_size_display: String,
-    entries: Vec<ReportEntry>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    dry_run: Option<DryRunReport>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    clean_result: Option<CleanReport>,
-}
-
-/// Dry-run 报告
-#[derive(serde::Serialize)]
-struct DryRunReport {
-    total_files: usize,
-    total_dirs: usize,
-    total_size: u64,
-    total_size_display: String,
-    items: Vec<DryRunReportItem>,
+/// 重新扫描当前视图（子目录重新列出，根层重新执行预设扫描），完成后恢复高亮位置
+fn rescan_current_view(
+    app: &mut App,
+    cancel_generation: &Arc<AtomicU64>,
+    config: &AppConfig,
+) -> Option<Receiver<ScanMessage>> {
+    let cursor = app.cursor_position();
+    let rx = if let Some(path) = app.navigation.current_path.clone() {
+        start_dir_scan(app, path, cancel_generation)
+    } else {
+        start_root_scan(app, cancel_generation, config)
+    };
+    app.set_pending_cursor(cursor);
+    rx
+}
+
+/// 撤销上一次移至回收站的清理
+fn undo_last_trash(
+    app: &mut App,
+    cancel_generation: &Arc<AtomicU64>,
+    config: &AppConfig,
+) -> Option<Receiver<ScanMessage>> {
+    if app.last_trashed_batch.is_empty() {
+        app.notice = Some("没有可撤销的回收站清理".to_string());
+        return None;
+    }
+
+    match Cleaner::restore_from_trash(&app.last_trashed_batch) {
+        Ok(restored_count) => {
+            app.last_trashed_batch.clear();
+            app.notice = Some(format!("已从回收站恢复 {} 个项目", restored_count));
+            rescan_current_view(app, cancel_generation, config)
+        }
+        Err(message) => {
+            app.set_error(message);
+            None
+        }
+    }
 }
 
+// ── 非交互模式 ──────────────────────────────────────────────
+use vac::cli::{LogLevel, OutputFormat};
+
 /// 同步执行扫描并收集结果
-fn run_scan_blocking(scan_target: &ScanTarget, config: &AppConfig) -> Result<Vec<CleanableEntry>> {
+fn run_scan_blocking(
+    scan_targets: &[ScanTarget],
+    config: &AppConfig,
+    use_scan_cache: bool,
+    categories: &[ItemCategory],
+    excluded_categories: &[ItemCategory],
+    log_level: LogLevel,
+) -> Result<Vec<CleanableEntry>> {
+    let mut entries = Vec::new();
+    run_scan_streaming(
+        scan_targets,
+        config,
+        use_scan_cache,
+        categories,
+        excluded_categories,
+        log_level,
+        |entry| {
+            entries.push(entry);
+            Ok(())
+        },
+    )?;
+    Ok(entries)
+}
+
+/// 依次扫描多个目标并合并结果：同一路径在多个目标中出现时只交出第一次
+fn run_scan_streaming(
+    scan_targets: &[ScanTarget],
+    config: &AppConfig,
+    use_scan_cache: bool,
+    categories: &[ItemCategory],
+    excluded_categories: &[ItemCategory],
+    log_level: LogLevel,
+    mut on_entry: impl FnMut(CleanableEntry) -> Result<()>,
+) -> Result<()> {
+    // 之前目标已交出的路径；与之相同、位于其下或包含它的条目跳过，避免重复计算与重叠删除
+    let mut seen = BTreeSet::new();
+    for scan_target in scan_targets {
+        let mut current = Vec::new();
+        run_target_streaming(
+            scan_target,
+            config,
+            use_scan_cache,
+            categories,
+            excluded_categories,
+            log_level,
+            |entry| {
+                if !overlaps_any(&seen, &entry.path) {
+                    current.push(entry.path.clone());
+                    on_entry(entry)?;
+                }
+                Ok(())
+            },
+        )?;
+        seen.extend(current);
+    }
+    Ok(())
+}
+
+/// 同步执行单个目标的扫描，每个条目大小确定后立即交给 `on_entry`
+///
+/// 目录条目先以未知大小发现，等待 `DirEntrySize` 回填后再交出；扫描结束时仍未回填的条目按未知大小交出。
+fn run_target_streaming(
+    scan_target: &ScanTarget,
+    config: &AppConfig,
+    use_scan_cache: bool,
+    categories: &[ItemCategory],
+    excluded_categories: &[ItemCategory],
+    log_level: LogLevel,
+    mut on_entry: impl FnMut(CleanableEntry) -> Result<()>,
+) -> Result<()> {
     let cancel_generation = Arc::new(AtomicU64::new(0));
     let job_id = SCAN_JOB_ID_BLOCKING;
     cancel_generation.store(job_id, Ordering::SeqCst);
 
     let requested_target = scan_target.clone();
     let extra_targets = config.expanded_extra_targets();
+    let custom_targets = config.expanded_custom_targets();
+    let categories = categories.to_vec();
+    let mut excluded_categories = excluded_categories.to_vec();
+    excluded_categories.extend(config.disabled_categories());
+    let follow_symlinks = config.scan.follow_symlinks;
+    let max_concurrency = config.scan.max_concurrency;
     let rx = spawn_scan_thread(
         &cancel_generation,
         job_id,
         move |scan_job_id, tx, cancel_generation_clone| match requested_target {
             ScanTarget::Preset => {
-                if let Some(scanner) = Scanner::with_extra_targets(extra_targets) {
+                if let Some(mut scanner) = Scanner::with_extra_targets(extra_targets) {
+                    scanner.set_custom_targets(custom_targets);
+                    scanner.set_categories(categories);
+                    scanner.set_excluded_categories(excluded_categories);
+                    scanner.set_follow_symlinks(follow_symlinks);
+                    scanner.set_max_concurrency(max_concurrency);
+                    if use_scan_cache {
+                        scanner.load_cache();
+                    }
                     scanner.scan_root_with_progress(scan_job_id, tx, cancel_generation_clone);
                 } else {
                     send_scan_init_error(scan_job_id, &tx);
                 }
             }
             ScanTarget::Home => {
-                if let Some(scanner) = Scanner::new() {
+                if let Some(mut scanner) = Scanner::new() {
+                    scanner.set_follow_symlinks(follow_symlinks);
+                    scanner.set_max_concurrency(max_concurrency);
                     let home_path = scanner.home_dir().clone();
                     scanner.scan_disk_with_progress(
                         scan_job_id,
@@ -565,7 +1597,9 @@ fn run_scan_blocking(scan_target: &ScanTarget, config: &AppConfig) -> Result<Vec
                 }
             }
             ScanTarget::Path(path) => {
-                if let Some(scanner) = Scanner::new() {
+                if let Some(mut scanner) = Scanner::new() {
+                    scanner.set_follow_symlinks(follow_symlinks);
+                    scanner.set_max_concurrency(max_concurrency);
                     scanner.scan_disk_with_progress(scan_job_id, path, tx, cancel_generation_clone);
                 } else {
                     send_scan_init_error(scan_job_id, &tx);
@@ -574,25 +1608,33 @@ fn run_scan_blocking(scan_target: &ScanTarget, config: &AppConfig) -> Result<Vec
         },
     );
 
-    let mut entries = Vec::new();
+    let mut pending_sizes: HashMap<PathBuf, CleanableEntry> = HashMap::new();
     for msg in rx {
         match msg {
-            ScanMessage::RootItem { entry, .. } => {
-                entries.push(entry);
-            }
-            ScanMessage::DirEntry { entry, .. } => {
-                entries.push(entry);
+            ScanMessage::RootItem { entry, .. } | ScanMessage::DirEntry { entry, .. } => {
+                if entry.kind == EntryKind::Directory && entry.size.is_none() {
+                    pending_sizes.insert(entry.path.clone(), entry);
+                } else {
+                    on_entry(entry)?;
+                }
             }
             ScanMessage::DirEntrySize { path, size, .. } => {
-                if let Some(entry) = entries.iter_mut().find(|e| e.path == path) {
+                if let Some(mut entry) = pending_sizes.remove(&path) {
                     entry.size = Some(size);
+                    on_entry(entry)?;
                 }
             }
-            ScanMessage::Progress { progress, .. } => {
-                eprint!("\r扫描进度: {}%", progress);
-            }
+            ScanMessage::Progress { progress, path, .. } => match log_level {
+                LogLevel::Quiet => {}
+                LogLevel::Normal => eprint!("\r扫描进度: {}%", progress),
+                LogLevel::Verbose => eprintln!("[{:>3}%] {}", progress, path),
+            },
             ScanMessage::Done { .. } => {
-                eprintln!("\r扫描完成。      ");
+                match log_level {
+                    LogLevel::Quiet => {}
+                    LogLevel::Normal => eprintln!("\r扫描完成。      "),
+                    LogLevel::Verbose => eprintln!("扫描完成。"),
+                }
                 break;
             }
             ScanMessage::Error { message, .. } => {
@@ -601,135 +1643,841 @@ fn run_scan_blocking(scan_target: &ScanTarget, config: &AppConfig) -> Result<Vec
         }
     }
 
-    Ok(entries)
+    for entry in pending_sizes.into_values() {
+        on_entry(entry)?;
+    }
+    Ok(())
 }
 
-/// 非交互模式入口
-fn run_non_interactive(cli: Cli) -> Result<()> {
-    let config = AppConfig::load();
+/// 清空回收站并生成报告
+fn run_empty_trash() -> EmptyTrashReport {
+    let result = Cleaner::empty_trash();
+    EmptyTrashReport {
+        success: result.success,
+        freed_space: result.freed_space,
+        freed_space_display: format_size(result.freed_space),
+        item_count: result.succeeded_count(),
+        errors: result.errors,
+    }
+}
+
+/// 在终端中询问是否清空回收站（不在终端中时返回错误，需改用 --yes）
+fn confirm_empty_trash() -> Result<bool> {
+    use std::io::IsTerminal;
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(color_eyre::eyre::eyre!(
+            "--empty-trash 需要 --yes 确认（当前不在终端中，无法询问）"
+        ));
+    }
+
+    let size = Cleaner::trash_size()
+        .map(format_size)
+        .unwrap_or_else(|| "未知".to_string());
+    eprint!("永久清空回收站（当前 {}，不可恢复）？[y/N] ", size);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
+/// 将清空回收站结果输出到终端
+fn print_empty_trash_report(report: &EmptyTrashReport) {
+    if report.success {
+        println!(
+            "已清空回收站: {} ({} 个项目)",
+            report.freed_space_display, report.item_count
+        );
+    } else {
+        println!(
+            "清空回收站部分失败（已删除 {} 个项目，释放 {}）:",
+            report.item_count, report.freed_space_display
+        );
+        for err in &report.errors {
+            println!("  ✗ {}", err);
+        }
+    }
+}
 
-    let sort_order = match cli.sort.as_str() {
-        "name" => SortOrder::ByName,
-        "time" => SortOrder::ByTime,
-        _ => SortOrder::BySize,
+/// 列出预设扫描目标（`--list-targets`），只检查路径是否存在，不计算大小
+fn run_list_targets(cli: &Cli, config_location: &ConfigLocation) -> Result<()> {
+    let (config, config_error) = AppConfig::load_or_default(config_location)?;
+    if let Some(err) = config_error {
+        eprintln!("警告: {}，已使用默认配置", err);
+    }
+    let mut scanner = scanner_from_config(&config)
+        .ok_or_else(|| color_eyre::eyre::eyre!(SCAN_INIT_ERROR_MESSAGE))?;
+    scanner.set_categories(cli.category.clone());
+    scanner.set_excluded_categories(cli.exclude_category.clone());
+
+    let report = ScanTargetsReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        targets: scanner
+            .list_scan_targets()
+            .into_iter()
+            .map(|(category, path, exists)| ScanTargetReport {
+                category: category.slug().to_string(),
+                label: category.as_str().to_string(),
+                path: path.display().to_string(),
+                exists,
+            })
+            .collect(),
     };
+    write_report(&report, cli, || print_scan_targets(&report))
+}
 
-    let scan_target = cli.scan.as_ref().expect("scan target is required");
-    let scan_target_name = match scan_target {
-        ScanTarget::Preset => "preset".to_string(),
-        ScanTarget::Home => "home".to_string(),
-        ScanTarget::Path(p) => p.display().to_string(),
+/// 将预设扫描目标输出到终端
+fn print_scan_targets(report: &ScanTargetsReport) {
+    // 类别标识为 ASCII，按其对齐；中文显示名放在行尾
+    let slug_width = report
+        .targets
+        .iter()
+        .map(|target| target.category.len())
+        .max()
+        .unwrap_or(0);
+    for target in &report.targets {
+        let status = if target.exists { "✓" } else { "✗" };
+        println!(
+            "{} {:<slug_width$}  {}  ({})",
+            status, target.category, target.path, target.label
+        );
+    }
+    let missing = report
+        .targets
+        .iter()
+        .filter(|target| !target.exists)
+        .count();
+    println!(
+        "共 {} 个目标，{} 个不存在（✗ 的路径不会被扫描）",
+        report.targets.len(),
+        missing
+    );
+}
+
+/// 非交互模式入口
+/// 执行非交互模式，返回清理与清空回收站是否全部成功（未执行这些操作时视为成功）
+fn run_non_interactive(cli: Cli, config_location: &ConfigLocation) -> Result<bool> {
+    let (config, config_error) = AppConfig::load_or_default(config_location)?;
+    if let Some(err) = config_error {
+        // 删除类操作不能在丢失安全设置的默认配置下执行
+        if cli.modifies_files() {
+            return Err(err.into());
+        }
+        eprintln!("警告: {}，已使用默认配置", err);
+    }
+    set_size_units(config.ui.size_units);
+
+    let sort_order = SortOrder::from_name(&cli.sort).unwrap_or(SortOrder::BySize);
+
+    if cli.empty_trash && force_trash_enabled(&cli, &config) {
+        return Err(color_eyre::eyre::eyre!(
+            "安全模式（safety.force_trash）下禁止清空回收站"
+        ));
+    }
+    if !cli.delete.is_empty() {
+        return run_delete(&cli, &config);
+    }
+    let empty_trash_report = if !cli.empty_trash {
+        None
+    } else if cli.yes || confirm_empty_trash()? {
+        Some(run_empty_trash())
+    } else {
+        eprintln!("已取消清空回收站。");
+        None
     };
 
-    eprintln!("VAC - 非交互模式");
-    eprintln!("扫描目标: {}", scan_target_name);
+    let scan_targets = cli.scan.as_slice();
+    if scan_targets.is_empty() {
+        // 仅清空回收站
+        let Some(report) = empty_trash_report else {
+            return Ok(true);
+        };
+        write_report(&report, &cli, || print_empty_trash_report(&report))?;
+        return Ok(report.success);
+    }
+    let scan_target_name = scan_targets
+        .iter()
+        .map(|scan_target| match scan_target {
+            ScanTarget::Preset => "preset".to_string(),
+            ScanTarget::Home => "home".to_string(),
+            ScanTarget::Path(p) => p.display().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let only_preset = scan_targets
+        .iter()
+        .all(|scan_target| matches!(scan_target, ScanTarget::Preset));
+
+    if !cli.category.is_empty() && !only_preset {
+        return Err(color_eyre::eyre::eyre!("--category 仅适用于 --scan preset"));
+    }
+    if !cli.exclude_category.is_empty() && !only_preset {
+        return Err(color_eyre::eyre::eyre!(
+            "--exclude-category 仅适用于 --scan preset"
+        ));
+    }
 
-    let mut entries = run_scan_blocking(scan_target, &config)?;
+    // 须在扫描前确定：保留范围按当前的修改时间选出最新 N 个
+    let retention = RetentionScope::from_cli(&cli, &config)?;
+
+    if cli.log_level() >= LogLevel::Normal {
+        eprintln!("VAC - 非交互模式");
+        eprintln!("扫描目标: {}", scan_target_name);
+    }
+
+    if cli.format == OutputFormat::Ndjson {
+        return run_ndjson_report(
+            &cli,
+            &config,
+            scan_targets,
+            scan_target_name,
+            retention.as_ref(),
+            empty_trash_report,
+        );
+    }
+
+    let mut entries = run_scan_blocking(
+        scan_targets,
+        &config,
+        !cli.no_cache,
+        &cli.category,
+        &cli.exclude_category,
+        cli.log_level(),
+    )?;
+    if let Some(ref retention) = retention {
+        entries.retain(|entry| retention.allows(entry));
+    }
+    let scanned = if cli.clean {
+        entries.clone()
+    } else {
+        Vec::new()
+    };
+    entries.retain(|entry| passes_report_filters(entry, &cli));
     sort_entries_by(&mut entries, sort_order);
+    if let Some(top) = cli.top {
+        entries.truncate(top);
+    }
 
     let total_size: u64 = entries.iter().filter_map(|e| e.size).sum();
+    // 须在清理之前估算，清理后路径已不存在
+    let safety_rules = SafetyRules::from_config(&config);
+    let (reclaimable_items, reclaimable_estimate) =
+        Cleaner::reclaimable_estimate(&entries, &safety_rules);
 
     // 构建报告条目
-    let report_entries: Vec<ReportEntry> = entries
-        .iter()
-        .map(|e| ReportEntry {
-            path: e.path.display().to_string(),
-            name: e.name.clone(),
-            kind: match e.kind {
-                EntryKind::Directory => "directory".to_string(),
-                EntryKind::File => "file".to_string(),
-            },
-            size: e.size,
-            size_display: e
-                .size
-                .map(format_size)
-                .unwrap_or_else(|| "未知".to_string()),
-            modified_at: e.modified_at.as_ref().map(|time| format_time(time, true)),
-        })
-        .collect();
+    let report_entries: Vec<ReportEntry> = entries.iter().map(ReportEntry::from_entry).collect();
+
+    let dry_run_report = cli
+        .dry_run
+        .then(|| build_dry_run_report(&entries, &safety_rules));
+    let clean_report = run_clean_report(&cli, &config, &entries, &scanned)?;
+    let use_secure_delete = secure_delete_enabled(&cli, &config);
+
+    let report = ScanReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        scan_target: scan_target_name.clone(),
+        sort_order: cli.sort.clone(),
+        total_items: entries.len(),
+        total_size,
+        total_size_display: format_size(total_size),
+        reclaimable_items,
+        reclaimable_estimate,
+        reclaimable_estimate_display: format_size(reclaimable_estimate),
+        categories: aggregate_by_category(&entries)
+            .into_iter()
+            .map(|(name, size)| ReportCategory {
+                name,
+                size,
+                size_display: format_size(size),
+            })
+            .collect(),
+        entries: report_entries,
+        dry_run: dry_run_report,
+        clean_result: clean_report,
+        empty_trash: empty_trash_report,
+    };
+
+    // 输出结果
+    if cli.format == OutputFormat::Csv {
+        write_csv_report(&report, &cli)?;
+    } else {
+        write_report(&report, &cli, || {
+            print_report_to_terminal(
+                &report,
+                &entries,
+                use_secure_delete,
+                config.ui.date_format.as_deref(),
+            )
+        })?;
+    }
+    Ok(all_succeeded(
+        report.clean_result.as_ref(),
+        report.empty_trash.as_ref(),
+    ))
+}
+
+/// 清理与清空回收站是否全部成功（未执行的操作视为成功）
+fn all_succeeded(clean: Option<&CleanReport>, empty_trash: Option<&EmptyTrashReport>) -> bool {
+    clean.is_none_or(|report| report.success) && empty_trash.is_none_or(|report| report.success)
+}
 
-    // Dry-run
-    let dry_run_report = if cli.dry_run {
-        let result = Cleaner::dry_run(&entries);
-        Some(DryRunReport {
-            total_files: result.total_files,
-            total_dirs: result.total_dirs,
-            total_size: result.total_size,
-            total_size_display: format_size(result.total_size),
-            items: result
-                .items
+/// 条目是否保留在报告与清理中：满足 `--min-size`，且不属于 `--exclude-category` 排除的类别
+fn passes_report_filters(entry: &CleanableEntry, cli: &Cli) -> bool {
+    meets_min_size(entry, cli.min_size)
+        && !entry.category.as_ref().is_some_and(|category| {
+            cli.exclude_category
                 .iter()
-                .map(|item| DryRunReportItem {
-                    path: item.path.display().to_string(),
-                    file_count: item.file_count,
-                    dir_count: item.dir_count,
-                    size: item.size,
-                    size_display: format_size(item.size),
-                })
-                .collect(),
+                .any(|filter| category.matches_filter(filter))
         })
-    } else {
+}
+
+/// 判断条目是否满足 `--min-size` 阈值：未设置阈值时全部保留，大小未知的条目视为不满足
+fn meets_min_size(entry: &CleanableEntry, min_size: Option<u64>) -> bool {
+    match min_size {
+        Some(threshold) => entry.size.is_some_and(|size| size >= threshold),
+        None => true,
+    }
+}
+
+/// 构建 dry-run 报告
+fn build_dry_run_report(entries: &[CleanableEntry], rules: &SafetyRules) -> DryRunReport {
+    let result = Cleaner::dry_run(entries, rules);
+    DryRunReport {
+        total_files: result.total_files,
+        total_dirs: result.total_dirs,
+        total_size: result.total_size,
+        total_size_display: format_size(result.total_size),
+        removable_size: result.removable_size,
+        removable_size_display: format_size(result.removable_size),
+        retained_size: result.retained_size,
+        retained_size_display: format_size(result.retained_size),
+        skipped_count: result.skipped_count,
+        items: result
+            .items
+            .iter()
+            .map(|item| DryRunReportItem {
+                path: item.path.display().to_string(),
+                file_count: item.file_count,
+                dir_count: item.dir_count,
+                size: item.size,
+                size_display: format_size(item.size),
+                removable_size: item.removable_size,
+                retained_size: item.retained_size,
+                skipped: item.skipped,
+            })
+            .collect(),
+    }
+}
+
+/// 按 `--clean` 执行清理并生成报告（未请求清理或 dry-run 时返回 None）
+///
+/// `scanned` 为过滤前的全部扫描结果，用于计入随父目标一并删除、但被过滤掉的嵌套目标大小。
+fn run_clean_report(
+    cli: &Cli,
+    config: &AppConfig,
+    entries: &[CleanableEntry],
+    scanned: &[CleanableEntry],
+) -> Result<Option<CleanReport>> {
+    if !cli.clean || cli.dry_run {
+        return Ok(None);
+    }
+    clean_entries(cli, config, entries, scanned)
+}
+
+/// 直接清理 `--delete` 指定的路径（不扫描），返回清理是否全部成功
+fn run_delete(cli: &Cli, config: &AppConfig) -> Result<bool> {
+    let scanner = scanner_from_config(config)
+        .ok_or_else(|| color_eyre::eyre::eyre!(SCAN_INIT_ERROR_MESSAGE))?;
+    let entries = match cli.keep_newest {
+        Some(keep) => {
+            let mut entries = Vec::new();
+            for path in &cli.delete {
+                entries.extend(retention_target_entries(path, keep, &scanner)?);
+            }
+            entries
+        }
+        None => cli
+            .delete
+            .iter()
+            .map(|path| delete_target_entry(path, &scanner))
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    let total_size: u64 = entries.iter().filter_map(|e| e.size).sum();
+    let dry_run = cli
+        .dry_run
+        .then(|| build_dry_run_report(&entries, &SafetyRules::from_config(config)));
+    let clean_result = if cli.dry_run {
         None
+    } else {
+        clean_entries(cli, config, &entries, &[])?
+    };
+    let report = DeleteReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        total_items: entries.len(),
+        total_size,
+        total_size_display: format_size(total_size),
+        entries: entries.iter().map(ReportEntry::from_entry).collect(),
+        dry_run,
+        clean_result,
     };
+    let use_secure_delete = secure_delete_enabled(cli, config);
+    write_report(&report, cli, || {
+        println!(
+            "待清理: {} 个项目 | 总大小: {}",
+            report.total_items, report.total_size_display
+        );
+        for entry in &report.entries {
+            println!("  {:>10}  {}", entry.size_display, entry.path);
+        }
+        if let Some(ref dry_run) = report.dry_run {
+            print_dry_run_report(dry_run, use_secure_delete);
+        }
+        if let Some(ref clean) = report.clean_result {
+            print_clean_report(clean, use_secure_delete);
+        }
+    })?;
+    Ok(all_succeeded(report.clean_result.as_ref(), None))
+}
 
-    // 清理
-    let use_trash = cli.trash || config.safety.move_to_trash;
-    let clean_report = if cli.clean && !cli.dry_run {
-        // 安全检查
-        for entry in &entries {
-            if !Cleaner::is_safe_to_delete(&entry.path) {
-                return Err(color_eyre::eyre::eyre!(
-                    "不安全的路径: {}",
-                    entry.path.display()
+/// 由 `--delete` 的路径生成清理条目：路径须存在，符号链接按文件处理（只删除链接本身）
+fn delete_target_entry(path: &std::path::Path, scanner: &Scanner) -> Result<CleanableEntry> {
+    let path = std::path::absolute(path)?;
+    let metadata = std::fs::symlink_metadata(&path)
+        .map_err(|err| color_eyre::eyre::eyre!("无法访问 {}: {}", path.display(), err))?;
+    let (kind, size) = if metadata.is_dir() {
+        (EntryKind::Directory, scanner.scan_directory(&path))
+    } else {
+        (EntryKind::File, metadata.len())
+    };
+    Ok(CleanableEntry {
+        kind,
+        category: None,
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string()),
+        size: Some(size),
+        modified_at: metadata.modified().ok(),
+        file_count: None,
+        path,
+    })
+}
+
+/// 由 `--delete` 与 `--keep-newest` 生成清理条目：路径须为目录，返回最新 `keep` 个之外的子项
+fn retention_target_entries(
+    path: &std::path::Path,
+    keep: usize,
+    scanner: &Scanner,
+) -> Result<Vec<CleanableEntry>> {
+    let path = std::path::absolute(path)?;
+    let metadata = std::fs::symlink_metadata(&path)
+        .map_err(|err| color_eyre::eyre::eyre!("无法访问 {}: {}", path.display(), err))?;
+    if !metadata.is_dir() {
+        return Err(color_eyre::eyre::eyre!(
+            "--keep-newest 需要目录: {}",
+            path.display()
+        ));
+    }
+    scanner
+        .retention_excess(&path, keep)
+        .map_err(|err| color_eyre::eyre::eyre!("无法读取目录 {}: {}", path.display(), err))
+}
+
+/// `--scan <路径> --keep-newest N` 的保留范围：每个扫描目录只允许报告与清理最新 N 个之外的子项
+struct RetentionScope {
+    /// 扫描目录及其中可清理子项的绝对路径
+    dirs: Vec<(PathBuf, Vec<PathBuf>)>,
+}
+
+impl RetentionScope {
+    /// 未设置 `--keep-newest` 时返回 None；扫描目标须为目录（由 `Cli::validate` 保证均为路径）
+    fn from_cli(cli: &Cli, config: &AppConfig) -> Result<Option<Self>> {
+        let Some(keep) = cli.keep_newest else {
+            return Ok(None);
+        };
+        let scanner = scanner_from_config(config)
+            .ok_or_else(|| color_eyre::eyre::eyre!(SCAN_INIT_ERROR_MESSAGE))?;
+        let mut dirs = Vec::new();
+        for target in &cli.scan {
+            if let ScanTarget::Path(path) = target {
+                let excess = retention_target_entries(path, keep, &scanner)?;
+                dirs.push((
+                    std::path::absolute(path)?,
+                    excess.into_iter().map(|entry| entry.path).collect(),
                 ));
             }
         }
+        Ok(Some(Self { dirs }))
+    }
 
-        let item_count = entries.len();
-        let result = if use_trash {
-            Cleaner::trash_items(&entries)
-        } else {
-            Cleaner::clean(&entries)
+    /// 扫描目录内的条目须位于可清理的子项之下；包含扫描目录本身的条目会连带删除受保护的子项，一律排除
+    fn allows(&self, entry: &CleanableEntry) -> bool {
+        let Ok(path) = std::path::absolute(&entry.path) else {
+            return false;
         };
+        self.dirs.iter().all(|(dir, excess)| {
+            if dir.starts_with(&path) {
+                false
+            } else if path.starts_with(dir) {
+                excess.iter().any(|allowed| path.starts_with(allowed))
+            } else {
+                true
+            }
+        })
+    }
+}
 
-        Some(CleanReport {
-            success: result.success,
-            freed_space: result.freed_space,
-            freed_space_display: format_size(result.freed_space),
-            item_count,
-            use_trash,
-            errors: result.errors,
+/// 对条目做安全检查、确认后执行清理并生成报告（用户在提示中取消时返回 None）
+fn clean_entries(
+    cli: &Cli,
+    config: &AppConfig,
+    entries: &[CleanableEntry],
+    roots: &[CleanableEntry],
+) -> Result<Option<CleanReport>> {
+    // 安全检查
+    let safety_rules = SafetyRules::from_config(config);
+    for entry in entries {
+        if safety_rules.is_own_data(&entry.path) {
+            return Err(color_eyre::eyre::eyre!(
+                "拒绝清理 VAC 自身的配置/缓存目录: {}",
+                entry.path.display()
+            ));
+        }
+        if !Cleaner::is_safe_to_delete(&entry.path, &safety_rules) {
+            return Err(color_eyre::eyre::eyre!(
+                "{}",
+                CleanError::Unsafe {
+                    path: entry.path.clone(),
+                }
+            ));
+        }
+    }
+
+    // 位于其他条目之下的路径随上级一起清理，不单独处理；父目标按含嵌套目标的完整大小计算
+    // 包含 VAC 自身数据的目录展开为其余子项，自身数据不随上级一起清理
+    let entries = &Cleaner::without_own_data(
+        &Cleaner::without_nested(
+            &entries
+                .iter()
+                .map(|entry| with_nested_root_sizes(entry.clone(), roots))
+                .collect::<Vec<_>>(),
+        ),
+        &safety_rules,
+    );
+    let force_trash = force_trash_enabled(cli, config);
+    let use_secure_delete = secure_delete_enabled(cli, config);
+    // --trash / --safe 覆盖配置文件，全部移至回收站
+    let trash_policy = if cli.trash || force_trash {
+        TrashPolicy::uniform(true)
+    } else {
+        TrashPolicy::from_config(config)
+    };
+    let item_count = entries.len();
+    let trashed_count = if use_secure_delete {
+        0
+    } else {
+        entries
+            .iter()
+            .filter(|entry| trash_policy.should_trash(entry))
+            .count()
+    };
+    // 由 trash 策略的实际划分得出，按类别分组时只要有条目移至回收站即为 true
+    let use_trash = trashed_count > 0;
+
+    if !entries.is_empty()
+        && !cli.yes
+        && !confirm_clean(entries, trashed_count, use_secure_delete, force_trash)?
+    {
+        eprintln!("已取消清理。");
+        return Ok(None);
+    }
+    let clean_options = CleanOptions {
+        stop_on_error: cli.strict || config.safety.stop_on_error,
+        ..CleanOptions::from_config(config)
+    };
+    let result = Cleaner::with_measured_freed(entries, config.safety.measure_freed_space, || {
+        Cleaner::with_pruned_empty_dirs(config.safety.prune_empty_dirs, || {
+            if use_secure_delete {
+                Cleaner::secure_clean(entries, SECURE_DELETE_PASSES, clean_options)
+            } else {
+                Cleaner::clean_with_policy(entries, &trash_policy, clean_options)
+            }
         })
+    });
+
+    Ok(Some(CleanReport {
+        success: result.success,
+        freed_space: result.freed_space,
+        freed_space_display: format_size(result.freed_space),
+        item_count,
+        use_trash,
+        trashed_count,
+        secure_delete: use_secure_delete,
+        errors: result.errors,
+    }))
+}
+
+/// 是否处于安全模式（`--safe` 或 `safety.force_trash`），此时一律移至回收站
+fn force_trash_enabled(cli: &Cli, config: &AppConfig) -> bool {
+    cli.safe || config.safety.force_trash
+}
+
+/// 是否安全擦除（`--secure` 或 `safety.secure_delete`，安全模式下始终为 false）
+fn secure_delete_enabled(cli: &Cli, config: &AppConfig) -> bool {
+    !force_trash_enabled(cli, config) && (cli.secure || config.safety.secure_delete)
+}
+
+/// 在终端中打印清理摘要并询问是否继续，非终端环境下要求显式传入 `--yes`
+///
+/// 提示写到 stderr、回答从 stdin 读取，两者都必须是终端；管道或重定向时直接报错，保持脚本行为确定。
+fn confirm_clean(
+    entries: &[CleanableEntry],
+    trashed_count: usize,
+    use_secure_delete: bool,
+    force_trash: bool,
+) -> Result<bool> {
+    use std::io::IsTerminal;
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() || !std::io::stderr().is_terminal() {
+        return Err(color_eyre::eyre::eyre!(
+            "--clean / --delete 需要 --yes 确认（当前不在终端中，无法询问）"
+        ));
+    }
+
+    let total_size: u64 = entries.iter().filter_map(|e| e.size).sum();
+    let permanent_count = entries.len() - trashed_count;
+    let mode = if use_secure_delete {
+        format!("全部 {} 项将安全删除（多次覆写，不可恢复）", entries.len())
+    } else if force_trash {
+        format!(
+            "安全模式: 仅移至回收站，全部 {} 项将移至回收站",
+            entries.len()
+        )
+    } else if permanent_count == 0 {
+        format!("全部 {} 项将移至回收站", entries.len())
     } else {
-        None
+        format!(
+            "{} 项移至回收站，{} 项永久删除",
+            trashed_count, permanent_count
+        )
     };
+    eprintln!("{}", mode);
+    eprint!(
+        "删除 {} 项，释放 {}？[y/N] ",
+        entries.len(),
+        format_size(total_size)
+    );
+    std::io::stderr().flush()?;
 
-    let report = ScanReport {
-        scan_target: scan_target_name.clone(),
-        sort_order: cli.sort.clone(),
-        total_items: entries.len(),
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
+/// NDJSON 流式输出：扫描中每个条目确定大小后立即写出一行，最后写出汇总行
+///
+/// 仅在需要 dry-run 或清理时才保留扫描条目；写入 `--output` 文件或 stdout。
+/// 指定 `--top` 时需先完成扫描并排序，条目在扫描结束后按排序顺序输出。
+fn run_ndjson_report(
+    cli: &Cli,
+    config: &AppConfig,
+    scan_targets: &[ScanTarget],
+    scan_target_name: String,
+    retention: Option<&RetentionScope>,
+    empty_trash_report: Option<EmptyTrashReport>,
+) -> Result<bool> {
+    let mut out: Box<dyn Write> = match cli.output {
+        Some(ref output_path) => {
+            Box::new(std::io::BufWriter::new(std::fs::File::create(output_path)?))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let keep_entries = cli.dry_run || cli.clean;
+    let safety_rules = SafetyRules::from_config(config);
+    let mut entries = Vec::new();
+    let mut scanned = Vec::new();
+    let mut total_items = 0usize;
+    let mut total_size = 0u64;
+    let mut reclaimable_items = 0usize;
+    let mut reclaimable_estimate = 0u64;
+    if let Some(top) = cli.top {
+        // 需要全部结果才能排序取前 N，此时放弃流式输出
+        let sort_order = SortOrder::from_name(&cli.sort).unwrap_or(SortOrder::BySize);
+        entries = run_scan_blocking(
+            scan_targets,
+            config,
+            !cli.no_cache,
+            &cli.category,
+            &cli.exclude_category,
+            cli.log_level(),
+        )?;
+        if let Some(retention) = retention {
+            entries.retain(|entry| retention.allows(entry));
+        }
+        if cli.clean {
+            scanned = entries.clone();
+        }
+        entries.retain(|entry| passes_report_filters(entry, cli));
+        sort_entries_by(&mut entries, sort_order);
+        entries.truncate(top);
+        (reclaimable_items, reclaimable_estimate) =
+            Cleaner::reclaimable_estimate(&entries, &safety_rules);
+        for entry in &entries {
+            total_items += 1;
+            total_size += entry.size.unwrap_or(0);
+            let line = NdjsonLine::Entry(ReportEntry::from_entry(entry));
+            writeln!(out, "{}", serde_json::to_string(&line)?)?;
+        }
+    } else {
+        run_scan_streaming(
+            scan_targets,
+            config,
+            !cli.no_cache,
+            &cli.category,
+            &cli.exclude_category,
+            cli.log_level(),
+            |entry| {
+                if retention.is_some_and(|retention| !retention.allows(&entry)) {
+                    return Ok(());
+                }
+                if cli.clean {
+                    scanned.push(entry.clone());
+                }
+                if !passes_report_filters(&entry, cli) {
+                    return Ok(());
+                }
+                total_items += 1;
+                total_size += entry.size.unwrap_or(0);
+                let (count, size) =
+                    Cleaner::reclaimable_estimate(std::slice::from_ref(&entry), &safety_rules);
+                reclaimable_items += count;
+                reclaimable_estimate += size;
+                let line = NdjsonLine::Entry(ReportEntry::from_entry(&entry));
+                writeln!(out, "{}", serde_json::to_string(&line)?)?;
+                if keep_entries {
+                    entries.push(entry);
+                }
+                Ok(())
+            },
+        )?;
+    }
+
+    let clean_result = run_clean_report(cli, config, &entries, &scanned)?;
+    let succeeded = all_succeeded(clean_result.as_ref(), empty_trash_report.as_ref());
+    let summary = NdjsonLine::Summary(Box::new(NdjsonSummary {
+        scan_target: scan_target_name,
+        total_items,
         total_size,
         total_size_display: format_size(total_size),
-        entries: report_entries,
-        dry_run: dry_run_report,
-        clean_result: clean_report,
+        reclaimable_items,
+        reclaimable_estimate,
+        reclaimable_estimate_display: format_size(reclaimable_estimate),
+        dry_run: cli
+            .dry_run
+            .then(|| build_dry_run_report(&entries, &safety_rules)),
+        clean_result,
+        empty_trash: empty_trash_report,
+    }));
+    writeln!(out, "{}", serde_json::to_string(&summary)?)?;
+    out.flush()?;
+
+    if let Some(ref output_path) = cli.output
+        && cli.log_level() >= LogLevel::Normal
+    {
+        eprintln!("报告已写入: {}", output_path.display());
+    }
+    Ok(succeeded)
+}
+
+/// CSV 输出：表头 + 每个条目一行，写入 `--output` 文件或 stdout
+///
+/// 大小或修改时间未知时对应单元格留空；清理结果等汇总信息输出到 stderr。
+fn write_csv_report(report: &ScanReport, cli: &Cli) -> Result<()> {
+    let mut out: Box<dyn Write> = match cli.output {
+        Some(ref output_path) => {
+            Box::new(std::io::BufWriter::new(std::fs::File::create(output_path)?))
+        }
+        None => Box::new(std::io::stdout().lock()),
     };
 
-    // 输出结果
+    writeln!(out, "path,name,kind,size_bytes,size_display,modified_at")?;
+    for entry in &report.entries {
+        let size_bytes = entry.size.map(|size| size.to_string()).unwrap_or_default();
+        let size_display = if entry.size.is_some() {
+            entry.size_display.as_str()
+        } else {
+            ""
+        };
+        let fields = [
+            entry.path.as_str(),
+            entry.name.as_str(),
+            entry.kind.as_str(),
+            size_bytes.as_str(),
+            size_display,
+            entry.modified_at.as_deref().unwrap_or(""),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_escape(field)).collect();
+        writeln!(out, "{}", row.join(","))?;
+    }
+    out.flush()?;
+
+    // 静默模式下仅在有失败项时输出
+    if let Some(ref clean) = report.clean_result
+        && (cli.log_level() >= LogLevel::Normal || !clean.errors.is_empty())
+    {
+        eprintln!(
+            "清理完成: {} 项，释放 {}，失败 {} 项",
+            clean.item_count,
+            clean.freed_space_display,
+            clean.errors.len()
+        );
+    }
+    if let Some(ref output_path) = cli.output
+        && cli.log_level() >= LogLevel::Normal
+    {
+        eprintln!("报告已写入: {}", output_path.display());
+    }
+    Ok(())
+}
+
+/// 输出报告：`--output` 写入 JSON 文件，`--format json` 打印 JSON 到 stdout，否则输出终端文本
+///
+/// 进度等提示信息统一走 stderr，保证 stdout 上的 JSON 可直接交给 `jq` 等工具处理。
+fn write_report<T: serde::Serialize>(
+    report: &T,
+    cli: &Cli,
+    print_text: impl FnOnce(),
+) -> Result<()> {
     if let Some(ref output_path) = cli.output {
-        let json = serde_json::to_string_pretty(&report)?;
+        let json = serde_json::to_string_pretty(report)?;
         std::fs::write(output_path, &json)?;
-        eprintln!("报告已写入: {}", output_path.display());
-    } else {
-        // 输出到终端
-        print_report_to_terminal(&report, &entries, use_trash);
+        if cli.log_level() >= LogLevel::Normal {
+            eprintln!("报告已写入: {}", output_path.display());
+        }
+        return Ok(());
     }
 
+    match cli.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Ndjson => println!("{}", serde_json::to_string(report)?),
+        // CSV 只用于扫描报告（`Cli::validate()` 已拒绝其他组合）
+        OutputFormat::Text | OutputFormat::Csv => print_text(),
+    }
     Ok(())
 }
 
 /// 将报告输出到终端
-fn print_report_to_terminal(report: &ScanReport, entries: &[CleanableEntry], use_trash: bool) {
+fn print_report_to_terminal(
+    report: &ScanReport,
+    entries: &[CleanableEntry],
+    use_secure_delete: bool,
+    date_format: Option<&str>,
+) {
     println!();
     println!(
         "扫描结果: {} 个项目 | 总大小: {}",
@@ -749,7 +2497,7 @@ fn print_report_to_terminal(report: &ScanReport, entries: &[CleanableEntry], use
         let time_str = entry
             .modified_at
             .as_ref()
-            .map(|time| format!("  {}", format_time(time, true)))
+            .map(|time| format!("  {}", format_time_with(time, date_format, true)))
             .unwrap_or_default();
 
         println!(
@@ -758,43 +2506,89 @@ fn print_report_to_terminal(report: &ScanReport, entries: &[CleanableEntry], use
         );
     }
     println!("{}", "─".repeat(REPORT_SEPARATOR_WIDTH));
+    // 固定英文前缀的单行汇总，便于 grep 提取
+    println!(
+        "TOTAL reclaimable: {} across {} items in {}",
+        report.reclaimable_estimate_display, report.reclaimable_items, report.scan_target
+    );
 
     // Dry-run 结果
     if let Some(ref dry_run) = report.dry_run {
-        println!();
-        println!("Dry-run 预览:");
-        println!(
-            "  总计: {} 个文件 / {} 个目录 / {}",
-            dry_run.total_files, dry_run.total_dirs, dry_run.total_size_display
-        );
-        for item in &dry_run.items {
-            println!(
-                "  • {} — {} 文件 / {} 目录 / {}",
-                item.path, item.file_count, item.dir_count, item.size_display
-            );
-        }
+        print_dry_run_report(dry_run, use_secure_delete);
     }
 
     // 清理结果
     if let Some(ref clean) = report.clean_result {
+        print_clean_report(clean, use_secure_delete);
+    }
+
+    // 清空回收站结果
+    if let Some(ref empty_trash) = report.empty_trash {
         println!();
-        let action = if use_trash {
-            "移至回收站"
-        } else {
-            "已删除"
-        };
-        if clean.success {
-            println!(
-                "{}: {} ({} 个项目)",
-                action, clean.freed_space_display, clean.item_count
-            );
+        print_empty_trash_report(empty_trash);
+    }
+
+    println!();
+}
+
+/// 将 dry-run 预览输出到终端
+fn print_dry_run_report(dry_run: &DryRunReport, use_secure_delete: bool) {
+    println!();
+    println!("Dry-run 预览:");
+    println!(
+        "  总计: {} 个文件 / {} 个目录 / {}",
+        dry_run.total_files, dry_run.total_dirs, dry_run.total_size_display
+    );
+    println!(
+        "  将删除: {} | 保留/跳过: {}（被清空的目录本身{}）",
+        dry_run.removable_size_display,
+        dry_run.retained_size_display,
+        if dry_run.skipped_count > 0 {
+            format!("，{} 项未通过安全检查", dry_run.skipped_count)
         } else {
-            println!("清理部分失败:");
-            for err in &clean.errors {
-                println!("  ✗ {}", err);
-            }
+            String::new()
         }
+    );
+    for item in &dry_run.items {
+        let skipped = if item.skipped {
+            "  [跳过: 未通过安全检查]"
+        } else {
+            ""
+        };
+        println!(
+            "  • {} — {} 文件 / {} 目录 / {}{}",
+            item.path, item.file_count, item.dir_count, item.size_display, skipped
+        );
+    }
+    if use_secure_delete {
+        println!(
+            "  注意: 已启用安全擦除，每个文件将覆写 {} 遍后删除，速度较慢且不可恢复",
+            SECURE_DELETE_PASSES
+        );
     }
+}
 
+/// 将清理结果输出到终端
+fn print_clean_report(clean: &CleanReport, use_secure_delete: bool) {
     println!();
+    let action = if use_secure_delete {
+        "已安全擦除".to_string()
+    } else if clean.trashed_count == 0 {
+        "已删除".to_string()
+    } else if clean.trashed_count == clean.item_count {
+        "移至回收站".to_string()
+    } else {
+        format!("已清理（其中 {} 项移至回收站）", clean.trashed_count)
+    };
+    if clean.success {
+        println!(
+            "{}: {} ({} 个项目)",
+            action, clean.freed_space_display, clean.item_count
+        );
+    } else {
+        println!("清理部分失败:");
+        for err in &clean.errors {
+            println!("  ✗ {}", err);
+        }
+    }
 }
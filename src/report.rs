@@ -0,0 +1,259 @@
+//! 非交互模式的报告结构（JSON / NDJSON 输出）
+//!
+//! 字段或结构发生不兼容变化时递增 `REPORT_SCHEMA_VERSION`，下游工具可反序列化为同样的类型。
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{CleanableEntry, EntryKind};
+use crate::cleaner::CleanError;
+use crate::scanner::format_size;
+use crate::utils::format_time;
+
+/// 报告格式版本，仅在不兼容变更（删除/重命名字段、改变字段含义或类型）时递增
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// 非交互模式的扫描结果条目（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportEntry {
+    pub path: String,
+    pub name: String,
+    pub kind: String,
+    pub size: Option<u64>,
+    pub size_display: String,
+    pub modified_at: Option<String>,
+}
+
+/// 非交互模式的类别汇总（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportCategory {
+    pub name: String,
+    pub size: u64,
+    pub size_display: String,
+}
+
+/// 非交互模式的 dry-run 条目（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunReportItem {
+    pub path: String,
+    pub file_count: usize,
+    pub dir_count: usize,
+    pub size: u64,
+    pub size_display: String,
+    pub removable_size: u64,
+    pub retained_size: u64,
+    pub skipped: bool,
+}
+
+/// 非交互模式的清理结果（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanReport {
+    pub success: bool,
+    pub freed_space: u64,
+    pub freed_space_display: String,
+    pub item_count: usize,
+    /// 是否有条目移至回收站（等价于 `trashed_count > 0`）
+    pub use_trash: bool,
+    /// 移至回收站的条目数（按 safety.trash_categories 分组时可能只占一部分）
+    pub trashed_count: usize,
+    pub secure_delete: bool,
+    pub errors: Vec<CleanError>,
+}
+
+/// 非交互模式的清空回收站结果（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmptyTrashReport {
+    pub success: bool,
+    pub freed_space: u64,
+    pub freed_space_display: String,
+    /// 实际删除的项目数（部分失败时只含已删除的部分）
+    pub item_count: usize,
+    /// 删除失败的项目，失败时其余项目仍会继续删除
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<CleanError>,
+}
+
+/// 预设扫描目标（`--list-targets` 的 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanTargetReport {
+    /// 类别标识（同 `--category`）
+    pub category: String,
+    /// 类别显示名
+    pub label: String,
+    pub path: String,
+    /// 路径不存在的条件目标不会被扫描
+    pub exists: bool,
+}
+
+/// `--list-targets` 的完整输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanTargetsReport {
+    /// 报告格式版本，见 `REPORT_SCHEMA_VERSION`
+    pub schema_version: u32,
+    pub targets: Vec<ScanTargetReport>,
+}
+
+/// 非交互模式的完整报告（用于 JSON 输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// 报告格式版本，见 `REPORT_SCHEMA_VERSION`
+    pub schema_version: u32,
+    pub scan_target: String,
+    pub sort_order: String,
+    pub total_items: usize,
+    pub total_size: u64,
+    pub total_size_display: String,
+    /// 通过安全检查、可实际回收的条目数
+    pub reclaimable_items: usize,
+    /// 全部清理后预计可回收的空间（排除未通过安全检查的条目）
+    pub reclaimable_estimate: u64,
+    pub reclaimable_estimate_display: String,
+    /// 按类别聚合的大小（降序，无类别的条目归入“其他”）
+    pub categories: Vec<ReportCategory>,
+    pub entries: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<DryRunReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clean_result: Option<CleanReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub empty_trash: Option<EmptyTrashReport>,
+}
+
+/// `--delete` 的完整输出：不扫描，直接清理指定路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteReport {
+    /// 报告格式版本，见 `REPORT_SCHEMA_VERSION`
+    pub schema_version: u32,
+    pub total_items: usize,
+    pub total_size: u64,
+    pub total_size_display: String,
+    pub entries: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<DryRunReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clean_result: Option<CleanReport>,
+}
+
+/// Dry-run 报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunReport {
+    pub total_files: usize,
+    pub total_dirs: usize,
+    pub total_size: u64,
+    pub total_size_display: String,
+    /// 实际会被删除的字节数（不含被保留的目录本身与跳过的项目）
+    pub removable_size: u64,
+    pub removable_size_display: String,
+    /// 保留（被清空的目录本身）或跳过（未通过安全检查）的字节数；
+    /// 与 `removable_size` 之和等于 `total_size` 加上各目录本身的占用（`total_size` 不含目录本身）
+    pub retained_size: u64,
+    pub retained_size_display: String,
+    pub skipped_count: usize,
+    pub items: Vec<DryRunReportItem>,
+}
+
+/// NDJSON 输出的单行（每行一个独立的 JSON 对象，以 `type` 区分）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NdjsonLine {
+    /// 扫描发现的条目
+    Entry(ReportEntry),
+    /// 末尾汇总行
+    Summary(Box<NdjsonSummary>),
+}
+
+/// NDJSON 汇总行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NdjsonSummary {
+    pub scan_target: String,
+    pub total_items: usize,
+    pub total_size: u64,
+    pub total_size_display: String,
+    pub reclaimable_items: usize,
+    pub reclaimable_estimate: u64,
+    pub reclaimable_estimate_display: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<DryRunReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clean_result: Option<CleanReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub empty_trash: Option<EmptyTrashReport>,
+}
+
+impl ReportEntry {
+    /// 由扫描条目生成报告条目
+    pub fn from_entry(entry: &CleanableEntry) -> Self {
+        Self {
+            path: entry.path.display().to_string(),
+            name: entry.name.clone(),
+            kind: match entry.kind {
+                EntryKind::Directory => "directory".to_string(),
+                EntryKind::File => "file".to_string(),
+            },
+            size: entry.size,
+            size_display: entry
+                .size
+                .map(format_size)
+                .unwrap_or_else(|| "未知".to_string()),
+            modified_at: entry
+                .modified_at
+                .as_ref()
+                .map(|time| format_time(time, true)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn scan_report_round_trips_with_schema_version() {
+        let report = ScanReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            scan_target: "preset".to_string(),
+            sort_order: "size".to_string(),
+            total_items: 1,
+            total_size: 8,
+            total_size_display: format_size(8),
+            reclaimable_items: 1,
+            reclaimable_estimate: 8,
+            reclaimable_estimate_display: format_size(8),
+            categories: Vec::new(),
+            entries: vec![ReportEntry::from_entry(&CleanableEntry {
+                kind: EntryKind::File,
+                category: None,
+                path: PathBuf::from("/tmp/report.bin"),
+                name: "report.bin".to_string(),
+                size: Some(8),
+                modified_at: None,
+                file_count: None,
+            })],
+            dry_run: None,
+            clean_result: Some(CleanReport {
+                success: false,
+                freed_space: 0,
+                freed_space_display: format_size(0),
+                item_count: 1,
+                use_trash: true,
+                trashed_count: 1,
+                secure_delete: false,
+                errors: vec![CleanError::NotFound {
+                    path: PathBuf::from("/tmp/report.bin"),
+                }],
+            }),
+            empty_trash: None,
+        };
+
+        let json = serde_json::to_value(&report).expect("serialize report");
+        assert_eq!(json["schema_version"], 1);
+        assert!(json.get("dry_run").is_none());
+
+        let parsed: ScanReport = serde_json::from_value(json).expect("deserialize report");
+        assert_eq!(parsed.entries[0].kind, "file");
+        assert_eq!(
+            parsed.clean_result.expect("clean result").errors,
+            report.clean_result.unwrap().errors
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::mpsc::Sender;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
-use crate::app::{CleanableEntry, EntryKind, ItemCategory};
+use crate::app::{CategoryStats, CleanableEntry, EntryKind, ItemCategory};
 
 const ROOT_PROGRESS_COMPLETE: f32 = 100.0;
 const DISK_PROGRESS_HALF: f32 = 50.0;
@@ -16,16 +19,6 @@ fn is_cancelled(cancel_generation: &AtomicU64, job_id: u64) -> bool {
     cancel_generation.load(Ordering::Relaxed) != job_id
 }
 
-fn add_target_if_exists(
-    targets: &mut Vec<(ItemCategory, PathBuf)>,
-    category: ItemCategory,
-    target_path: PathBuf,
-) {
-    if target_path.exists() {
-        targets.push((category, target_path));
-    }
-}
-
 /// 扫描类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScanKind {
@@ -75,103 +68,316 @@ impl ScanMessage {
     }
 }
 
+/// 扫描缓存条目：记录目录上次计算的大小及当时的顶层修改时间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub size: u64,
+    pub modified_at: SystemTime,
+    /// 目录下的文件数（旧版本写入的缓存没有该字段）
+    #[serde(default)]
+    pub file_count: Option<u64>,
+    /// 计算大小时跳过的嵌套扫描目标，跳过集合不同时缓存不命中
+    #[serde(default)]
+    pub pruned: Vec<PathBuf>,
+}
+
+/// 扫描缓存（按路径索引）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    pub entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ScanCache {
+    /// 查询缓存大小，仅当顶层修改时间未变化时命中
+    pub fn lookup(&self, path: &Path, modified_at: SystemTime) -> Option<u64> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.modified_at == modified_at)
+            .map(|entry| entry.size)
+    }
+}
+
 /// 磁盘扫描器
 pub struct Scanner {
     home_dir: PathBuf,
     /// 用户配置的额外扫描目标
     extra_targets: Vec<PathBuf>,
+    /// 用户配置的带名称自定义扫描目标 `(名称, 路径)`
+    custom_targets: Vec<(String, PathBuf)>,
+    /// 扫描缓存（未加载时为 None，不读写缓存）
+    cache: Option<ScanCache>,
+    /// 仅扫描这些类别的预设目标（为空时扫描全部）
+    categories: Vec<ItemCategory>,
+    /// 从预设目标中排除的类别（在 `categories` 过滤之后应用）
+    excluded_categories: Vec<ItemCategory>,
+    /// 计算目录大小时是否跟随符号链接
+    follow_symlinks: bool,
+    /// 限制并行度的专用线程池（None 时使用 rayon 全局线程池）
+    thread_pool: Option<rayon::ThreadPool>,
+    /// 列出目录时是否跳过子目录大小计算（留待按需计算）
+    lazy_sizes: bool,
 }
 
 impl Scanner {
+    /// 预设扫描目标表会产生的类别（顺序同 `ItemCategory::ALL`），设置面板只列出这些类别
+    ///
+    /// `AppCache` 与 `NodeModules` 只出现在主目录 / 自定义路径扫描中，开关它们不影响预设扫描。
+    pub const PRESET_CATEGORIES: [ItemCategory; 13] = [
+        ItemCategory::SystemCache,
+        ItemCategory::Logs,
+        ItemCategory::Temp,
+        ItemCategory::XcodeDerivedData,
+        ItemCategory::HomebrewCache,
+        ItemCategory::CocoaPods,
+        ItemCategory::NpmCache,
+        ItemCategory::PipCache,
+        ItemCategory::DockerData,
+        ItemCategory::CargoCache,
+        ItemCategory::Downloads,
+        ItemCategory::Trash,
+        ItemCategory::Custom(None),
+    ];
+
+    /// 创建扫描器；无法确定用户主目录时返回 None
     pub fn new() -> Option<Self> {
-        directories::UserDirs::new().map(|dirs| Self {
-            home_dir: dirs.home_dir().to_path_buf(),
+        crate::utils::home_dir().map(|home_dir| Self {
+            home_dir,
             extra_targets: Vec::new(),
+            custom_targets: Vec::new(),
+            cache: None,
+            categories: Vec::new(),
+            excluded_categories: Vec::new(),
+            follow_symlinks: false,
+            thread_pool: None,
+            lazy_sizes: false,
         })
     }
 
     /// 带额外扫描目标创建
     pub fn with_extra_targets(extra_targets: Vec<PathBuf>) -> Option<Self> {
-        directories::UserDirs::new().map(|dirs| Self {
-            home_dir: dirs.home_dir().to_path_buf(),
+        Self::new().map(|scanner| Self {
             extra_targets,
+            ..scanner
         })
     }
 
-    /// 获取所有扫描目标
+    /// 设置带名称的自定义扫描目标，每个目标在统计中单独成类
+    pub fn set_custom_targets(&mut self, custom_targets: Vec<(String, PathBuf)>) {
+        self.custom_targets = custom_targets;
+    }
+
+    /// 计算目录大小时跟随符号链接（循环链接由 WalkDir 检测并跳过）
+    ///
+    /// 开启后不读写扫描缓存，避免与未跟随时计算的大小混用。
+    pub fn set_follow_symlinks(&mut self, follow_symlinks: bool) {
+        self.follow_symlinks = follow_symlinks;
+    }
+
+    /// 限制并行计算目录大小的线程数；传入 None（或线程池创建失败）时使用 rayon 全局线程池
+    pub fn set_max_concurrency(&mut self, max_concurrency: Option<usize>) {
+        self.thread_pool = max_concurrency
+            .filter(|&threads| threads > 0)
+            .and_then(|threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .ok()
+            });
+    }
+
+    /// 列出目录时只发送条目、不计算子目录大小，之后由 `measure_dirs()` 按需计算
+    pub fn set_lazy_sizes(&mut self, lazy_sizes: bool) {
+        self.lazy_sizes = lazy_sizes;
+    }
+
+    /// 在专用线程池（若已设置）中执行并行任务
+    fn run_parallel<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// 限制预设扫描只包含指定类别（传入空列表表示不限制）
+    pub fn set_categories(&mut self, categories: Vec<ItemCategory>) {
+        self.categories = categories;
+    }
+
+    /// 从预设扫描中排除指定类别（与 `set_categories()` 组合时先包含再排除）
+    pub fn set_excluded_categories(&mut self, excluded_categories: Vec<ItemCategory>) {
+        self.excluded_categories = excluded_categories;
+    }
+
+    /// 缓存文件路径（~/.cache/vac/scan-cache.json）
+    pub fn cache_path(&self) -> PathBuf {
+        self.home_dir
+            .join(".cache")
+            .join("vac")
+            .join("scan-cache.json")
+    }
+
+    /// 从默认缓存文件加载扫描缓存，文件缺失或损坏时使用空缓存
+    pub fn load_cache(&mut self) {
+        let cache_path = self.cache_path();
+        self.load_cache_from(&cache_path);
+    }
+
+    /// 从指定文件加载扫描缓存
+    pub fn load_cache_from(&mut self, cache_path: &Path) {
+        let cache = fs::read_to_string(cache_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.cache = Some(cache);
+    }
+
+    /// 将扫描缓存写入默认缓存文件（未加载缓存时不做任何事）
+    pub fn save_cache(&self) -> std::io::Result<()> {
+        self.save_cache_to(&self.cache_path())
+    }
+
+    /// 将扫描缓存写入指定文件
+    pub fn save_cache_to(&self, cache_path: &Path) -> std::io::Result<()> {
+        let Some(cache) = &self.cache else {
+            return Ok(());
+        };
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(cache).map_err(std::io::Error::other)?;
+        fs::write(cache_path, json)
+    }
+
+    /// 获取所有扫描目标（条件目标仅在路径存在时包含）
+    ///
+    /// 同一路径只保留一个目标，避免重复扫描与重复计数：带名称的自定义目标优先于 `extra_targets`，
+    /// 其余按首次出现保留。
     pub fn get_scan_targets(&self) -> Vec<(ItemCategory, PathBuf)> {
+        let targets: Vec<(ItemCategory, PathBuf)> = self
+            .scan_target_candidates()
+            .into_iter()
+            .filter(|(_, path, always)| *always || path.exists())
+            .map(|(category, path, _)| (category, path))
+            .collect();
+        let named_paths: HashSet<PathBuf> = targets
+            .iter()
+            .filter(|(category, _)| matches!(category, ItemCategory::Custom(Some(_))))
+            .map(|(_, path)| path.clone())
+            .collect();
+        let mut seen = HashSet::new();
+        targets
+            .into_iter()
+            .filter(|(category, path)| {
+                !(*category == ItemCategory::Custom(None) && named_paths.contains(path))
+            })
+            .filter(|(_, path)| seen.insert(path.clone()))
+            .collect()
+    }
+
+    /// 列出全部预设扫描目标及其路径是否存在，包括因不存在而不会扫描的条件目标（不计算大小）
+    pub fn list_scan_targets(&self) -> Vec<(ItemCategory, PathBuf, bool)> {
+        self.scan_target_candidates()
+            .into_iter()
+            .map(|(category, path, _)| {
+                let exists = path.exists();
+                (category, path, exists)
+            })
+            .collect()
+    }
+
+    /// 候选扫描目标 `(类别, 路径, 是否无条件包含)`，已按包含 / 排除类别过滤
+    fn scan_target_candidates(&self) -> Vec<(ItemCategory, PathBuf, bool)> {
         let mut targets = vec![
             // 系统缓存
             (
                 ItemCategory::SystemCache,
                 self.home_dir.join("Library/Caches"),
+                true,
             ),
             // 日志文件
-            (ItemCategory::Logs, self.home_dir.join("Library/Logs")),
+            (ItemCategory::Logs, self.home_dir.join("Library/Logs"), true),
             // 临时文件
-            (ItemCategory::Temp, PathBuf::from("/tmp")),
-            (ItemCategory::Temp, PathBuf::from("/var/tmp")),
+            (ItemCategory::Temp, PathBuf::from("/tmp"), true),
+            (ItemCategory::Temp, PathBuf::from("/var/tmp"), true),
             // 下载文件夹
-            (ItemCategory::Downloads, self.home_dir.join("Downloads")),
+            (
+                ItemCategory::Downloads,
+                self.home_dir.join("Downloads"),
+                true,
+            ),
             // 垃圾桶
-            (ItemCategory::Trash, self.home_dir.join(".Trash")),
+            (ItemCategory::Trash, self.home_dir.join(".Trash"), true),
+            // 以下为条件目标，路径存在时才扫描
+            // Xcode 派生数据
+            (
+                ItemCategory::XcodeDerivedData,
+                self.home_dir.join("Library/Developer/Xcode/DerivedData"),
+                false,
+            ),
+            // Homebrew 缓存
+            (
+                ItemCategory::HomebrewCache,
+                self.home_dir.join("Library/Caches/Homebrew"),
+                false,
+            ),
+            // CocoaPods 缓存
+            (
+                ItemCategory::CocoaPods,
+                self.home_dir.join("Library/Caches/CocoaPods"),
+                false,
+            ),
+            // npm 缓存
+            (
+                ItemCategory::NpmCache,
+                self.home_dir.join(".npm/_cacache"),
+                false,
+            ),
+            // pip 缓存
+            (
+                ItemCategory::PipCache,
+                self.home_dir.join("Library/Caches/pip"),
+                false,
+            ),
+            // Docker 数据
+            (
+                ItemCategory::DockerData,
+                self.home_dir
+                    .join("Library/Containers/com.docker.docker/Data"),
+                false,
+            ),
+            // Cargo 缓存
+            (
+                ItemCategory::CargoCache,
+                self.home_dir.join(".cargo/registry/cache"),
+                false,
+            ),
         ];
 
-        // Xcode 派生数据（条件添加）
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::XcodeDerivedData,
-            self.home_dir.join("Library/Developer/Xcode/DerivedData"),
-        );
-
-        // Homebrew 缓存（条件添加）
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::HomebrewCache,
-            self.home_dir.join("Library/Caches/Homebrew"),
-        );
-
-        // CocoaPods 缓存
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::CocoaPods,
-            self.home_dir.join("Library/Caches/CocoaPods"),
-        );
-
-        // npm 缓存
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::NpmCache,
-            self.home_dir.join(".npm/_cacache"),
-        );
-
-        // pip 缓存
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::PipCache,
-            self.home_dir.join("Library/Caches/pip"),
-        );
-
-        // Docker 数据
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::DockerData,
-            self.home_dir
-                .join("Library/Containers/com.docker.docker/Data"),
-        );
-
-        // Cargo 缓存
-        add_target_if_exists(
-            &mut targets,
-            ItemCategory::CargoCache,
-            self.home_dir.join(".cargo/registry/cache"),
-        );
-
         // 用户配置的额外扫描目标
         for extra_path in &self.extra_targets {
-            add_target_if_exists(&mut targets, ItemCategory::Custom, extra_path.clone());
+            targets.push((ItemCategory::Custom(None), extra_path.clone(), false));
+        }
+        for (name, path) in &self.custom_targets {
+            targets.push((
+                ItemCategory::Custom(Some(name.clone())),
+                path.clone(),
+                false,
+            ));
         }
 
+        if !self.categories.is_empty() {
+            targets.retain(|(category, _, _)| {
+                self.categories
+                    .iter()
+                    .any(|filter| category.matches_filter(filter))
+            });
+        }
+        targets.retain(|(category, _, _)| {
+            !self
+                .excluded_categories
+                .iter()
+                .any(|filter| category.matches_filter(filter))
+        });
         targets
     }
 
@@ -182,7 +388,7 @@ impl Scanner {
         }
 
         WalkDir::new(path)
-            .follow_links(false)
+            .follow_links(self.follow_symlinks)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
@@ -191,18 +397,118 @@ impl Scanner {
             .sum()
     }
 
+    /// 保留策略：按修改时间列出目录的直接子项，返回最新 `keep` 个之外的条目
+    ///
+    /// 结果从新到旧排列，子目录大小已计算；修改时间未知的子项视为最旧。
+    /// 符号链接（如指向最新版本的 `current`）不参与排序也不会被列出，既不占用保留名额，
+    /// 也不会因链接本身较旧而被清理。清理只清空目录而保留目录本身，
+    /// 空子目录的修改时间会因此刷新，故不参与排序，以免占用保留名额。
+    pub fn retention_excess(
+        &self,
+        dir: &Path,
+        keep: usize,
+    ) -> std::io::Result<Vec<CleanableEntry>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_symlink() {
+                continue;
+            }
+            let metadata = entry.metadata().ok();
+            let path = entry.path();
+            let kind = if file_type.is_dir() {
+                if fs::read_dir(&path).is_ok_and(|mut children| children.next().is_none()) {
+                    continue;
+                }
+                EntryKind::Directory
+            } else {
+                EntryKind::File
+            };
+            entries.push(CleanableEntry {
+                kind,
+                category: None,
+                path,
+                name: entry.file_name().to_string_lossy().to_string(),
+                size: metadata.as_ref().map(|m| m.len()),
+                modified_at: metadata.and_then(|m| m.modified().ok()),
+                file_count: None,
+            });
+        }
+
+        entries.sort_by(|a, b| {
+            b.modified_at
+                .cmp(&a.modified_at)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let mut excess = entries.split_off(keep.min(entries.len()));
+        for entry in &mut excess {
+            if entry.kind == EntryKind::Directory {
+                entry.size = Some(self.scan_directory(&entry.path));
+            }
+        }
+        Ok(excess)
+    }
+
     fn scan_directory_with_cancel(
         &self,
         path: &PathBuf,
+        pruned: &[PathBuf],
         job_id: u64,
         cancel_gen: &AtomicU64,
-    ) -> u64 {
-        calc_dir_size(path, job_id, cancel_gen)
+    ) -> (u64, u64) {
+        calc_dir_stats_pruned(path, self.follow_symlinks, pruned, job_id, cancel_gen)
+    }
+
+    /// 计算目录大小与文件数（跳过 `pruned` 中的子目录），顶层修改时间未变化时直接复用缓存结果
+    ///
+    /// 返回 `(大小, 文件数)`；命中旧版本缓存时文件数未知。
+    /// 目录的修改时间只随直接子项的增删改名变化，更深层文件的写入、增删不会使缓存失效，
+    /// 此时返回的是过期大小（需 `--no-cache` 重新计算）。
+    fn scan_directory_cached(
+        &mut self,
+        path: &PathBuf,
+        modified_at: Option<SystemTime>,
+        pruned: &[PathBuf],
+        job_id: u64,
+        cancel_gen: &AtomicU64,
+    ) -> (u64, Option<u64>) {
+        let use_cache = !self.follow_symlinks;
+        if use_cache
+            && let (Some(cache), Some(modified_at)) = (&self.cache, modified_at)
+            && let Some(entry) = cache
+                .entries
+                .get(path)
+                .filter(|entry| entry.modified_at == modified_at && entry.pruned == pruned)
+        {
+            return (entry.size, entry.file_count);
+        }
+
+        let (size, file_count) = self.scan_directory_with_cancel(path, pruned, job_id, cancel_gen);
+        if is_cancelled(cancel_gen, job_id) {
+            return (size, Some(file_count));
+        }
+        if use_cache && let (Some(cache), Some(modified_at)) = (&mut self.cache, modified_at) {
+            cache.entries.insert(
+                path.clone(),
+                CacheEntry {
+                    size,
+                    modified_at,
+                    file_count: Some(file_count),
+                    pruned: pruned.to_vec(),
+                },
+            );
+        }
+        (size, Some(file_count))
     }
 
     /// 带进度回调的根目录扫描
+    ///
+    /// 已加载缓存时，顶层修改时间未变化的目录直接复用缓存大小，扫描完成后回写缓存。
+    /// 目标嵌套时（如 Homebrew 缓存位于系统缓存之下），父目标的大小不含子目标，避免重复计算。
     pub fn scan_root_with_progress(
-        &self,
+        &mut self,
         job_id: u64,
         tx: Sender<ScanMessage>,
         cancel_gen: Arc<AtomicU64>,
@@ -213,8 +519,12 @@ impl Scanner {
 
         let targets = self.get_scan_targets();
         let total = targets.len().max(1);
+        let nested: Vec<Vec<PathBuf>> = targets
+            .iter()
+            .map(|(_, path)| nested_target_paths(&targets, path))
+            .collect();
 
-        for (index, (category, path)) in targets.into_iter().enumerate() {
+        for (index, ((category, path), pruned)) in targets.into_iter().zip(nested).enumerate() {
             if is_cancelled(&cancel_gen, job_id) {
                 return;
             }
@@ -228,13 +538,14 @@ impl Scanner {
             });
 
             if path.exists() {
-                let size = self.scan_directory_with_cancel(&path, job_id, &cancel_gen);
+                let modified_at = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                let (size, file_count) =
+                    self.scan_directory_cached(&path, modified_at, &pruned, job_id, &cancel_gen);
                 if is_cancelled(&cancel_gen, job_id) {
                     return;
                 }
                 if size > 0 {
                     let name = category.as_str().to_string();
-                    let modified_at = fs::metadata(&path).and_then(|m| m.modified()).ok();
                     let entry = CleanableEntry {
                         kind: EntryKind::Directory,
                         category: Some(category),
@@ -242,12 +553,15 @@ impl Scanner {
                         name,
                         size: Some(size),
                         modified_at,
+                        file_count,
                     };
                     let _ = tx.send(ScanMessage::RootItem { job_id, entry });
                 }
             }
         }
 
+        // 缓存写入失败不影响扫描结果
+        let _ = self.save_cache();
         let _ = tx.send(ScanMessage::Done { job_id });
     }
 
@@ -304,6 +618,7 @@ impl Scanner {
                     name,
                     size: None,
                     modified_at,
+                    file_count: None,
                 };
                 let _ = tx.send(ScanMessage::DirEntry { job_id, entry });
             } else if file_type.is_file() {
@@ -317,26 +632,16 @@ impl Scanner {
                     name,
                     size,
                     modified_at,
+                    file_count: None,
                 };
                 let _ = tx.send(ScanMessage::DirEntry { job_id, entry });
             }
         }
 
         // 并行计算目录大小
-        dir_paths.par_iter().for_each(|dir_path| {
-            if is_cancelled(&cancel_gen, job_id) {
-                return;
-            }
-            let size = calc_dir_size(dir_path, job_id, &cancel_gen);
-            if is_cancelled(&cancel_gen, job_id) {
-                return;
-            }
-            let _ = tx.send(ScanMessage::DirEntrySize {
-                job_id,
-                path: dir_path.clone(),
-                size,
-            });
-        });
+        if !self.lazy_sizes {
+            self.send_dir_sizes(job_id, &dir_paths, &tx, &cancel_gen);
+        }
 
         let _ = tx.send(ScanMessage::Done { job_id });
     }
@@ -421,6 +726,7 @@ impl Scanner {
                     name,
                     size: None,
                     modified_at,
+                    file_count: None,
                 };
                 let _ = tx.send(ScanMessage::RootItem { job_id, entry });
             } else if file_type.is_file() {
@@ -434,6 +740,7 @@ impl Scanner {
                     name,
                     size,
                     modified_at,
+                    file_count: None,
                 };
                 let _ = tx.send(ScanMessage::RootItem { job_id, entry });
             }
@@ -445,22 +752,74 @@ impl Scanner {
             progress: DISK_PROGRESS_STAGE_SIZE,
             path: "并行计算目录大小...".to_string(),
         });
-        dir_paths.par_iter().for_each(|dir_path| {
-            if is_cancelled(&cancel_gen, job_id) {
-                return;
-            }
-            let size = calc_dir_size(dir_path, job_id, &cancel_gen);
-            if is_cancelled(&cancel_gen, job_id) {
-                return;
-            }
-            let _ = tx.send(ScanMessage::DirEntrySize {
-                job_id,
-                path: dir_path.clone(),
-                size,
+        self.send_dir_sizes(job_id, &dir_paths, &tx, &cancel_gen);
+
+        let _ = tx.send(ScanMessage::Done { job_id });
+    }
+
+    /// 按需计算指定目录的大小，逐个发送 `DirEntrySize`，全部完成后发送 `Done`
+    pub fn measure_dirs(
+        &self,
+        job_id: u64,
+        dir_paths: Vec<PathBuf>,
+        tx: Sender<ScanMessage>,
+        cancel_gen: Arc<AtomicU64>,
+    ) {
+        self.send_dir_sizes(job_id, &dir_paths, &tx, &cancel_gen);
+        let _ = tx.send(ScanMessage::Done { job_id });
+    }
+
+    /// 并行计算目录大小并逐个发送 `DirEntrySize`（已取消时不再发送）
+    fn send_dir_sizes(
+        &self,
+        job_id: u64,
+        dir_paths: &[PathBuf],
+        tx: &Sender<ScanMessage>,
+        cancel_gen: &AtomicU64,
+    ) {
+        let follow_links = self.follow_symlinks;
+        self.run_parallel(|| {
+            dir_paths.par_iter().for_each(|dir_path| {
+                if is_cancelled(cancel_gen, job_id) {
+                    return;
+                }
+                let size = calc_dir_size(dir_path, follow_links, job_id, cancel_gen);
+                if is_cancelled(cancel_gen, job_id) {
+                    return;
+                }
+                let _ = tx.send(ScanMessage::DirEntrySize {
+                    job_id,
+                    path: dir_path.clone(),
+                    size,
+                });
             });
         });
+    }
 
-        let _ = tx.send(ScanMessage::Done { job_id });
+    /// 同步扫描预设可清理目录，返回全部根层条目（供库调用方使用，无需自行处理通道）
+    ///
+    /// 开始时读取 `cancel` 的当前值作为任务代数，其他线程修改该值即取消扫描，此时返回已完成的条目。
+    pub fn scan_root(&mut self, cancel: &Arc<AtomicU64>) -> Vec<CleanableEntry> {
+        let job_id = cancel.load(Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel();
+        self.scan_root_with_progress(job_id, tx, Arc::clone(cancel));
+        // 根层扫描不会发送错误消息
+        collect_scan_entries(rx).unwrap_or_default()
+    }
+
+    /// 同步扫描指定路径的顶层目录/文件，返回带大小的条目
+    ///
+    /// 路径不存在、不是目录或无法读取时返回错误消息；取消方式同 `scan_root()`，
+    /// 取消后尚未算出大小的目录条目大小为 `None`。
+    pub fn scan_disk(
+        &self,
+        path: PathBuf,
+        cancel: &Arc<AtomicU64>,
+    ) -> Result<Vec<CleanableEntry>, String> {
+        let job_id = cancel.load(Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel();
+        self.scan_disk_with_progress(job_id, path, tx, Arc::clone(cancel));
+        collect_scan_entries(rx)
     }
 
     /// 获取用户主目录
@@ -469,28 +828,178 @@ impl Scanner {
     }
 }
 
-impl Default for Scanner {
-    fn default() -> Self {
-        Self::new().expect("无法获取用户目录")
+/// 按条目自带的类别聚合大小，返回 (类别名, 总大小)，按大小降序（大小相同时按名称）；没有类别的条目归入“其他”
+pub fn aggregate_by_category(entries: &[CleanableEntry]) -> Vec<(String, u64)> {
+    aggregate_category_stats(entries, |entry| entry.category.as_ref())
+        .into_iter()
+        .map(|stats| (stats.name, stats.size))
+        .collect()
+}
+
+/// 按 `category_of` 给出的类别聚合大小与文件数，按大小降序，大小相同时按名称升序（输出顺序稳定）
+///
+/// `category_of` 返回 None 的条目归入“其他”；任一条目文件数未知时该分类文件数为 None。
+pub fn aggregate_category_stats<'a>(
+    entries: &'a [CleanableEntry],
+    category_of: impl Fn(&'a CleanableEntry) -> Option<&'a ItemCategory>,
+) -> Vec<CategoryStats> {
+    let mut stats: HashMap<String, CategoryStats> = HashMap::new();
+    for entry in entries {
+        let category_name = category_of(entry)
+            .map(|c| c.as_str().to_string())
+            .unwrap_or_else(|| "其他".to_string());
+        let category_stats = stats
+            .entry(category_name.clone())
+            .or_insert_with(|| CategoryStats {
+                name: category_name,
+                size: 0,
+                item_count: 0,
+                file_count: Some(0),
+            });
+        category_stats.size += entry.size.unwrap_or(0);
+        category_stats.item_count += 1;
+        category_stats.file_count = category_stats
+            .file_count
+            .zip(entry.file_count)
+            .map(|(total, count)| total + count);
     }
+    let mut result: Vec<CategoryStats> = stats.into_values().collect();
+    result.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+/// 收集扫描消息中的条目，并按 `DirEntrySize` 回填目录大小（发送端已全部关闭）
+fn collect_scan_entries(rx: Receiver<ScanMessage>) -> Result<Vec<CleanableEntry>, String> {
+    let mut entries: Vec<CleanableEntry> = Vec::new();
+    let mut index_by_path: HashMap<PathBuf, usize> = HashMap::new();
+    for msg in rx {
+        match msg {
+            ScanMessage::RootItem { entry, .. } | ScanMessage::DirEntry { entry, .. } => {
+                index_by_path.insert(entry.path.clone(), entries.len());
+                entries.push(entry);
+            }
+            ScanMessage::DirEntrySize { path, size, .. } => {
+                if let Some(&index) = index_by_path.get(&path) {
+                    entries[index].size = Some(size);
+                }
+            }
+            ScanMessage::Error { message, .. } => return Err(message),
+            ScanMessage::Progress { .. } | ScanMessage::Done { .. } => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// 目录预览结果（仅包含直接子项）
+#[derive(Debug, Clone)]
+pub struct PeekResult {
+    pub path: PathBuf,
+    /// 按名称排序后的前 `limit` 个子项（目录大小不计算）
+    pub entries: Vec<CleanableEntry>,
+    /// 子项总数
+    pub total: usize,
+}
+
+/// 轻量读取目录的直接子项，用于预览而不进入目录
+///
+/// 先只读取名称与类型（通常无需 stat）排序并截断到 `limit`，再只为保留的子项读取大小与修改时间。
+pub fn peek_dir(path: &Path, limit: usize) -> std::io::Result<PeekResult> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path)?.filter_map(|e| e.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let kind = if file_type.is_dir() {
+            EntryKind::Directory
+        } else {
+            EntryKind::File
+        };
+        entries.push(CleanableEntry {
+            kind,
+            category: None,
+            path: entry.path(),
+            name: entry.file_name().to_string_lossy().to_string(),
+            size: None,
+            modified_at: None,
+            file_count: None,
+        });
+    }
+
+    let total = entries.len();
+    crate::app::sort_entries_by(&mut entries, crate::app::SortOrder::ByName);
+    entries.truncate(limit);
+    for entry in &mut entries {
+        let metadata = fs::symlink_metadata(&entry.path).ok();
+        if entry.kind == EntryKind::File {
+            entry.size = metadata.as_ref().map(|m| m.len());
+        }
+        entry.modified_at = metadata.and_then(|m| m.modified().ok());
+    }
+    Ok(PeekResult {
+        path: path.to_path_buf(),
+        entries,
+        total,
+    })
 }
 
 /// 根据配置创建 Scanner
 pub fn scanner_from_config(config: &crate::config::AppConfig) -> Option<Scanner> {
     let extra_targets = config.expanded_extra_targets();
-    Scanner::with_extra_targets(extra_targets)
+    let mut scanner = Scanner::with_extra_targets(extra_targets)?;
+    scanner.set_custom_targets(config.expanded_custom_targets());
+    scanner.set_follow_symlinks(config.scan.follow_symlinks);
+    scanner.set_max_concurrency(config.scan.max_concurrency);
+    Some(scanner)
+}
+
+/// 位于 `parent` 之下的其他扫描目标路径（不含与 `parent` 相同的路径）
+fn nested_target_paths(targets: &[(ItemCategory, PathBuf)], parent: &Path) -> Vec<PathBuf> {
+    targets
+        .iter()
+        .map(|(_, path)| path)
+        .filter(|path| path.as_path() != parent && path.starts_with(parent))
+        .cloned()
+        .collect()
 }
 
 /// 计算目录大小（可取消），独立函数以支持 rayon 并行调用
-fn calc_dir_size(path: &PathBuf, job_id: u64, cancel_gen: &AtomicU64) -> u64 {
+fn calc_dir_size(path: &PathBuf, follow_links: bool, job_id: u64, cancel_gen: &AtomicU64) -> u64 {
+    calc_dir_stats(path, follow_links, job_id, cancel_gen).0
+}
+
+/// 计算目录大小与文件数（可取消），返回 `(大小, 文件数)`
+///
+/// `follow_links` 为 true 时跟随符号链接，WalkDir 检测到的循环链接作为错误项跳过。
+fn calc_dir_stats(
+    path: &PathBuf,
+    follow_links: bool,
+    job_id: u64,
+    cancel_gen: &AtomicU64,
+) -> (u64, u64) {
+    calc_dir_stats_pruned(path, follow_links, &[], job_id, cancel_gen)
+}
+
+/// 同 `calc_dir_stats()`，但跳过 `pruned` 中的子目录（不进入其内部）
+fn calc_dir_stats_pruned(
+    path: &PathBuf,
+    follow_links: bool,
+    pruned: &[PathBuf],
+    job_id: u64,
+    cancel_gen: &AtomicU64,
+) -> (u64, u64) {
     if !path.exists() {
-        return 0;
+        return (0, 0);
     }
 
     let mut total = 0u64;
-    for entry in WalkDir::new(path).follow_links(false).into_iter() {
+    let mut file_count = 0u64;
+    let walker = WalkDir::new(path)
+        .follow_links(follow_links)
+        .into_iter()
+        .filter_entry(|entry| !pruned.iter().any(|skip| entry.path() == skip));
+    for entry in walker {
         if is_cancelled(cancel_gen, job_id) {
-            return total;
+            return (total, file_count);
         }
         let entry = match entry {
             Ok(entry) => entry,
@@ -501,15 +1010,49 @@ fn calc_dir_size(path: &PathBuf, job_id: u64, cancel_gen: &AtomicU64) -> u64 {
         }
         if let Ok(metadata) = entry.metadata() {
             total += metadata.len();
+            file_count += 1;
         }
     }
 
-    total
+    (total, file_count)
 }
 
-/// 格式化字节大小为人类可读格式
+/// 大小显示单位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    /// 二进制单位（KiB / MiB / GiB，1024 进制）
+    #[default]
+    Binary,
+    /// 十进制单位（kB / MB / GB，1000 进制，与 Finder 一致）
+    Decimal,
+}
+
+/// 全局大小显示单位是否为十进制（启动时由配置设置一次）
+static DECIMAL_SIZE_UNITS: AtomicBool = AtomicBool::new(false);
+
+/// 设置 `format_size` 使用的全局大小单位
+pub fn set_size_units(units: SizeUnits) {
+    DECIMAL_SIZE_UNITS.store(units == SizeUnits::Decimal, Ordering::Relaxed);
+}
+
+/// 格式化字节大小为人类可读格式（单位由 `set_size_units` 决定）
 pub fn format_size(bytes: u64) -> String {
-    bytesize::ByteSize::b(bytes).to_string()
+    let units = if DECIMAL_SIZE_UNITS.load(Ordering::Relaxed) {
+        SizeUnits::Decimal
+    } else {
+        SizeUnits::Binary
+    };
+    format_size_with(bytes, units)
+}
+
+/// 以指定单位格式化字节大小
+pub fn format_size_with(bytes: u64, units: SizeUnits) -> String {
+    let display = bytesize::ByteSize::b(bytes).display();
+    match units {
+        SizeUnits::Binary => display.iec().to_string(),
+        SizeUnits::Decimal => display.si().to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -520,6 +1063,92 @@ mod tests {
     use std::sync::mpsc;
     use std::sync::{Arc, atomic::AtomicU64};
 
+    #[test]
+    fn preset_categories_match_scan_target_table() {
+        let scanner =
+            Scanner::with_extra_targets(vec![PathBuf::from("/tmp/extra")]).expect("user dirs");
+        let mut produced: Vec<ItemCategory> = Vec::new();
+        for (category, _, _) in scanner.scan_target_candidates() {
+            if !produced.contains(&category) {
+                produced.push(category);
+            }
+        }
+        assert_eq!(produced.len(), Scanner::PRESET_CATEGORIES.len());
+        assert!(
+            Scanner::PRESET_CATEGORIES
+                .iter()
+                .all(|category| produced.contains(category))
+        );
+        assert!(!Scanner::PRESET_CATEGORIES.contains(&ItemCategory::AppCache));
+        assert!(!Scanner::PRESET_CATEGORIES.contains(&ItemCategory::NodeModules));
+    }
+
+    #[test]
+    fn get_scan_targets_respects_category_filter() {
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.set_categories(vec![ItemCategory::Temp, ItemCategory::Logs]);
+        let targets = scanner.get_scan_targets();
+        assert!(!targets.is_empty());
+        assert!(
+            targets
+                .iter()
+                .all(|(category, _)| matches!(category, ItemCategory::Temp | ItemCategory::Logs))
+        );
+        assert!(targets.iter().any(|(_, path)| path == Path::new("/tmp")));
+    }
+
+    #[test]
+    fn list_scan_targets_marks_missing_conditional_targets() {
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.set_custom_targets(vec![
+            ("Scratch".to_string(), PathBuf::from("/tmp")),
+            (
+                "Gone".to_string(),
+                PathBuf::from("/tmp/path-does-not-exist"),
+            ),
+        ]);
+        scanner.set_categories(vec![ItemCategory::Custom(None), ItemCategory::Temp]);
+
+        let listed = scanner.list_scan_targets();
+        assert!(listed.contains(&(ItemCategory::Temp, PathBuf::from("/tmp"), true)));
+        assert!(listed.contains(&(
+            ItemCategory::Custom(Some("Gone".to_string())),
+            PathBuf::from("/tmp/path-does-not-exist"),
+            false
+        )));
+        // 扫描时按路径去重，Scratch 与预设的 /tmp 只扫描一次
+        let scanned = scanner.get_scan_targets();
+        let existing: HashSet<&PathBuf> = listed
+            .iter()
+            .filter(|(_, _, exists)| *exists)
+            .map(|(_, path, _)| path)
+            .collect();
+        assert_eq!(scanned.len(), existing.len());
+    }
+
+    #[test]
+    fn get_scan_targets_applies_exclusions_after_inclusions() {
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.set_categories(vec![ItemCategory::Temp, ItemCategory::Logs]);
+        scanner.set_excluded_categories(vec![ItemCategory::Logs]);
+        let targets = scanner.get_scan_targets();
+        assert!(!targets.is_empty());
+        assert!(
+            targets
+                .iter()
+                .all(|(category, _)| *category == ItemCategory::Temp)
+        );
+
+        scanner.set_categories(Vec::new());
+        scanner.set_excluded_categories(vec![ItemCategory::Temp]);
+        let targets = scanner.get_scan_targets();
+        assert!(
+            !targets
+                .iter()
+                .any(|(category, _)| *category == ItemCategory::Temp)
+        );
+    }
+
     #[test]
     fn scan_directory_returns_zero_for_missing_path() {
         let scanner = Scanner::new().expect("user dirs");
@@ -572,15 +1201,11 @@ mod tests {
         let mut saw_dir_size = false;
         for msg in rx {
             match msg {
-                ScanMessage::DirEntry { entry, .. } => {
-                    if entry.kind == EntryKind::Directory {
-                        saw_dir = true;
-                    }
+                ScanMessage::DirEntry { entry, .. } if entry.kind == EntryKind::Directory => {
+                    saw_dir = true;
                 }
-                ScanMessage::DirEntrySize { path, size, .. } => {
-                    if path == sub_dir && size > 0 {
-                        saw_dir_size = true;
-                    }
+                ScanMessage::DirEntrySize { path, size, .. } if path == sub_dir && size > 0 => {
+                    saw_dir_size = true;
                 }
                 ScanMessage::Done { .. } => break,
                 _ => {}
@@ -606,4 +1231,406 @@ mod tests {
 
         assert!(rx.try_recv().is_err());
     }
+
+    #[test]
+    fn peek_dir_lists_children_up_to_limit() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-peek-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        fs::create_dir(dir.path().join("sub")).expect("create sub dir");
+        fs::write(dir.path().join("a.txt"), b"hello").expect("write a");
+        fs::write(dir.path().join("b.txt"), b"hi").expect("write b");
+
+        let result = peek_dir(dir.path(), 2).expect("peek dir");
+        assert_eq!(result.total, 3);
+        let names: Vec<&str> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["sub", "a.txt"]);
+        assert_eq!(result.entries[0].size, None);
+        assert_eq!(result.entries[1].size, Some(5));
+        assert!(result.entries[1].modified_at.is_some());
+    }
+
+    #[test]
+    fn scan_cache_round_trips_through_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-cache-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let cache_path = dir.path().join("nested").join("scan-cache.json");
+
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.load_cache_from(&cache_path);
+        let modified_at = SystemTime::UNIX_EPOCH;
+        scanner.cache.as_mut().unwrap().entries.insert(
+            PathBuf::from("/tmp/cached"),
+            CacheEntry {
+                size: 42,
+                modified_at,
+                file_count: Some(3),
+                pruned: Vec::new(),
+            },
+        );
+        scanner.save_cache_to(&cache_path).expect("save cache");
+
+        let mut reloaded = Scanner::new().expect("user dirs");
+        reloaded.load_cache_from(&cache_path);
+        let cache = reloaded.cache.expect("cache loaded");
+        assert_eq!(
+            cache.lookup(Path::new("/tmp/cached"), modified_at),
+            Some(42)
+        );
+        assert_eq!(cache.entries[Path::new("/tmp/cached")].file_count, Some(3));
+    }
+
+    #[test]
+    fn scan_directory_cached_reuses_size_until_mtime_changes() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-cache-hit-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        fs::write(dir.path().join("a.txt"), b"hello").expect("write file");
+        let path = dir.path().to_path_buf();
+        let cancel_gen = AtomicU64::new(1);
+
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.cache = Some(ScanCache::default());
+        let modified_at = SystemTime::UNIX_EPOCH;
+        scanner.cache.as_mut().unwrap().entries.insert(
+            path.clone(),
+            CacheEntry {
+                size: 999,
+                modified_at,
+                file_count: None,
+                pruned: Vec::new(),
+            },
+        );
+
+        // 修改时间一致：命中缓存（旧缓存没有文件数）
+        let (size, file_count) =
+            scanner.scan_directory_cached(&path, Some(modified_at), &[], 1, &cancel_gen);
+        assert_eq!(size, 999);
+        assert_eq!(file_count, None);
+
+        // 修改时间变化：重新计算并更新缓存
+        let changed = modified_at + std::time::Duration::from_secs(1);
+        let (size, file_count) =
+            scanner.scan_directory_cached(&path, Some(changed), &[], 1, &cancel_gen);
+        assert_eq!(size, 5);
+        assert_eq!(file_count, Some(1));
+        assert_eq!(scanner.cache.unwrap().lookup(&path, changed), Some(5));
+    }
+
+    #[test]
+    fn format_size_with_binary_and_decimal_units() {
+        assert_eq!(format_size_with(1_048_576, SizeUnits::Binary), "1.0 MiB");
+        assert_eq!(format_size_with(1_048_576, SizeUnits::Decimal), "1.0 MB");
+        assert_eq!(format_size_with(1_500_000, SizeUnits::Decimal), "1.5 MB");
+        assert_eq!(format_size_with(512, SizeUnits::Decimal), "512 B");
+    }
+
+    #[test]
+    fn old_cache_entries_without_file_count_still_load() {
+        let json = r#"{"entries":{"/tmp/old":{"size":7,"modified_at":{"secs_since_epoch":0,"nanos_since_epoch":0}}}}"#;
+        let cache: ScanCache = serde_json::from_str(json).expect("parse old cache");
+        assert_eq!(cache.entries[Path::new("/tmp/old")].file_count, None);
+    }
+
+    #[test]
+    fn named_custom_targets_carry_their_label() {
+        let mut scanner =
+            Scanner::with_extra_targets(vec![PathBuf::from("/tmp")]).expect("user dirs");
+        scanner.set_custom_targets(vec![
+            ("Scratch".to_string(), PathBuf::from("/tmp")),
+            (
+                "Gone".to_string(),
+                PathBuf::from("/tmp/path-does-not-exist"),
+            ),
+        ]);
+        scanner.set_categories(vec![ItemCategory::Custom(None)]);
+
+        // 同时列在 extra_targets 与命名目标中的路径只扫描一次，保留名称
+        let targets = scanner.get_scan_targets();
+        assert_eq!(
+            targets,
+            vec![(
+                ItemCategory::Custom(Some("Scratch".to_string())),
+                PathBuf::from("/tmp")
+            )]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlinks_counts_symlinked_subdirectory() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-symlink-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let scanned = dir.path().join("cache");
+        let storage = dir.path().join("storage");
+        fs::create_dir(&scanned).expect("create cache dir");
+        fs::create_dir(&storage).expect("create storage dir");
+        fs::write(scanned.join("local.bin"), vec![0u8; 5]).expect("write file");
+        fs::write(storage.join("relocated.bin"), vec![0u8; 10]).expect("write file");
+        std::os::unix::fs::symlink(&storage, scanned.join("relocated")).expect("symlink");
+        // 指向自身祖先的循环链接由 WalkDir 检测并跳过
+        std::os::unix::fs::symlink(&scanned, scanned.join("loop")).expect("symlink");
+
+        let mut scanner = Scanner::new().expect("user dirs");
+        assert_eq!(scanner.scan_directory(&scanned), 5);
+        let cancel_gen = AtomicU64::new(0);
+        assert_eq!(calc_dir_stats(&scanned, false, 0, &cancel_gen), (5, 1));
+
+        scanner.set_follow_symlinks(true);
+        assert_eq!(scanner.scan_directory(&scanned), 15);
+        assert_eq!(calc_dir_stats(&scanned, true, 0, &cancel_gen), (15, 2));
+    }
+
+    #[test]
+    fn max_concurrency_runs_size_calculation_in_bounded_pool() {
+        let mut scanner = Scanner::new().expect("user dirs");
+        assert_eq!(
+            scanner.run_parallel(rayon::current_num_threads),
+            rayon::current_num_threads()
+        );
+        scanner.set_max_concurrency(Some(2));
+        assert_eq!(scanner.run_parallel(rayon::current_num_threads), 2);
+
+        let dir = tempfile::Builder::new()
+            .prefix("vac-pool-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        for index in 0..4 {
+            let sub_dir = dir.path().join(format!("dir{index}"));
+            fs::create_dir(&sub_dir).expect("create dir");
+            fs::write(sub_dir.join("data.bin"), vec![0u8; 3]).expect("write file");
+        }
+
+        let (tx, rx) = mpsc::channel();
+        scanner.scan_dir_listing(1, dir.path().to_path_buf(), tx, Arc::new(AtomicU64::new(1)));
+        let sizes = rx
+            .iter()
+            .filter(|msg| matches!(msg, ScanMessage::DirEntrySize { size: 3, .. }))
+            .count();
+        assert_eq!(sizes, 4);
+
+        // 专用线程池下取消同样生效
+        let (tx, rx) = mpsc::channel();
+        scanner.scan_dir_listing(1, dir.path().to_path_buf(), tx, Arc::new(AtomicU64::new(2)));
+        assert!(rx.try_recv().is_err());
+
+        // 0 视为未设置，回退到全局线程池
+        scanner.set_max_concurrency(Some(0));
+        assert!(scanner.thread_pool.is_none());
+    }
+
+    #[test]
+    fn lazy_listing_skips_sizes_until_measured() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-lazy-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let sub_dir = dir.path().join("folder");
+        fs::create_dir(&sub_dir).expect("create dir");
+        fs::write(sub_dir.join("data.bin"), vec![0u8; 9]).expect("write file");
+
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.set_lazy_sizes(true);
+        let (tx, rx) = mpsc::channel();
+        scanner.scan_dir_listing(1, dir.path().to_path_buf(), tx, Arc::new(AtomicU64::new(1)));
+        let messages: Vec<ScanMessage> = rx.iter().collect();
+        assert!(messages.iter().any(|msg| matches!(
+            msg,
+            ScanMessage::DirEntry { entry, .. } if entry.path == sub_dir && entry.size.is_none()
+        )));
+        assert!(
+            !messages
+                .iter()
+                .any(|msg| matches!(msg, ScanMessage::DirEntrySize { .. }))
+        );
+
+        let (tx, rx) = mpsc::channel();
+        scanner.measure_dirs(1, vec![sub_dir.clone()], tx, Arc::new(AtomicU64::new(1)));
+        let messages: Vec<ScanMessage> = rx.iter().collect();
+        assert!(matches!(
+            &messages[..],
+            [ScanMessage::DirEntrySize { path, size: 9, .. }, ScanMessage::Done { .. }] if *path == sub_dir
+        ));
+    }
+
+    #[test]
+    fn scan_disk_collects_entries_with_sizes_without_channels() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-blocking-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let sub_dir = dir.path().join("folder");
+        fs::create_dir(&sub_dir).expect("create dir");
+        fs::write(sub_dir.join("data.bin"), vec![0u8; 9]).expect("write file");
+        fs::write(dir.path().join("top.bin"), vec![0u8; 4]).expect("write file");
+
+        let scanner = Scanner::new().expect("user dirs");
+        let cancel = Arc::new(AtomicU64::new(7));
+        let mut entries = scanner
+            .scan_disk(dir.path().to_path_buf(), &cancel)
+            .expect("scan disk");
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let sizes: Vec<(&str, Option<u64>)> = entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.size))
+            .collect();
+        assert_eq!(sizes, vec![("folder", Some(9)), ("top.bin", Some(4))]);
+
+        let missing = scanner.scan_disk(dir.path().join("missing"), &cancel);
+        assert!(missing.unwrap_err().contains("路径不存在"));
+    }
+
+    #[test]
+    fn aggregate_by_category_sums_sizes_per_category_descending() {
+        let tagged = |category: Option<ItemCategory>, size: Option<u64>| CleanableEntry {
+            kind: EntryKind::Directory,
+            category,
+            path: PathBuf::from("/tmp/item"),
+            name: "item".to_string(),
+            size,
+            modified_at: None,
+            file_count: None,
+        };
+        let entries = vec![
+            tagged(Some(ItemCategory::CargoCache), Some(10)),
+            tagged(None, Some(30)),
+            tagged(Some(ItemCategory::CargoCache), Some(15)),
+            tagged(None, None),
+        ];
+        assert_eq!(
+            aggregate_by_category(&entries),
+            vec![
+                ("其他".to_string(), 30),
+                (ItemCategory::CargoCache.as_str().to_string(), 25),
+            ]
+        );
+
+        // 大小相同时按名称排序
+        let tied = vec![
+            tagged(Some(ItemCategory::NpmCache), Some(5)),
+            tagged(Some(ItemCategory::CargoCache), Some(5)),
+            tagged(Some(ItemCategory::Logs), Some(5)),
+        ];
+        let names: Vec<String> = aggregate_by_category(&tied)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn nested_targets_are_not_counted_twice() {
+        let dir = tempfile::Builder::new()
+            .prefix("vac-nested-targets-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+        let parent = dir.path().join("caches");
+        let child = parent.join("homebrew");
+        fs::create_dir_all(&child).expect("create dirs");
+        fs::write(parent.join("own.bin"), vec![0u8; 10]).expect("write parent file");
+        fs::write(child.join("pkg.bin"), vec![0u8; 100]).expect("write child file");
+
+        let mut scanner = Scanner::new().expect("user dirs");
+        scanner.set_custom_targets(vec![
+            ("Parent".to_string(), parent.clone()),
+            ("Child".to_string(), child.clone()),
+        ]);
+        scanner.set_categories(vec![ItemCategory::Custom(None)]);
+
+        let entries = scanner.scan_root(&Arc::new(AtomicU64::new(1)));
+        let size_of = |path: &Path| {
+            entries
+                .iter()
+                .find(|entry| entry.path == path)
+                .and_then(|entry| entry.size)
+        };
+        assert_eq!(size_of(&parent), Some(10));
+        assert_eq!(size_of(&child), Some(100));
+        assert_eq!(
+            entries.iter().filter_map(|entry| entry.size).sum::<u64>(),
+            110
+        );
+    }
+
+    #[test]
+    fn retention_excess_keeps_newest_children() {
+        let scanner = Scanner::new().expect("user dirs");
+        let dir = tempfile::Builder::new()
+            .prefix("vac-retention-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        for (index, name) in ["old", "mid", "new"].iter().enumerate() {
+            let path = dir.path().join(name);
+            fs::write(&path, vec![0u8; index + 1]).expect("write file");
+            let file = fs::File::options()
+                .write(true)
+                .open(&path)
+                .expect("open file");
+            file.set_modified(base + std::time::Duration::from_secs(index as u64 * 60))
+                .expect("set mtime");
+        }
+        let old_dir = dir.path().join("ancient");
+        fs::create_dir(&old_dir).expect("create dir");
+        fs::write(old_dir.join("log"), b"12345").expect("write nested file");
+        fs::File::open(&old_dir)
+            .and_then(|handle| handle.set_modified(base - std::time::Duration::from_secs(60)))
+            .expect("set dir mtime");
+        fs::create_dir(dir.path().join("emptied")).expect("create empty dir");
+
+        let excess = scanner.retention_excess(dir.path(), 2).expect("list dir");
+        let names: Vec<_> = excess.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["old", "ancient"]);
+        assert_eq!(excess[1].size, Some(5));
+
+        assert!(
+            scanner
+                .retention_excess(dir.path(), 10)
+                .expect("list")
+                .is_empty()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn retention_excess_skips_symlinked_children() {
+        let scanner = Scanner::new().expect("user dirs");
+        let dir = tempfile::Builder::new()
+            .prefix("vac-retention-link-")
+            .tempdir_in("/tmp")
+            .expect("create temp dir");
+
+        let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        for (index, name) in ["r1", "r2"].iter().enumerate() {
+            let release = dir.path().join(name);
+            fs::create_dir(&release).expect("create release");
+            fs::write(release.join("app"), b"app").expect("write file");
+            fs::File::open(&release)
+                .and_then(|handle| {
+                    handle.set_modified(base + std::time::Duration::from_secs(index as u64 * 60))
+                })
+                .expect("set dir mtime");
+        }
+        let current = dir.path().join("current");
+        std::os::unix::fs::symlink(dir.path().join("r2"), &current).expect("create symlink");
+
+        let excess = scanner.retention_excess(dir.path(), 1).expect("list dir");
+        let names: Vec<_> = excess.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["r1"]);
+
+        let result =
+            crate::cleaner::Cleaner::clean(&excess, crate::cleaner::CleanOptions::default());
+        assert!(result.success);
+        assert!(fs::symlink_metadata(&current).is_ok());
+        assert_eq!(fs::read(current.join("app")).expect("read release"), b"app");
+    }
 }
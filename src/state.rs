@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::ScanTarget;
+
+/// 跨会话保存的状态（~/.cache/vac/state.json）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// 上次成功完成的扫描目标
+    #[serde(default)]
+    pub last_scan: Option<ScanTarget>,
+}
+
+impl SessionState {
+    /// 默认状态文件路径（~/.cache/vac/state.json）
+    pub fn default_path() -> Option<PathBuf> {
+        crate::utils::home_dir().map(|home| home.join(".cache").join("vac").join("state.json"))
+    }
+
+    /// 从默认状态文件加载，文件缺失或损坏时返回空状态
+    pub fn load() -> Self {
+        Self::default_path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    /// 从指定文件加载
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 写入默认状态文件
+    pub fn save(&self) -> std::io::Result<()> {
+        match Self::default_path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// 写入指定文件
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// 返回仍然可用的上次扫描目标（记录的路径已不存在时忽略）
+    pub fn valid_last_scan(&self) -> Option<ScanTarget> {
+        self.last_scan
+            .clone()
+            .filter(|target| target.is_available())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_state_round_trips_through_file() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let state_path = dir.path().join("nested").join("state.json");
+        let state = SessionState {
+            last_scan: Some(ScanTarget::Path(dir.path().to_path_buf())),
+        };
+
+        state.save_to(&state_path).expect("save state");
+        assert_eq!(SessionState::load_from(&state_path), state);
+        assert_eq!(
+            state.valid_last_scan(),
+            Some(ScanTarget::Path(dir.path().to_path_buf()))
+        );
+    }
+
+    #[test]
+    fn missing_or_corrupt_state_is_empty() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let state_path = dir.path().join("state.json");
+        assert_eq!(
+            SessionState::load_from(&state_path),
+            SessionState::default()
+        );
+
+        fs::write(&state_path, "{not json").expect("write state");
+        assert_eq!(
+            SessionState::load_from(&state_path),
+            SessionState::default()
+        );
+    }
+
+    #[test]
+    fn valid_last_scan_ignores_removed_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let gone = dir.path().join("gone");
+        let state = SessionState {
+            last_scan: Some(ScanTarget::Path(gone)),
+        };
+        assert_eq!(state.valid_last_scan(), None);
+
+        let preset = SessionState {
+            last_scan: Some(ScanTarget::Preset),
+        };
+        assert_eq!(preset.valid_last_scan(), Some(ScanTarget::Preset));
+    }
+}
@@ -9,11 +9,15 @@ use ratatui::{
     },
 };
 
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::app::{App, EntryKind, Mode, SortOrder};
-use crate::scanner::format_size;
-use crate::utils::format_time;
+use crate::app::{App, CONFIRM_WORD, CleanAction, EntryKind, Mode, SortOrder};
+use crate::cleaner::{CleanError, Cleaner, SECURE_DELETE_PASSES};
+use crate::config::ThemeConfig;
+use crate::scanner::{Scanner, format_size};
+use crate::utils::{format_elapsed, format_eta, format_relative_time, format_time_with};
 
 const DEFAULT_POPUP_WIDTH_PERCENT: u16 = 70;
 const DEFAULT_POPUP_HEIGHT_PERCENT: u16 = 80;
@@ -24,10 +28,18 @@ const STATS_POPUP_HEIGHT_PERCENT: u16 = 70;
 const ERROR_POPUP_WIDTH_PERCENT: u16 = 60;
 const ERROR_POPUP_HEIGHT_PERCENT: u16 = 20;
 const MAX_VISIBLE_COMPLETIONS: usize = 5;
+/// 扫描动画帧
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// 扫描动画每帧持续的毫秒数
+const SPINNER_FRAME_MS: u128 = 100;
 const STATS_BAR_WIDTH: usize = 20;
+const LIST_BAR_WIDTH: usize = 10;
 const POPUP_LIST_RESERVED_LINES: u16 = 11;
+/// 确认弹窗中按类别小计最多显示的类别数，其余合并为一行
+const CONFIRM_CATEGORY_LINES_MAX: usize = 4;
 
 /// UI 颜色主题
+#[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
     pub primary: Color,
     pub secondary: Color,
@@ -58,6 +70,84 @@ impl Default for Theme {
     }
 }
 
+impl Theme {
+    /// 按名称获取预设主题: default / dracula / solarized / mono
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "dracula" => Some(Self {
+                primary: Color::Rgb(0xbd, 0x93, 0xf9),
+                secondary: Color::Rgb(0x8b, 0xe9, 0xfd),
+                accent: Color::Rgb(0xff, 0x79, 0xc6),
+                warning: Color::Rgb(0xf1, 0xfa, 0x8c),
+                danger: Color::Rgb(0xff, 0x55, 0x55),
+                success: Color::Rgb(0x50, 0xfa, 0x7b),
+                text: Color::Rgb(0xf8, 0xf8, 0xf2),
+                text_dim: Color::Rgb(0x62, 0x72, 0xa4),
+                bg: Color::Reset,
+                bg_highlight: Color::Rgb(0x44, 0x47, 0x5a),
+            }),
+            "solarized" => Some(Self {
+                primary: Color::Rgb(0x26, 0x8b, 0xd2),
+                secondary: Color::Rgb(0x2a, 0xa1, 0x98),
+                accent: Color::Rgb(0xd3, 0x36, 0x82),
+                warning: Color::Rgb(0xb5, 0x89, 0x00),
+                danger: Color::Rgb(0xdc, 0x32, 0x2f),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                text: Color::Rgb(0x93, 0xa1, 0xa1),
+                text_dim: Color::Rgb(0x58, 0x6e, 0x75),
+                bg: Color::Reset,
+                bg_highlight: Color::Rgb(0x07, 0x36, 0x42),
+            }),
+            "mono" => Some(Self {
+                primary: Color::White,
+                secondary: Color::Gray,
+                accent: Color::White,
+                warning: Color::White,
+                danger: Color::White,
+                success: Color::Gray,
+                text: Color::White,
+                text_dim: Color::DarkGray,
+                bg: Color::Reset,
+                bg_highlight: Color::DarkGray,
+            }),
+            _ => None,
+        }
+    }
+
+    /// 根据配置构建主题：未知预设回退到默认主题，无法解析的颜色保留预设值
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = config
+            .preset
+            .as_deref()
+            .and_then(Self::preset)
+            .unwrap_or_default();
+        let overrides = [
+            (&mut theme.primary, &config.primary),
+            (&mut theme.secondary, &config.secondary),
+            (&mut theme.accent, &config.accent),
+            (&mut theme.warning, &config.warning),
+            (&mut theme.danger, &config.danger),
+            (&mut theme.success, &config.success),
+            (&mut theme.text, &config.text),
+            (&mut theme.text_dim, &config.text_dim),
+            (&mut theme.bg, &config.bg),
+            (&mut theme.bg_highlight, &config.bg_highlight),
+        ];
+        for (color, raw) in overrides {
+            if let Some(parsed) = raw.as_deref().and_then(parse_color) {
+                *color = parsed;
+            }
+        }
+        theme
+    }
+}
+
+/// 解析颜色名（如 "cyan"、"light-blue"）、调色板索引（0-255）或十六进制（"#rrggbb"）
+fn parse_color(raw: &str) -> Option<Color> {
+    raw.trim().parse().ok()
+}
+
 fn styled_block<'a>(
     title: Option<&'a str>,
     border_type: BorderType,
@@ -89,7 +179,7 @@ fn path_short_name(path: &std::path::Path) -> String {
 
 /// 渲染整个 UI
 pub fn render(frame: &mut Frame, app: &mut App) {
-    let theme = Theme::default();
+    let theme = Theme::from_config(&app.theme_config);
 
     let [header_area, main_area, footer_area] = Layout::vertical([
         Constraint::Length(3),
@@ -104,11 +194,16 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // 渲染覆盖层
     match app.mode {
-        Mode::Help => render_help_popup(frame, &theme),
+        Mode::Help => render_help_popup(frame, app, &theme),
         Mode::Confirm => render_confirm_popup(frame, app, &theme),
         Mode::InputPath => render_input_popup(frame, app, &theme),
         Mode::Search => render_search_bar(frame, app, &theme),
         Mode::Stats => render_stats_popup(frame, app, &theme),
+        Mode::Peek => render_peek_popup(frame, app, &theme),
+        Mode::ConfirmEmptyTrash => render_empty_trash_popup(frame, app, &theme),
+        Mode::Bookmarks => render_bookmarks_popup(frame, app, &theme),
+        Mode::ConfirmQuit => render_quit_popup(frame, app, &theme),
+        Mode::Settings => render_settings_popup(frame, app, &theme),
         _ => {}
     }
 
@@ -125,8 +220,45 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         Span::styled("- macOS 磁盘清理工具", Style::default().fg(theme.text_dim)),
     ];
 
+    let filter = app
+        .active_filter
+        .as_ref()
+        .map(|filter| {
+            format!(
+                "过滤: {} ({}/{}) | ",
+                filter.query,
+                app.entries.len(),
+                filter.total
+            )
+        })
+        .unwrap_or_default();
+    let lazy = if app.lazy_sizes {
+        "快速列表 | "
+    } else {
+        ""
+    };
+    let disk = app
+        .disk_usage
+        .map(|(total, available)| {
+            format!(
+                "磁盘: {} 可用 / {} | ",
+                format_size(available),
+                format_size(total)
+            )
+        })
+        .unwrap_or_default();
+    let trash = app
+        .trash_usage
+        .map(|size| format!("回收站: {} | ", format_size(size)))
+        .unwrap_or_default();
     let stats = format!(
-        "路径: {} | 总计: {} ({} 项) | 已选: {} ({} 项)",
+        "{}{}{}{}排序: {} {} | 路径: {} | 总计: {} ({} 项) | 已选: {} ({} 项)",
+        filter,
+        lazy,
+        disk,
+        trash,
+        app.sort_order.as_str(),
+        app.sort_order.arrow(),
         app.breadcrumb(),
         format_size(app.total_size),
         app.entries.len(),
@@ -148,10 +280,120 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
 fn render_main(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     match app.mode {
         Mode::Scanning => render_scanning(frame, area, app, theme),
+        Mode::Cleaning => render_cleaning(frame, area, app, theme),
+        _ if app.show_details => {
+            let [list_area, detail_area] = Layout::horizontal([
+                Constraint::Percentage(100 - app.detail_percent),
+                Constraint::Percentage(app.detail_percent),
+            ])
+            .areas(area);
+            render_list(frame, list_area, app, theme);
+            render_details(frame, detail_area, app, theme);
+        }
         _ => render_list(frame, area, app, theme),
     }
 }
 
+/// 渲染右侧详情面板（高亮条目的完整路径、类别、大小、时间、内容统计与安全状态）
+fn render_details(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let block = styled_block(Some(" 详情 "), BorderType::Rounded, theme.secondary)
+        .padding(Padding::horizontal(1));
+    let Some(entry) = app.current_entry() else {
+        let empty = Paragraph::new(Span::styled(
+            "没有高亮的条目",
+            Style::default().fg(theme.text_dim),
+        ))
+        .block(block);
+        frame.render_widget(empty, area);
+        return;
+    };
+
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(theme.text_dim));
+    let value = |text: String| Span::styled(text, Style::default().fg(theme.text));
+    let kind = match entry.kind {
+        EntryKind::Directory => "目录",
+        EntryKind::File => "文件",
+    };
+    let contents = match entry.kind {
+        EntryKind::File => "—".to_string(),
+        EntryKind::Directory => match &app.detail_counts {
+            Some((path, files, dirs)) if *path == entry.path => {
+                format!("{} 个文件 / {} 个子目录", files, dirs)
+            }
+            _ => "统计中…".to_string(),
+        },
+    };
+    let (safety, safety_color) = if app.safety_rules.is_own_data(&entry.path) {
+        ("已阻止（VAC 自身数据）", theme.danger)
+    } else if Cleaner::is_safe_to_delete(&entry.path, &app.safety_rules) {
+        ("可清理", theme.success)
+    } else {
+        ("已阻止（安全规则）", theme.danger)
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(
+            entry.name.clone(),
+            Style::default().fg(theme.primary).bold(),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            label("路径: "),
+            value(entry.path.display().to_string()),
+        ]),
+        Line::from(vec![label("类型: "), value(kind.to_string())]),
+        Line::from(vec![
+            label("类别: "),
+            value(
+                entry
+                    .category
+                    .as_ref()
+                    .map(|category| category.as_str().to_string())
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+        ]),
+        Line::from(vec![
+            label("大小: "),
+            value(
+                entry
+                    .size
+                    .map(format_size)
+                    .unwrap_or_else(|| "…".to_string()),
+            ),
+        ]),
+        Line::from(vec![
+            label("修改: "),
+            value(
+                entry
+                    .modified_at
+                    .as_ref()
+                    .map(|time| format_time_with(time, app.date_format.as_deref(), true))
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+        ]),
+        Line::from(vec![label("内容: "), value(contents)]),
+        Line::from(vec![
+            label("安全: "),
+            Span::styled(safety, Style::default().fg(safety_color)),
+        ]),
+        Line::from(vec![
+            label("选中: "),
+            value(
+                if app.is_selected(&entry.path) {
+                    "是"
+                } else {
+                    "否"
+                }
+                .to_string(),
+            ),
+        ]),
+    ];
+    let details = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
+    frame.render_widget(details, area);
+}
+
 /// 渲染扫描进度
 fn render_scanning(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let [_, center, _] = Layout::vertical([
@@ -168,18 +410,32 @@ fn render_scanning(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     ])
     .areas(center);
 
+    let elapsed = app
+        .scan_started_at
+        .map(|started_at| started_at.elapsed())
+        .unwrap_or_default();
+    let title = format!(
+        " {} 扫描中... {} ",
+        spinner_frame(elapsed),
+        format_elapsed(elapsed)
+    );
+    let eta = app
+        .scan_eta_remaining(Instant::now())
+        .map(|remaining| format!("约剩 {}", format_eta(remaining)))
+        .unwrap_or_else(|| "估算中…".to_string());
     let gauge = Gauge::default()
         .block(styled_block(
-            Some(" 扫描中... "),
+            Some(&title),
             BorderType::Rounded,
             theme.primary,
         ))
         .gauge_style(Style::default().fg(theme.accent).bg(theme.bg_highlight))
         .percent(app.scan_progress as u16)
         .label(format!(
-            "{}% | 已发现: {}",
+            "{}% | 已发现: {} | {}",
             app.scan_progress,
-            format_size(app.total_size)
+            format_size(app.total_size),
+            eta
         ));
 
     frame.render_widget(gauge, gauge_area);
@@ -192,10 +448,62 @@ fn render_scanning(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     frame.render_widget(path_text, path_area);
 }
 
+/// 按已用时间选择动画帧，使动画速度与刷新频率无关
+fn spinner_frame(elapsed: Duration) -> &'static str {
+    let index = (elapsed.as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_FRAMES.len();
+    SPINNER_FRAMES[index]
+}
+
+/// 渲染清理进度
+fn render_cleaning(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let [_, center, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(5),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let [_, gauge_area, _] = Layout::horizontal([
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+    ])
+    .areas(center);
+
+    let title = match app.confirm_clean_action() {
+        _ if app.emptying_trash => " 清空回收站中... ",
+        CleanAction::SecureDelete => " 安全擦除中... ",
+        CleanAction::Trash => " 移至回收站中... ",
+        CleanAction::Delete => " 删除中... ",
+        CleanAction::Mixed { .. } => " 清理中... ",
+    };
+    let gauge = Gauge::default()
+        .block(styled_block(Some(title), BorderType::Rounded, theme.danger))
+        .gauge_style(Style::default().fg(theme.accent).bg(theme.bg_highlight))
+        .percent(app.clean_percent())
+        .label(format!(
+            "已处理 {}/{} ({}/{})",
+            app.clean_completed,
+            app.clean_total,
+            format_size(app.clean_done_bytes),
+            format_size(app.clean_total_bytes)
+        ));
+
+    frame.render_widget(gauge, gauge_area);
+
+    // 显示最近处理完成的路径
+    let path_area = Rect::new(gauge_area.x, gauge_area.y + 5, gauge_area.width, 1);
+    let path_text = Paragraph::new(app.current_clean_path.clone())
+        .style(Style::default().fg(theme.text_dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(path_text, path_area);
+}
+
 /// 渲染可清理项目列表
 fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
-    // 更新可视区域高度（减去边框 2 行）
+    // 更新可视区域高度（减去边框 2 行）与列表位置
     app.visible_height = area.height.saturating_sub(2) as usize;
+    app.list_area = area;
     if app.entries.is_empty() {
         let content = if app.scan_in_progress {
             vec![
@@ -206,7 +514,7 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
                 )),
             ]
         } else {
-            vec![
+            let mut lines = vec![
                 Line::from(""),
                 Line::from(Span::styled(
                     "VAC - macOS 磁盘清理工具",
@@ -225,6 +533,17 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
                     Span::styled("  d  ", Style::default().fg(theme.accent).bold()),
                     Span::styled("输入自定义路径扫描", Style::default().fg(theme.text)),
                 ]),
+            ];
+            if let Some(target) = &app.last_scan {
+                lines.push(Line::from(vec![
+                    Span::styled("  r  ", Style::default().fg(theme.accent).bold()),
+                    Span::styled(
+                        format!("重新扫描上次目标: {}", target.label()),
+                        Style::default().fg(theme.text),
+                    ),
+                ]));
+            }
+            lines.extend([
                 Line::from(vec![
                     Span::styled("  ?  ", Style::default().fg(theme.accent).bold()),
                     Span::styled("查看完整帮助", Style::default().fg(theme.text)),
@@ -234,7 +553,8 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
                     "提示: 清理前请先备份重要数据",
                     Style::default().fg(theme.warning),
                 )),
-            ]
+            ]);
+            lines
         };
         let empty_text = Paragraph::new(content)
             .alignment(Alignment::Center)
@@ -247,24 +567,45 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
         return;
     }
 
+    let anchor_range = app.anchor_range();
+    let highlighter = app.search_highlighter();
+    let max_size = app.entries.iter().filter_map(|e| e.size).max().unwrap_or(0);
+    let now = SystemTime::now();
     let items: Vec<ListItem> = app
         .entries
         .iter()
-        .map(|entry| {
+        .enumerate()
+        .map(|(index, entry)| {
             let selected = app.is_selected(&entry.path);
+            let in_anchor_range =
+                anchor_range.is_some_and(|(start, end)| (start..=end).contains(&index));
             let checkbox = if selected { "[✓]" } else { "[ ]" };
             let size = entry
                 .size
                 .map(format_size)
                 .unwrap_or_else(|| "…".to_string());
-            let name = match entry.kind {
+            let mut name = match entry.kind {
                 EntryKind::Directory => format!("{}/", entry.name),
                 EntryKind::File => entry.name.clone(),
             };
+            if app.tree_mode {
+                let marker = match entry.kind {
+                    EntryKind::Directory if app.expanded.contains(&entry.path) => "▾ ",
+                    EntryKind::Directory => "▸ ",
+                    EntryKind::File => "  ",
+                };
+                name = format!("{}{}{}", "  ".repeat(app.entry_depth(index)), marker, name);
+            }
             let time_str = entry
                 .modified_at
                 .as_ref()
-                .map(|time| format_time(time, false))
+                .map(|time| {
+                    if app.relative_time {
+                        format_relative_time(time, now)
+                    } else {
+                        format_time_with(time, app.date_format.as_deref(), false)
+                    }
+                })
                 .unwrap_or_default();
             let mut spans = vec![
                 Span::styled(
@@ -276,10 +617,56 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
                     }),
                 ),
                 Span::raw(" "),
-                Span::styled(name, Style::default().fg(theme.text)),
+            ];
+            if app.show_bars {
+                spans.push(Span::styled(
+                    size_bar(entry.size, max_size, LIST_BAR_WIDTH),
+                    Style::default().fg(theme.accent),
+                ));
+                spans.push(Span::raw(" "));
+            }
+            let name_style = Style::default().fg(if in_anchor_range {
+                theme.accent
+            } else {
+                theme.text
+            });
+            // 跳转搜索时高亮名称中的匹配部分（名称前可能有树形缩进，目录名后有 `/`）
+            let name_start =
+                name.len() - entry.name.len() - usize::from(entry.kind == EntryKind::Directory);
+            match highlighter
+                .as_ref()
+                .and_then(|matcher| matcher.find(&entry.name))
+            {
+                Some(range) => {
+                    let (start, end) = (name_start + range.start, name_start + range.end);
+                    spans.extend([
+                        Span::styled(name[..start].to_string(), name_style),
+                        Span::styled(
+                            name[start..end].to_string(),
+                            name_style
+                                .fg(theme.accent)
+                                .add_modifier(Modifier::UNDERLINED),
+                        ),
+                        Span::styled(name[end..].to_string(), name_style),
+                    ]);
+                }
+                None => spans.push(Span::styled(name, name_style)),
+            }
+            spans.extend([
                 Span::raw(" "),
                 Span::styled(format!("({})", size), Style::default().fg(theme.warning)),
-            ];
+            ]);
+            if app.hidden.contains(&entry.path) {
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled("已隐藏", Style::default().fg(theme.text_dim)));
+            }
+            if app.is_recomputing(&entry.path) {
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(
+                    "重新计算中…",
+                    Style::default().fg(theme.accent),
+                ));
+            }
             if !time_str.is_empty() {
                 spans.push(Span::raw(" "));
                 spans.push(Span::styled(time_str, Style::default().fg(theme.text_dim)));
@@ -288,17 +675,20 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
         })
         .collect();
 
+    let block = styled_block(Some(" 可清理项目 "), BorderType::Rounded, theme.secondary)
+        .padding(Padding::horizontal(1));
+    let highlight_symbol = "▶ ";
+    // 复选框紧跟在边框、内边距与高亮符号之后，记录其列偏移供鼠标点击定位
+    app.list_checkbox_column =
+        block.inner(area).x - area.x + Span::raw(highlight_symbol).width() as u16;
     let list = List::new(items)
-        .block(
-            styled_block(Some(" 可清理项目 "), BorderType::Rounded, theme.secondary)
-                .padding(Padding::horizontal(1)),
-        )
+        .block(block)
         .highlight_style(
             Style::default()
                 .bg(theme.bg_highlight)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol("▶ ");
+        .highlight_symbol(highlight_symbol);
 
     frame.render_stateful_widget(list, area, &mut app.list_state);
 
@@ -311,6 +701,20 @@ fn render_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     }
 }
 
+/// 生成固定宽度的相对大小条：按 `size / max_size` 填充 `█`，其余用 `░` 补齐
+///
+/// 非零大小至少显示一格；大小未知或最大值为 0 时全部为空格子。
+fn size_bar(size: Option<u64>, max_size: u64, width: usize) -> String {
+    let filled = match size {
+        Some(size) if max_size > 0 => {
+            let scaled = (u128::from(size) * width as u128).div_ceil(u128::from(max_size));
+            (scaled as usize).min(width)
+        }
+        _ => 0,
+    };
+    "█".repeat(filled) + &"░".repeat(width - filled)
+}
+
 /// 渲染底部状态栏
 fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let sort_indicator = match app.sort_order {
@@ -319,20 +723,32 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         SortOrder::ByTime => "[排序:时间]",
     };
 
+    let floor_indicator = if app.size_floor > 0 {
+        format!(" [≥{}]", format_size(app.size_floor))
+    } else {
+        String::new()
+    };
+
     let base_help = format!(
-        "s: 扫描 | S: 扫描主目录 | d: 自定义路径 | o: 排序 {} | t: 统计 | Space: 选择 | c: 清理 | ?: 帮助 | q: 退出",
-        sort_indicator
+        "s: 扫描 | S: 扫描主目录 | d: 自定义路径 | o: 排序 {}{} | t: 统计 | Space: 选择 | c: 清理 | ?: 帮助 | q: 退出",
+        sort_indicator, floor_indicator
     );
 
     let help_text = match app.mode {
         Mode::Normal => {
-            if let Some((freed, count)) = app.last_clean_result {
+            if let Some(count) = app.pending_count {
+                format!("{} | g/G: 跳到第 {} 项 | 其他键取消", count, count)
+            } else if let Some(ref notice) = app.notice {
+                format!("{} | {}", notice, base_help)
+            } else if let Some((freed, count)) = app.last_clean_result {
                 format!(
                     "已释放 {} ({} 个项目) | {}",
                     format_size(freed),
                     count,
                     base_help
                 )
+            } else if app.select_anchor.is_some() {
+                "可视选择: j/k 扩展范围 | Space/Enter: 切换范围选中 | Esc: 取消".to_string()
             } else if app.scan_in_progress {
                 format!("{} | 扫描中...", base_help)
             } else {
@@ -340,17 +756,34 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             }
         }
         Mode::Scanning => "扫描中，请稍候... | Esc: 取消".to_string(),
+        Mode::Cleaning if app.clean_cancel_requested => {
+            "正在取消，当前条目处理完后停止...".to_string()
+        }
+        Mode::Cleaning => format!(
+            "清理中 {}/{}... | Esc: 取消",
+            app.clean_completed, app.clean_total
+        ),
         Mode::Confirm => {
-            if app.use_trash {
-                "Enter: 确认移至回收站 | d: 详情预览 | Esc: 取消".to_string()
-            } else {
-                "Enter: 确认删除 | d: 详情预览 | Esc: 取消".to_string()
-            }
+            let action = match app.confirm_clean_action() {
+                CleanAction::SecureDelete => "安全擦除",
+                CleanAction::Trash => "移至回收站",
+                CleanAction::Delete => "删除",
+                CleanAction::Mixed { .. } => "清理",
+            };
+            format!("Enter: 确认{} | d: 详情预览 | Esc: 取消", action)
         }
         Mode::Help => "按任意键关闭帮助".to_string(),
         Mode::Stats => "按任意键关闭统计".to_string(),
+        Mode::Peek => "按任意键关闭预览".to_string(),
+        Mode::Bookmarks => "j/k: 移动 | Enter: 扫描书签 | Esc: 关闭".to_string(),
+        Mode::ConfirmEmptyTrash => "Enter: 确认清空回收站 | Esc: 取消".to_string(),
+        Mode::ConfirmQuit => "y: 退出 | 其他键: 取消".to_string(),
+        Mode::Settings => "j/k: 移动 | Space: 启用/禁用 | Esc: 关闭（退出时保存）".to_string(),
         Mode::InputPath => "输入路径后按 Enter 确认 | Tab: 补全 | Esc: 取消".to_string(),
-        Mode::Search => "Enter: 确认搜索 | Esc: 取消搜索".to_string(),
+        Mode::Search => {
+            "Enter: 确认搜索 | ↑/↓: 历史 | Ctrl+R: 切换正则 | Ctrl+F: 过滤/跳转 | Ctrl+A: 选中匹配项 | Esc: 取消搜索"
+                .to_string()
+        }
     };
 
     let footer = Paragraph::new(help_text)
@@ -362,7 +795,7 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
 }
 
 /// 渲染帮助弹窗
-fn render_help_popup(frame: &mut Frame, theme: &Theme) {
+fn render_help_popup(frame: &mut Frame, app: &mut App, theme: &Theme) {
     let area = centered_rect(
         DEFAULT_POPUP_WIDTH_PERCENT,
         DEFAULT_POPUP_HEIGHT_PERCENT,
@@ -383,6 +816,12 @@ fn render_help_popup(frame: &mut Frame, theme: &Theme) {
         help_line("  s          ", "扫描预设可清理目录", theme),
         help_line("  S          ", "扫描用户主目录", theme),
         help_line("  d          ", "输入自定义路径扫描", theme),
+        help_line("  +          ", "扫描路径并追加到当前结果", theme),
+        help_line("  r          ", "重新扫描上次的扫描目标", theme),
+        help_line("  b          ", "打开书签列表（配置 ui.bookmarks）", theme),
+        help_line("  ,          ", "设置预设扫描包含的类别", theme),
+        help_line("  D          ", "显示/隐藏右侧详情面板", theme),
+        help_line("  < / >      ", "收窄/加宽详情面板", theme),
         Line::from(""),
         Line::from(Span::styled(
             "浏览与排序",
@@ -394,23 +833,54 @@ fn render_help_popup(frame: &mut Frame, theme: &Theme) {
         help_line("  ↑/k        ", "向上移动", theme),
         help_line("  ↓/j        ", "向下移动", theme),
         help_line("  g/G        ", "跳到顶部/底部", theme),
+        help_line("  数字+g/G   ", "跳到第 N 项（如 42G）", theme),
         help_line("  Ctrl+d/u   ", "向下/上翻半页", theme),
         help_line("  PgDn/PgUp  ", "向下/上翻半页", theme),
         help_line("  /          ", "搜索/过滤列表", theme),
         help_line("  o          ", "切换排序方式 (名称/大小/时间)", theme),
+        help_line("  T          ", "切换树形视图（Enter 原位展开目录）", theme),
+        help_line("  F          ", "切换快速列表（子目录大小按需计算）", theme),
+        help_line("  R          ", "重新计算高亮目录的大小", theme),
+        help_line(
+            "  m          ",
+            "切换最小显示大小（关/1/10/100 MiB）",
+            theme,
+        ),
+        help_line("  h          ", "本次会话中隐藏当前项", theme),
+        help_line("  H          ", "显示/不显示已隐藏的条目", theme),
         Line::from(""),
         Line::from(Span::styled(
             "选择与清理",
             Style::default().fg(theme.secondary).bold(),
         )),
         help_line("  Space      ", "选择/取消选择当前项", theme),
+        help_line(
+            "  v          ",
+            "设置范围锚点（Space/Enter 选中范围）",
+            theme,
+        ),
         help_line("  a          ", "全选/取消全选", theme),
+        help_line("  A          ", "选中所有匹配当前搜索的条目", theme),
+        help_line("  i          ", "反选当前视图", theme),
+        help_line("  n/N        ", "跳到下一个/上一个已选中项", theme),
         help_line("  c          ", "执行清理", theme),
+        help_line("  x          ", "仅清理当前项", theme),
+        help_line("  u          ", "撤销上次移至回收站的清理", theme),
+        help_line("  e          ", "列出回收站内容（清空前查看）", theme),
+        help_line("  E          ", "清空回收站（永久删除）", theme),
         Line::from(""),
         Line::from(Span::styled(
             "其他",
             Style::default().fg(theme.secondary).bold(),
         )),
+        help_line("  p          ", "预览当前目录内容", theme),
+        help_line("  K          ", "保留最新 5 个子项，选中其余", theme),
+        help_line("  y          ", "复制当前项的绝对路径到剪贴板", theme),
+        help_line(
+            "  O          ",
+            "在 Finder 中显示当前项（文件上按 Enter 同效）",
+            theme,
+        ),
         help_line("  t          ", "空间占用统计", theme),
         help_line("  ?          ", "显示/隐藏帮助", theme),
         help_line("  q          ", "退出程序", theme),
@@ -419,18 +889,56 @@ fn render_help_popup(frame: &mut Frame, theme: &Theme) {
             "注意: 清理操作不可逆，请谨慎操作！",
             Style::default().fg(theme.warning),
         )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "j/k、PgDn/PgUp 滚动，其他任意键关闭",
+            Style::default().fg(theme.text_dim),
+        )),
     ];
 
+    // 边框与上下内边距共占 4 行
+    let visible_height = area.height.saturating_sub(4) as usize;
+    let max_scroll = help_content.len().saturating_sub(visible_height);
+    app.help_scroll = app.help_scroll.min(max_scroll);
+
+    let mut block = styled_block(Some(" 帮助 "), BorderType::Double, theme.primary)
+        .padding(Padding::uniform(1));
+    if let Some(indicator) = scroll_indicator(app.help_scroll, max_scroll) {
+        block = block.title_bottom(
+            Line::from(Span::styled(indicator, Style::default().fg(theme.text_dim)))
+                .right_aligned(),
+        );
+    }
+
     let help = Paragraph::new(help_content)
-        .block(
-            styled_block(Some(" 帮助 "), BorderType::Double, theme.primary)
-                .padding(Padding::uniform(1)),
-        )
-        .wrap(Wrap { trim: true });
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((app.help_scroll as u16, 0));
 
     frame.render_widget(help, area);
 }
 
+/// 内容溢出时的滚动提示：下方还有内容显示“更多 ↓”，已滚动时显示“↑”
+fn scroll_indicator(scroll: usize, max_scroll: usize) -> Option<&'static str> {
+    match (scroll > 0, scroll < max_scroll) {
+        (false, false) => None,
+        (false, true) => Some(" 更多 ↓ "),
+        (true, true) => Some(" ↑ 更多 ↓ "),
+        (true, false) => Some(" ↑ "),
+    }
+}
+
+/// 计算补全候选的可见窗口 `[start, end)`，保证选中项始终可见
+fn completion_window(selected: usize, len: usize, max_visible: usize) -> (usize, usize) {
+    if len <= max_visible {
+        return (0, len);
+    }
+    let start = (selected + 1)
+        .saturating_sub(max_visible)
+        .min(len - max_visible);
+    (start, start + max_visible)
+}
+
 /// 渲染路径输入弹窗
 fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     // 动态计算弹窗高度：基础行数 + 候选列表行数
@@ -441,7 +949,7 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     // 候选列表: 空行(1) + 候选项(N) + 可能的省略提示(1)
     let extra_lines = if has_completions {
         1 + completion_count
-            + if app.tab_completions.len() > MAX_VISIBLE_COMPLETIONS {
+            + if app.tab_completions.len() + app.tab_completion_hidden > MAX_VISIBLE_COMPLETIONS {
                 1
             } else {
                 0
@@ -460,7 +968,7 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
 
     let input_display = if app.input_buffer.is_empty() {
         Span::styled(
-            "输入路径 (支持 ~ 表示主目录)",
+            "输入路径 (支持 ~ 与 $VAR)",
             Style::default().fg(theme.text_dim),
         )
     } else {
@@ -469,11 +977,19 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
 
     let mut content = vec![
         Line::from(Span::styled(
-            "磁盘扫描",
+            if app.input_appends {
+                "追加扫描"
+            } else {
+                "磁盘扫描"
+            },
             Style::default().fg(theme.primary).bold(),
         )),
         Line::from(""),
-        Line::from("请输入要扫描的目录路径:"),
+        Line::from(if app.input_appends {
+            "请输入要追加到当前结果的目录路径:"
+        } else {
+            "请输入要扫描的目录路径:"
+        }),
         Line::from(""),
         Line::from(vec![
             Span::raw("> "),
@@ -486,12 +1002,12 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     if has_completions {
         content.push(Line::from(""));
         let current_index = app.tab_completion_index.unwrap_or(0);
-        for (i, completion) in app
-            .tab_completions
-            .iter()
-            .enumerate()
-            .take(MAX_VISIBLE_COMPLETIONS)
-        {
+        let (start, end) = completion_window(
+            current_index,
+            app.tab_completions.len(),
+            MAX_VISIBLE_COMPLETIONS,
+        );
+        for (i, completion) in app.tab_completions.iter().enumerate().take(end).skip(start) {
             let is_selected = i == current_index;
             if is_selected {
                 content.push(Line::from(vec![
@@ -508,9 +1024,16 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
                 ]));
             }
         }
-        if app.tab_completions.len() > MAX_VISIBLE_COMPLETIONS {
+        let total = app.tab_completions.len() + app.tab_completion_hidden;
+        if total > MAX_VISIBLE_COMPLETIONS {
+            let remaining = total - end;
+            let summary = if remaining > 0 {
+                format!("    … 还有 {} 项（共 {} 项）", remaining, total)
+            } else {
+                format!("    … 共 {} 项", total)
+            };
             content.push(Line::from(Span::styled(
-                format!("    ... 共 {} 项", app.tab_completions.len()),
+                summary,
                 Style::default().fg(theme.text_dim),
             )));
         }
@@ -537,7 +1060,7 @@ fn render_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
 }
 
 /// 渲染确认删除弹窗（可滚动预览列表）
-fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+fn render_confirm_popup(frame: &mut Frame, app: &mut App, theme: &Theme) {
     let area = centered_rect(
         CONFIRM_POPUP_WIDTH_PERCENT,
         CONFIRM_POPUP_HEIGHT_PERCENT,
@@ -550,21 +1073,41 @@ fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         return;
     }
 
-    let selected_count = app.selections.len();
-
     // 收集待删路径，按大小降序
     let mut items: Vec<(PathBuf, u64)> = app
-        .selections
-        .iter()
-        .map(|(path, entry)| (path.clone(), entry.size.unwrap_or(0)))
+        .confirm_items()
+        .into_iter()
+        .map(|entry| (entry.path, entry.size.unwrap_or(0)))
         .collect();
-    items.sort_by(|a, b| b.1.cmp(&a.1));
+    let selected_count = items.len();
+    items.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    let mut summary_spans = vec![Span::raw(format!(
+        "共 {} 个项目 | 释放空间: {}",
+        selected_count,
+        format_size(
+            app.confirm_size()
+                .saturating_sub(app.confirm_blocked_size())
+        )
+    ))];
+    if !app.confirm_blocked.is_empty() {
+        summary_spans.push(Span::styled(
+            format!(
+                " | ⚠ {} 项未通过安全检查，将跳过",
+                app.confirm_blocked.len()
+            ),
+            Style::default().fg(theme.danger).bold(),
+        ));
+    }
+    let summary_line = Line::from(summary_spans);
 
     // 头部信息行
-    let action_title = if app.use_trash {
-        "⚠ 确认移至回收站"
-    } else {
-        "⚠ 确认删除"
+    let clean_action = app.confirm_clean_action();
+    let action_title = match clean_action {
+        CleanAction::SecureDelete => "⚠ 确认安全擦除",
+        CleanAction::Trash => "⚠ 确认移至回收站",
+        CleanAction::Delete => "⚠ 确认删除",
+        CleanAction::Mixed { .. } => "⚠ 确认清理",
     };
     let mut lines = vec![
         Line::from(Span::styled(
@@ -572,31 +1115,62 @@ fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
             Style::default().fg(theme.warning).bold(),
         )),
         Line::from(""),
-        Line::from(format!(
-            "共 {} 个项目 | 释放空间: {}",
-            selected_count,
-            format_size(app.selected_size)
-        )),
-        Line::from(""),
+        summary_line,
     ];
+    let category_lines = confirm_category_lines(app, theme);
+    let category_line_count = category_lines.len() as u16;
+    lines.extend(category_lines);
+    lines.push(Line::from(""));
 
-    // 可视列表区高度 = popup 总高 - 边框(2) - padding(2) - 头(4) - 尾(3)
-    let visible_height = area.height.saturating_sub(POPUP_LIST_RESERVED_LINES) as usize;
-    let scroll = app
-        .confirm_scroll
-        .min(items.len().saturating_sub(visible_height));
+    // 可视列表区高度 = popup 总高 - 边框(2) - padding(2) - 头(4) - 尾(3)，再减去类别小计与确认词输入行
+    let visible_height = area
+        .height
+        .saturating_sub(POPUP_LIST_RESERVED_LINES + category_line_count + confirm_word_lines(app))
+        as usize;
+    app.confirm_visible_height = visible_height;
+    app.clamp_confirm_scroll(items.len(), visible_height);
+    let scroll = app.confirm_scroll;
+    // 按类别部分移至回收站时，标出将移至回收站的条目
+    let trash_paths: HashSet<PathBuf> = if matches!(clean_action, CleanAction::Mixed { .. }) {
+        let policy = app.trash_policy();
+        app.confirm_cleanable_items()
+            .into_iter()
+            .filter(|item| policy.should_trash(item))
+            .map(|item| item.path)
+            .collect()
+    } else {
+        HashSet::new()
+    };
 
     for (path, size) in items.iter().skip(scroll).take(visible_height) {
         let name = path_short_name(path);
-        lines.push(Line::from(vec![
+        let blocked = app.confirm_blocked.contains(path);
+        let name_color = if blocked { theme.danger } else { theme.text };
+        let mut spans = vec![
             Span::styled("  • ", Style::default().fg(theme.text_dim)),
-            Span::styled(name, Style::default().fg(theme.text)),
+            Span::styled(name, Style::default().fg(name_color)),
             Span::raw("  "),
             Span::styled(
                 format!("({})", format_size(*size)),
                 Style::default().fg(theme.warning),
             ),
-        ]));
+        ];
+        if blocked {
+            spans.push(Span::styled(
+                if app.safety_rules.is_own_data(path) {
+                    "  ⚠ VAC 自身数据"
+                } else {
+                    "  ⚠ 已阻止"
+                },
+                Style::default().fg(theme.danger).bold(),
+            ));
+        } else if trash_paths.contains(path) {
+            spans.push(Span::styled(
+                "  ↺ 回收站",
+                Style::default().fg(theme.success),
+            ));
+        }
+        lines.push(Line::from(spans));
     }
 
     if items.len() > visible_height {
@@ -607,12 +1181,23 @@ fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     }
 
     lines.push(Line::from(""));
-    let warning_text = if app.use_trash {
-        "文件将移至系统回收站，可从回收站恢复"
-    } else {
-        "此操作不可逆！"
+    let secure_warning = format!(
+        "安全擦除：每个文件覆写 {} 遍后删除，速度较慢且不可逆！",
+        SECURE_DELETE_PASSES
+    );
+    let warning_text = match clean_action {
+        CleanAction::SecureDelete => secure_warning,
+        CleanAction::Trash if app.force_trash => {
+            "安全模式: 仅移至回收站，永久删除不可用".to_string()
+        }
+        CleanAction::Trash => "文件将移至系统回收站，可从回收站恢复".to_string(),
+        CleanAction::Delete => "此操作不可逆！".to_string(),
+        CleanAction::Mixed { trashed } => format!(
+            "{} 项按类别规则移至回收站，其余将永久删除，不可逆！",
+            trashed
+        ),
     };
-    let warning_color = if app.use_trash {
+    let warning_color = if clean_action == CleanAction::Trash {
         theme.warning
     } else {
         theme.danger
@@ -621,14 +1206,22 @@ fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         warning_text,
         Style::default().fg(warning_color),
     )));
+    lines.extend(confirm_word_line(app, theme));
+    let typing = app.confirm_word_input.is_some();
     lines.push(Line::from(vec![
-        Span::styled("Enter", Style::default().fg(theme.accent)),
+        Span::styled("Enter", confirm_enter_style(app, theme)),
         Span::raw(" 确认 | "),
-        Span::styled("d", Style::default().fg(theme.accent)),
+        Span::styled(
+            if typing { "Tab" } else { "d" },
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" 详情预览 | "),
         Span::styled("Esc", Style::default().fg(theme.accent)),
         Span::raw(" 取消 | "),
-        Span::styled("j/k", Style::default().fg(theme.accent)),
+        Span::styled(
+            if typing { "↑/↓" } else { "j/k" },
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" 滚动"),
     ]));
 
@@ -638,8 +1231,81 @@ fn render_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     frame.render_widget(confirm, area);
 }
 
+/// 渲染清空回收站确认弹窗
+fn render_empty_trash_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(
+        ERROR_POPUP_WIDTH_PERCENT,
+        CONFIRM_POPUP_HEIGHT_PERCENT / 2,
+        frame.area(),
+    );
+    frame.render_widget(Clear, area);
+
+    let lines = vec![
+        Line::from(Span::styled(
+            "⚠ 确认清空回收站",
+            Style::default().fg(theme.warning).bold(),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "回收站当前大小: {}",
+            app.trash_usage
+                .map(format_size)
+                .unwrap_or_else(|| "统计中或未知".to_string())
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "回收站中的所有文件将被永久删除，此操作不可逆！",
+            Style::default().fg(theme.danger),
+        )),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(theme.accent)),
+            Span::raw(" 确认 | "),
+            Span::styled("Esc", Style::default().fg(theme.accent)),
+            Span::raw(" 取消"),
+        ]),
+    ];
+
+    let popup = Paragraph::new(lines)
+        .block(styled_block(None, BorderType::Double, theme.danger).padding(Padding::uniform(1)))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(popup, area);
+}
+
+/// 渲染退出确认弹窗（有已选项目时）
+fn render_quit_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(
+        ERROR_POPUP_WIDTH_PERCENT,
+        CONFIRM_POPUP_HEIGHT_PERCENT / 2,
+        frame.area(),
+    );
+    frame.render_widget(Clear, area);
+
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("有 {} 个已选项目，确认退出？", app.selections.len()),
+            Style::default().fg(theme.warning).bold(),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(theme.accent)),
+            Span::raw(" 退出 | "),
+            Span::styled("N", Style::default().fg(theme.accent)),
+            Span::raw(" 取消"),
+        ]),
+    ];
+
+    let popup = Paragraph::new(lines)
+        .block(styled_block(None, BorderType::Double, theme.warning).padding(Padding::uniform(1)))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(popup, area);
+}
+
 /// 渲染 dry-run 详情视图
-fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     let mut lines = vec![
         Line::from(Span::styled(
             "🔍 删除预览 (Dry-run)",
@@ -648,6 +1314,17 @@ fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &App, theme: &Theme)
         Line::from(""),
     ];
 
+    if app.dry_run_computing() {
+        let completed = app
+            .dry_run_result
+            .as_ref()
+            .map_or(0, |result| result.items.len());
+        lines.push(Line::from(Span::styled(
+            format!("计算中… ({}/{})", completed, app.dry_run_total),
+            Style::default().fg(theme.text_dim),
+        )));
+    }
+
     if let Some(ref result) = app.dry_run_result {
         lines.push(Line::from(vec![
             Span::styled("总计: ", Style::default().fg(theme.text)),
@@ -666,12 +1343,48 @@ fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &App, theme: &Theme)
                 Style::default().fg(theme.danger),
             ),
         ]));
+        let mut breakdown = vec![
+            Span::styled("将删除: ", Style::default().fg(theme.text)),
+            Span::styled(
+                format_size(result.removable_size),
+                Style::default().fg(theme.danger).bold(),
+            ),
+            Span::raw(" | "),
+            Span::styled("保留/跳过: ", Style::default().fg(theme.text)),
+            Span::styled(
+                format_size(result.retained_size),
+                Style::default().fg(theme.success),
+            ),
+        ];
+        if result.skipped_count > 0 {
+            breakdown.push(Span::styled(
+                format!(" ({} 项未通过安全检查)", result.skipped_count),
+                Style::default().fg(theme.warning),
+            ));
+        }
+        lines.push(Line::from(breakdown));
+        if app.secure_delete_active() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "已启用安全擦除：每个文件覆写 {} 遍后删除，速度较慢且不可逆",
+                    SECURE_DELETE_PASSES
+                ),
+                Style::default().fg(theme.danger),
+            )));
+        }
         lines.push(Line::from(""));
 
-        let visible_height = area.height.saturating_sub(POPUP_LIST_RESERVED_LINES) as usize;
-        let scroll = app
+        // 比确认列表多出“将删除 / 保留”一行
+        let visible_height = area
+            .height
+            .saturating_sub(POPUP_LIST_RESERVED_LINES + 1 + confirm_word_lines(app))
+            as usize;
+        app.confirm_visible_height = visible_height;
+        // `result` 借用自 app，此处直接按字段修正，规则同 `App::clamp_confirm_scroll()`
+        app.confirm_scroll = app
             .confirm_scroll
             .min(result.items.len().saturating_sub(visible_height));
+        let scroll = app.confirm_scroll;
 
         for item in result.items.iter().skip(scroll).take(visible_height) {
             let name = path_short_name(&item.path);
@@ -704,10 +1417,16 @@ fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &App, theme: &Theme)
     }
 
     lines.push(Line::from(""));
+    lines.extend(confirm_word_line(app, theme));
+    let toggle_key = if app.confirm_word_input.is_some() {
+        "Tab"
+    } else {
+        "d"
+    };
     lines.push(Line::from(vec![
-        Span::styled("Enter", Style::default().fg(theme.accent)),
+        Span::styled("Enter", confirm_enter_style(app, theme)),
         Span::raw(" 确认删除 | "),
-        Span::styled("d", Style::default().fg(theme.accent)),
+        Span::styled(toggle_key, Style::default().fg(theme.accent)),
         Span::raw(" 返回列表 | "),
         Span::styled("Esc", Style::default().fg(theme.accent)),
         Span::raw(" 取消"),
@@ -719,6 +1438,73 @@ fn render_dry_run_view(frame: &mut Frame, area: Rect, app: &App, theme: &Theme)
     frame.render_widget(popup, area);
 }
 
+/// 待清理项目涉及多个类别时，按类别列出项数与大小小计（单一类别时不显示）
+fn confirm_category_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
+    let stats = app.confirm_category_stats();
+    if stats.len() < 2 {
+        return Vec::new();
+    }
+    let mut lines: Vec<Line<'static>> = stats
+        .iter()
+        .take(CONFIRM_CATEGORY_LINES_MAX)
+        .map(|category| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}: ", category.name),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(
+                    format!("{} 项, {}", category.item_count, format_size(category.size)),
+                    Style::default().fg(theme.warning),
+                ),
+            ])
+        })
+        .collect();
+    if stats.len() > CONFIRM_CATEGORY_LINES_MAX {
+        lines.push(Line::from(Span::styled(
+            format!("  … 另有 {} 类", stats.len() - CONFIRM_CATEGORY_LINES_MAX),
+            Style::default().fg(theme.text_dim),
+        )));
+    }
+    lines
+}
+
+/// 确认词输入行占用的行数（不需要输入时为 0）
+fn confirm_word_lines(app: &App) -> u16 {
+    u16::from(app.confirm_word_input.is_some())
+}
+
+/// 确认词输入行：输入正确前以危险色显示
+fn confirm_word_line(app: &App, theme: &Theme) -> Option<Line<'static>> {
+    let input = app.confirm_word_input.as_ref()?;
+    let input_color = if app.confirm_armed() {
+        theme.success
+    } else {
+        theme.danger
+    };
+    Some(Line::from(vec![
+        Span::styled(
+            format!(
+                "将永久删除 {}，输入 {} 以启用 Enter: ",
+                format_size(app.confirm_permanent_size()),
+                CONFIRM_WORD
+            ),
+            Style::default().fg(theme.warning).bold(),
+        ),
+        Span::styled(input.clone(), Style::default().fg(input_color).bold()),
+        Span::styled("▏", Style::default().fg(theme.accent)),
+    ]))
+}
+
+/// Enter 提示的样式：未输入确认词时灰显
+fn confirm_enter_style(app: &App, theme: &Theme) -> Style {
+    if app.confirm_armed() {
+        Style::default().fg(theme.accent)
+    } else {
+        Style::default().fg(theme.text_dim)
+    }
+}
+
 /// 渲染错误弹窗
 fn render_error_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     if let Some(ref msg) = app.error_message {
@@ -729,17 +1515,39 @@ fn render_error_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         );
         frame.render_widget(Clear, area);
 
-        let content = vec![
+        let mut content = vec![
             Line::from(Span::styled(
                 "❌ 错误",
                 Style::default().fg(theme.danger).bold(),
             )),
             Line::from(""),
             Line::from(msg.as_str()),
-            Line::from(""),
-            Line::from("按 Enter 或 Esc 关闭"),
         ];
 
+        // 清理错误按类别分组着色
+        for (label, errors) in app.grouped_clean_errors() {
+            let color = clean_error_color(errors[0], theme);
+            content.push(Line::from(""));
+            content.push(Line::from(Span::styled(
+                format!("{} ({})", label, errors.len()),
+                Style::default().fg(color).bold(),
+            )));
+            for error in errors {
+                // I/O 错误保留原始信息，其余类别标题已说明原因
+                let text = match error {
+                    CleanError::Io { .. } => error.to_string(),
+                    _ => error.path().display().to_string(),
+                };
+                content.push(Line::from(Span::styled(
+                    text,
+                    Style::default().fg(theme.text),
+                )));
+            }
+        }
+
+        content.push(Line::from(""));
+        content.push(Line::from("按 Enter 或 Esc 关闭"));
+
         let error = Paragraph::new(content)
             .block(
                 styled_block(None, BorderType::Double, theme.danger).padding(Padding::uniform(1)),
@@ -751,6 +1559,19 @@ fn render_error_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     }
 }
 
+/// 清理错误类别对应的颜色
+fn clean_error_color(error: &CleanError, theme: &Theme) -> Color {
+    match error {
+        CleanError::PermissionDenied { .. }
+        | CleanError::ReadOnly { .. }
+        | CleanError::Immutable { .. }
+        | CleanError::Busy { .. }
+        | CleanError::HardLinked { .. } => theme.warning,
+        CleanError::NotFound { .. } => theme.text_dim,
+        CleanError::Io { .. } | CleanError::Unsafe { .. } => theme.danger,
+    }
+}
+
 /// 渲染统计面板弹窗
 fn render_stats_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     let area = centered_rect(
@@ -761,19 +1582,22 @@ fn render_stats_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     frame.render_widget(Clear, area);
 
     let stats = app.get_category_stats();
-    let total_size: u64 = stats.iter().map(|(_, s)| *s).sum();
+    let total_size: u64 = stats.iter().map(|category| category.size).sum();
 
     let mut lines = vec![
-        Line::from(Span::styled(
-            "空间占用统计",
-            Style::default().fg(theme.primary).bold(),
-        )),
+        Line::from(vec![
+            Span::styled("空间占用统计", Style::default().fg(theme.primary).bold()),
+            Span::styled(
+                format!(" · {}", app.stats_scope.as_str()),
+                Style::default().fg(theme.secondary),
+            ),
+        ]),
         Line::from(""),
     ];
 
-    for (category_name, size) in &stats {
+    for category in &stats {
         let percent = if total_size > 0 {
-            (*size as f64 / total_size as f64 * 100.0) as u16
+            (category.size as f64 / total_size as f64 * 100.0) as u16
         } else {
             0
         };
@@ -781,14 +1605,20 @@ fn render_stats_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         let bar: String = "█".repeat(filled) + &"░".repeat(STATS_BAR_WIDTH - filled);
 
         // 分类名固定宽度对齐
-        let padded_name = format!("{:<14}", category_name);
-        let size_str = format!("{:>10}", format_size(*size));
+        let padded_name = format!("{:<14}", category.name);
+        let size_str = format!("{:>10}", format_size(category.size));
+        let count_str = match category.file_count {
+            Some(count) => format!("{:>9} 个文件", count),
+            None => format!("{:>9}       ", "-"),
+        };
 
         lines.push(Line::from(vec![
             Span::styled(padded_name, Style::default().fg(theme.text)),
             Span::raw(" "),
             Span::styled(size_str, Style::default().fg(theme.warning)),
             Span::raw("  "),
+            Span::styled(count_str, Style::default().fg(theme.text_dim)),
+            Span::raw("  "),
             Span::styled(bar, Style::default().fg(theme.accent)),
             Span::raw("  "),
             Span::styled(
@@ -808,10 +1638,13 @@ fn render_stats_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         Span::raw(format!(" ({} 个分类)", stats.len())),
     ]));
     lines.push(Line::from(""));
-    lines.push(Line::from(Span::styled(
-        "按任意键关闭",
-        Style::default().fg(theme.text_dim),
-    )));
+    lines.push(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(theme.accent)),
+        Span::styled(
+            " 切换 当前视图 / 全部 | 其他键关闭",
+            Style::default().fg(theme.text_dim),
+        ),
+    ]));
 
     let popup = Paragraph::new(lines).block(
         styled_block(Some(" 统计 "), BorderType::Double, theme.primary)
@@ -821,6 +1654,169 @@ fn render_stats_popup(frame: &mut Frame, app: &App, theme: &Theme) {
     frame.render_widget(popup, area);
 }
 
+/// 渲染目录预览弹窗（只读，列出直接子项）
+fn render_peek_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(
+        DEFAULT_POPUP_WIDTH_PERCENT,
+        DEFAULT_POPUP_HEIGHT_PERCENT,
+        frame.area(),
+    );
+    frame.render_widget(Clear, area);
+
+    let title = app
+        .peek_path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            title,
+            Style::default().fg(theme.primary).bold(),
+        )),
+        Line::from(""),
+    ];
+
+    match &app.peek_result {
+        None => lines.push(Line::from(Span::styled(
+            "正在读取...",
+            Style::default().fg(theme.text_dim),
+        ))),
+        Some(Err(message)) => lines.push(Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.danger),
+        ))),
+        Some(Ok(peek)) => {
+            if peek.entries.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "（空目录）",
+                    Style::default().fg(theme.text_dim),
+                )));
+            }
+            for entry in &peek.entries {
+                let name = match entry.kind {
+                    EntryKind::Directory => format!("{}/", entry.name),
+                    EntryKind::File => entry.name.clone(),
+                };
+                let mut spans = vec![
+                    Span::styled("  • ", Style::default().fg(theme.text_dim)),
+                    Span::styled(name, Style::default().fg(theme.text)),
+                ];
+                if let Some(size) = entry.size {
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(
+                        format!("({})", format_size(size)),
+                        Style::default().fg(theme.warning),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+            if peek.total > peek.entries.len() {
+                lines.push(Line::from(Span::styled(
+                    format!("  ... 共 {} 项", peek.total),
+                    Style::default().fg(theme.text_dim),
+                )));
+            }
+        }
+    }
+
+    let popup = Paragraph::new(lines).block(
+        styled_block(Some(" 预览 "), BorderType::Double, theme.primary)
+            .padding(Padding::uniform(1)),
+    );
+
+    frame.render_widget(popup, area);
+}
+
+/// 渲染书签列表弹窗（不存在的书签灰显并标注）
+fn render_bookmarks_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(
+        DEFAULT_POPUP_WIDTH_PERCENT,
+        DEFAULT_POPUP_HEIGHT_PERCENT,
+        frame.area(),
+    );
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "选择要扫描的书签",
+            Style::default().fg(theme.primary).bold(),
+        )),
+        Line::from(""),
+    ];
+    for (index, bookmark) in app.bookmarks.iter().enumerate() {
+        let path = bookmark.path.display().to_string();
+        if !bookmark.available {
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(path, Style::default().fg(theme.text_dim)),
+                Span::styled("  （不存在）", Style::default().fg(theme.text_dim)),
+            ]));
+        } else if index == app.bookmark_index {
+            lines.push(Line::from(vec![
+                Span::styled("  ▶ ", Style::default().fg(theme.accent)),
+                Span::styled(path, Style::default().fg(theme.accent).bold()),
+            ]));
+        } else {
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(path, Style::default().fg(theme.text)),
+            ]));
+        }
+    }
+
+    let popup = Paragraph::new(lines).block(
+        styled_block(Some(" 书签 "), BorderType::Double, theme.primary)
+            .padding(Padding::uniform(1)),
+    );
+
+    frame.render_widget(popup, area);
+}
+
+/// 渲染扫描类别设置面板
+fn render_settings_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(
+        DEFAULT_POPUP_WIDTH_PERCENT,
+        DEFAULT_POPUP_HEIGHT_PERCENT,
+        frame.area(),
+    );
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "预设扫描（s）包含的类别",
+            Style::default().fg(theme.primary).bold(),
+        )),
+        Line::from(""),
+    ];
+    for (index, category) in Scanner::PRESET_CATEGORIES.iter().enumerate() {
+        let checkbox = if app.is_category_enabled(category) {
+            "[x] "
+        } else {
+            "[ ] "
+        };
+        let (marker, style) = if index == app.settings_index {
+            ("  ▶ ", Style::default().fg(theme.accent).bold())
+        } else {
+            ("    ", Style::default().fg(theme.text))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.accent)),
+            Span::styled(format!("{}{}", checkbox, category.as_str()), style),
+            Span::styled(
+                format!("  {}", category.slug()),
+                Style::default().fg(theme.text_dim),
+            ),
+        ]));
+    }
+
+    let popup = Paragraph::new(lines).block(
+        styled_block(Some(" 设置 "), BorderType::Double, theme.primary)
+            .padding(Padding::uniform(1)),
+    );
+
+    frame.render_widget(popup, area);
+}
+
 /// 渲染搜索栏（底部浮层）
 fn render_search_bar(frame: &mut Frame, app: &App, theme: &Theme) {
     let area = frame.area();
@@ -838,18 +1834,29 @@ fn render_search_bar(frame: &mut Frame, app: &App, theme: &Theme) {
         Span::styled(&app.search_query, Style::default().fg(theme.text))
     };
 
-    let content = Line::from(vec![
+    let mut spans = vec![
         Span::styled("/", Style::default().fg(theme.accent).bold()),
         Span::raw(" "),
         search_display,
         Span::styled("█", Style::default().fg(theme.accent)),
-    ]);
+    ];
+    if let Some(ref error) = app.search_error {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            error.as_str(),
+            Style::default().fg(theme.danger),
+        ));
+    }
+    let content = Line::from(spans);
 
-    let bar = Paragraph::new(content).block(styled_block(
-        Some(" 搜索 "),
-        BorderType::Rounded,
-        theme.accent,
-    ));
+    let title = match (app.search_is_regex, app.search_jump) {
+        (false, false) => " 搜索 ",
+        (true, false) => " 搜索 (正则) ",
+        (false, true) => " 搜索 (跳转) ",
+        (true, true) => " 搜索 (正则, 跳转) ",
+    };
+    let bar =
+        Paragraph::new(content).block(styled_block(Some(title), BorderType::Rounded, theme.accent));
 
     frame.render_widget(bar, bar_area);
 }
@@ -872,3 +1879,89 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
 
     center
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_accepts_hex_and_names() {
+        assert_eq!(parse_color("#89b4fa"), Some(Color::Rgb(0x89, 0xb4, 0xfa)));
+        assert_eq!(parse_color(" cyan "), Some(Color::Cyan));
+        assert_eq!(parse_color("light-blue"), Some(Color::LightBlue));
+        assert_eq!(parse_color("#zzzzzz"), None);
+        assert_eq!(parse_color("not-a-color"), None);
+    }
+
+    #[test]
+    fn size_bar_scales_relative_to_max() {
+        assert_eq!(size_bar(Some(100), 100, 4), "████");
+        assert_eq!(size_bar(Some(50), 100, 4), "██░░");
+        assert_eq!(size_bar(Some(1), 1_000_000, 4), "█░░░");
+        assert_eq!(size_bar(Some(0), 100, 4), "░░░░");
+        assert_eq!(size_bar(None, 100, 4), "░░░░");
+        assert_eq!(size_bar(Some(5), 0, 4), "░░░░");
+    }
+
+    #[test]
+    fn scroll_indicator_reflects_position() {
+        assert_eq!(scroll_indicator(0, 0), None);
+        assert_eq!(scroll_indicator(0, 5), Some(" 更多 ↓ "));
+        assert_eq!(scroll_indicator(2, 5), Some(" ↑ 更多 ↓ "));
+        assert_eq!(scroll_indicator(5, 5), Some(" ↑ "));
+    }
+
+    #[test]
+    fn theme_preset_selection() {
+        assert_eq!(Theme::preset("default"), Some(Theme::default()));
+        assert_eq!(
+            Theme::preset("Dracula").map(|theme| theme.primary),
+            Some(Color::Rgb(0xbd, 0x93, 0xf9))
+        );
+        assert!(Theme::preset("solarized").is_some());
+        assert!(Theme::preset("mono").is_some());
+        assert!(Theme::preset("unknown").is_none());
+    }
+
+    #[test]
+    fn theme_from_config_applies_overrides() {
+        let config = ThemeConfig {
+            preset: Some("solarized".to_string()),
+            primary: Some("#89b4fa".to_string()),
+            ..ThemeConfig::default()
+        };
+        let theme = Theme::from_config(&config);
+        let solarized = Theme::preset("solarized").expect("preset");
+        assert_eq!(theme.primary, Color::Rgb(0x89, 0xb4, 0xfa));
+        assert_eq!(theme.accent, solarized.accent);
+    }
+
+    #[test]
+    fn theme_from_config_falls_back_on_invalid_values() {
+        let config = ThemeConfig {
+            preset: Some("nope".to_string()),
+            danger: Some("#12".to_string()),
+            ..ThemeConfig::default()
+        };
+        assert_eq!(Theme::from_config(&config), Theme::default());
+    }
+
+    #[test]
+    fn completion_window_keeps_selection_visible() {
+        assert_eq!(completion_window(0, 3, 5), (0, 3));
+        assert_eq!(completion_window(0, 12, 5), (0, 5));
+        assert_eq!(completion_window(4, 12, 5), (0, 5));
+        assert_eq!(completion_window(5, 12, 5), (1, 6));
+        assert_eq!(completion_window(11, 12, 5), (7, 12));
+    }
+
+    #[test]
+    fn spinner_frame_advances_with_elapsed_time() {
+        assert_eq!(spinner_frame(Duration::ZERO), SPINNER_FRAMES[0]);
+        assert_eq!(spinner_frame(Duration::from_millis(250)), SPINNER_FRAMES[2]);
+        assert_eq!(
+            spinner_frame(Duration::from_millis(SPINNER_FRAME_MS as u64 * 10)),
+            SPINNER_FRAMES[0]
+        );
+    }
+}
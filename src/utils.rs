@@ -1,8 +1,15 @@
-use std::time::SystemTime;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 pub const SECONDS_PER_DAY: i64 = 86_400;
 const SECONDS_PER_HOUR: i64 = 3_600;
 const SECONDS_PER_MINUTE: i64 = 60;
+const DAYS_PER_WEEK: i64 = 7;
+const DAYS_PER_MONTH: i64 = 30;
+const DAYS_PER_YEAR: i64 = 365;
 pub const EPOCH_YEAR: i32 = 1970;
 
 fn is_leap_year(year: i32) -> bool {
@@ -13,103 +20,650 @@ fn days_in_year(year: i32) -> i64 {
     if is_leap_year(year) { 366 } else { 365 }
 }
 
-/// 将路径中的 `~` 展开为主目录绝对路径。
-pub fn expand_tilde(raw_path: &str) -> String {
-    if raw_path.starts_with('~')
-        && let Some(user_dirs) = directories::UserDirs::new()
+/// `getpwnam_r` 使用的缓冲区大小
+#[cfg(unix)]
+const PASSWD_BUFFER_SIZE: usize = 4_096;
+
+/// 路径是否与集合中的某个路径相同、位于其下或包含它
+///
+/// `PathBuf` 按路径组件排序，某个路径的全部子路径在集合中紧跟在它之后，只需检查下一个元素。
+pub fn overlaps_any(paths: &BTreeSet<PathBuf>, path: &Path) -> bool {
+    path.ancestors().any(|ancestor| paths.contains(ancestor))
+        || paths
+            .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+            .next()
+            .is_some_and(|next| next.starts_with(path))
+}
+
+/// 显式指定用户主目录的环境变量
+pub const HOME_ENV_VAR: &str = "HOME";
+
+/// 当前用户主目录：优先使用 `HOME` 环境变量（须为绝对路径），否则向系统查询。
+///
+/// 受限或无头环境中两者都不可用时返回 None。
+pub fn home_dir() -> Option<PathBuf> {
+    home_dir_from(std::env::var_os(HOME_ENV_VAR))
+}
+
+fn home_dir_from(env_home: Option<OsString>) -> Option<PathBuf> {
+    env_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()))
+}
+
+/// 在系统文件管理器中显示路径的命令：目录直接打开；文件在 macOS 上经 `open -R` 显示并选中，
+/// 其他平台（`xdg-open`）无法选中文件，改为打开其所在目录。
+pub fn reveal_command(path: &Path, is_dir: bool) -> std::process::Command {
+    #[cfg(target_os = "macos")]
     {
-        let home_path = user_dirs.home_dir().display().to_string();
-        return raw_path.replacen('~', &home_path, 1);
+        let mut command = std::process::Command::new("open");
+        if !is_dir {
+            command.arg("-R");
+        }
+        command.arg(path);
+        command
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let target = if is_dir {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(target);
+        command
     }
-    raw_path.to_string()
 }
 
-/// 格式化 SystemTime。
+/// 展开路径输入：先展开开头的 `~` / `~user`，再展开 `$VAR` / `${VAR}` 环境变量。
+///
+/// 未知用户或未设置的变量保持原文不变。
+pub fn expand_path(raw_path: &str) -> String {
+    expand_env_vars(&expand_tilde(raw_path))
+}
+
+/// 将路径开头的 `~` 展开为当前用户主目录，`~user` 展开为该用户的主目录。
+pub fn expand_tilde(raw_path: &str) -> String {
+    let Some(rest) = raw_path.strip_prefix('~') else {
+        return raw_path.to_string();
+    };
+    let (user, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let home_path = if user.is_empty() {
+        home_dir().map(|home| home.display().to_string())
+    } else {
+        user_home_dir(user)
+    };
+    match home_path {
+        Some(home_path) => format!("{home_path}{tail}"),
+        None => raw_path.to_string(),
+    }
+}
+
+/// 通过 `getpwnam_r` 查询指定用户的主目录。
+#[cfg(unix)]
+fn user_home_dir(user: &str) -> Option<String> {
+    let c_user = std::ffi::CString::new(user).ok()?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; PASSWD_BUFFER_SIZE];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let status = unsafe {
+        libc::getpwnam_r(
+            c_user.as_ptr(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if status != 0 || result.is_null() || passwd.pw_dir.is_null() {
+        return None;
+    }
+    let home_dir = unsafe { std::ffi::CStr::from_ptr(passwd.pw_dir) };
+    Some(home_dir.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn user_home_dir(_user: &str) -> Option<String> {
+    None
+}
+
+/// 通过 `statvfs` 查询路径所在卷的 (总容量, 可用空间) 字节数，查询失败时返回 None。
+///
+/// 可用空间为非特权用户可用的块数（`f_bavail`），与 Finder / `df` 的显示一致。
+#[cfg(unix)]
+pub fn disk_usage(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let status = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if status != 0 {
+        return None;
+    }
+    let block_size = stat.f_frsize as u64;
+    let total = (stat.f_blocks as u64).saturating_mul(block_size);
+    let available = (stat.f_bavail as u64).saturating_mul(block_size);
+    Some((total, available))
+}
+
+#[cfg(not(unix))]
+pub fn disk_usage(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// 展开 `$VAR` / `${VAR}` 形式的环境变量，未设置的变量保持原文。
+pub fn expand_env_vars(raw: &str) -> String {
+    let mut output = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(dollar) = rest.find('$') {
+        output.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+        let value = is_env_var_name(name)
+            .then(|| std::env::var(name).ok())
+            .flatten();
+        match value {
+            Some(value) => {
+                output.push_str(&value);
+                rest = &after[consumed..];
+            }
+            None => {
+                output.push('$');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// 按 RFC 4180 转义 CSV 字段：含逗号、双引号或换行时整体加双引号，内部双引号加倍。
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 将 SystemTime 格式化为相对 `now` 的粗略时间（如 “3 天前”）。
+///
+/// 不足 1 分钟或晚于 `now` 时返回 “刚刚”；月按 30 天、年按 365 天计。
+pub fn format_relative_time(time: &SystemTime, now: SystemTime) -> String {
+    let elapsed = now
+        .duration_since(*time)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0);
+    let days = elapsed / SECONDS_PER_DAY;
+    if elapsed < SECONDS_PER_MINUTE {
+        "刚刚".to_string()
+    } else if elapsed < SECONDS_PER_HOUR {
+        format!("{} 分钟前", elapsed / SECONDS_PER_MINUTE)
+    } else if elapsed < SECONDS_PER_DAY {
+        format!("{} 小时前", elapsed / SECONDS_PER_HOUR)
+    } else if days < DAYS_PER_WEEK {
+        format!("{} 天前", days)
+    } else if days < DAYS_PER_MONTH {
+        format!("{} 周前", days / DAYS_PER_WEEK)
+    } else if days < DAYS_PER_YEAR {
+        format!("{} 个月前", days / DAYS_PER_MONTH)
+    } else {
+        format!("{} 年前", days / DAYS_PER_YEAR)
+    }
+}
+
+/// 将耗时格式化为 `mm:ss`（超过 1 小时时分钟数继续累加，如 `75:03`）。
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let total_seconds = elapsed.as_secs();
+    format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60)
+}
+
+/// 将剩余时间格式化为紧凑形式：`45s`、`1m20s`、`1h05m`。
+pub fn format_eta(remaining: Duration) -> String {
+    let total_seconds = remaining.as_secs();
+    if total_seconds < 60 {
+        format!("{}s", total_seconds)
+    } else if total_seconds < 3600 {
+        format!("{}m{:02}s", total_seconds / 60, total_seconds % 60)
+    } else {
+        format!("{}h{:02}m", total_seconds / 3600, total_seconds % 3600 / 60)
+    }
+}
+
+/// 按系统本地时区格式化 SystemTime。
 ///
 /// - `include_time = false` => `YYYY-MM-DD`
 /// - `include_time = true` => `YYYY-MM-DD HH:MM:SS`
 pub fn format_time(time: &SystemTime, include_time: bool) -> String {
-    let duration = time
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    let timestamp_seconds = duration.as_secs() as i64;
-
-    let total_days = timestamp_seconds / SECONDS_PER_DAY;
-    let seconds_within_day = timestamp_seconds % SECONDS_PER_DAY;
-    let hours = seconds_within_day / SECONDS_PER_HOUR;
-    let minutes = (seconds_within_day % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
-    let seconds = seconds_within_day % SECONDS_PER_MINUTE;
-
-    let mut remaining_days = total_days;
-    let mut year = EPOCH_YEAR;
-    loop {
-        let current_year_days = days_in_year(year);
-        if remaining_days < current_year_days {
-            break;
+    format_time_with_offset(time, local_utc_offset(time), include_time)
+}
+
+/// 按 UTC 格式化 SystemTime，结果与运行环境的时区无关。
+pub fn format_time_utc(time: &SystemTime, include_time: bool) -> String {
+    format_time_with_offset(time, 0, include_time)
+}
+
+/// 查询给定时刻系统本地时区相对 UTC 的偏移（秒），查询失败时按 UTC 处理。
+#[cfg(unix)]
+fn local_utc_offset(time: &SystemTime) -> i64 {
+    let Ok(duration) = time.duration_since(SystemTime::UNIX_EPOCH) else {
+        return 0;
+    };
+    let Ok(timestamp) = libc::time_t::try_from(duration.as_secs()) else {
+        return 0;
+    };
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::localtime_r(&timestamp, &mut tm) };
+    if result.is_null() {
+        0
+    } else {
+        tm.tm_gmtoff as i64
+    }
+}
+
+#[cfg(not(unix))]
+fn local_utc_offset(_time: &SystemTime) -> i64 {
+    0
+}
+
+/// 日期时间各字段。
+struct DateTimeParts {
+    year: i32,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+}
+
+impl DateTimeParts {
+    /// 以指定的 UTC 偏移（秒）拆分 SystemTime。
+    fn from_time(time: &SystemTime, offset_seconds: i64) -> Self {
+        let duration = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let timestamp_seconds = (duration.as_secs() as i64 + offset_seconds).max(0);
+
+        let total_days = timestamp_seconds / SECONDS_PER_DAY;
+        let seconds_within_day = timestamp_seconds % SECONDS_PER_DAY;
+
+        let mut remaining_days = total_days;
+        let mut year = EPOCH_YEAR;
+        loop {
+            let current_year_days = days_in_year(year);
+            if remaining_days < current_year_days {
+                break;
+            }
+            remaining_days -= current_year_days;
+            year += 1;
         }
-        remaining_days -= current_year_days;
-        year += 1;
-    }
-
-    let month_day_table: [i64; 12] = [
-        31,
-        if is_leap_year(year) { 29 } else { 28 },
-        31,
-        30,
-        31,
-        30,
-        31,
-        31,
-        30,
-        31,
-        30,
-        31,
-    ];
-
-    let mut month_index = 0usize;
-    for (index, &days_in_month) in month_day_table.iter().enumerate() {
-        if remaining_days < days_in_month {
-            month_index = index;
-            break;
+
+        let month_day_table: [i64; 12] = [
+            31,
+            if is_leap_year(year) { 29 } else { 28 },
+            31,
+            30,
+            31,
+            30,
+            31,
+            31,
+            30,
+            31,
+            30,
+            31,
+        ];
+
+        let mut month_index = 0usize;
+        for (index, &days_in_month) in month_day_table.iter().enumerate() {
+            if remaining_days < days_in_month {
+                month_index = index;
+                break;
+            }
+            remaining_days -= days_in_month;
+        }
+
+        Self {
+            year,
+            month: month_index as i64 + 1,
+            day: remaining_days + 1,
+            hour: seconds_within_day / SECONDS_PER_HOUR,
+            minute: (seconds_within_day % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE,
+            second: seconds_within_day % SECONDS_PER_MINUTE,
         }
-        remaining_days -= days_in_month;
     }
 
-    let day_of_month = remaining_days + 1;
+    /// 按 strftime 风格的格式串输出；格式串无效时返回 None。
+    fn render(&self, pattern: &str) -> Option<String> {
+        if !is_valid_date_format(pattern) {
+            return None;
+        }
+        let mut output = String::with_capacity(pattern.len() + 8);
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                output.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => output.push_str(&format!("{:04}", self.year)),
+                Some('m') => output.push_str(&format!("{:02}", self.month)),
+                Some('d') => output.push_str(&format!("{:02}", self.day)),
+                Some('H') => output.push_str(&format!("{:02}", self.hour)),
+                Some('M') => output.push_str(&format!("{:02}", self.minute)),
+                Some('S') => output.push_str(&format!("{:02}", self.second)),
+                Some('%') => output.push('%'),
+                _ => return None,
+            }
+        }
+        Some(output)
+    }
+}
+
+/// 检查日期格式串是否只包含支持的占位符（%Y %m %d %H %M %S %%）且非空。
+pub fn is_valid_date_format(pattern: &str) -> bool {
+    if pattern.is_empty() {
+        return false;
+    }
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '%' && !matches!(chars.next(), Some('Y' | 'm' | 'd' | 'H' | 'M' | 'S' | '%')) {
+            return false;
+        }
+    }
+    true
+}
+
+/// 按用户配置的日期格式串（本地时区）格式化 SystemTime。
+///
+/// 未配置或格式串无效时回退到 `format_time(time, include_time)` 的默认布局。
+pub fn format_time_with(
+    time: &SystemTime,
+    date_format: Option<&str>,
+    include_time: bool,
+) -> String {
+    let offset_seconds = local_utc_offset(time);
+    date_format
+        .and_then(|pattern| DateTimeParts::from_time(time, offset_seconds).render(pattern))
+        .unwrap_or_else(|| format_time_with_offset(time, offset_seconds, include_time))
+}
+
+/// 以指定的 UTC 偏移（秒）格式化 SystemTime。
+fn format_time_with_offset(time: &SystemTime, offset_seconds: i64, include_time: bool) -> String {
+    let parts = DateTimeParts::from_time(time, offset_seconds);
     if include_time {
         format!(
             "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-            year,
-            month_index + 1,
-            day_of_month,
-            hours,
-            minutes,
-            seconds
+            parts.year, parts.month, parts.day, parts.hour, parts.minute, parts.second
         )
     } else {
-        format!("{:04}-{:02}-{:02}", year, month_index + 1, day_of_month)
+        format!("{:04}-{:02}-{:02}", parts.year, parts.month, parts.day)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::{Duration, UNIX_EPOCH};
+    use std::time::UNIX_EPOCH;
 
     #[test]
     fn format_time_formats_date_without_clock() {
         let time = UNIX_EPOCH + Duration::from_secs(SECONDS_PER_DAY as u64);
-        assert_eq!(format_time(&time, false), "1970-01-02");
+        assert_eq!(format_time_utc(&time, false), "1970-01-02");
     }
 
     #[test]
     fn format_time_formats_date_with_clock() {
         let time = UNIX_EPOCH + Duration::from_secs(SECONDS_PER_DAY as u64 + 3_661);
-        assert_eq!(format_time(&time, true), "1970-01-02 01:01:01");
+        assert_eq!(format_time_utc(&time, true), "1970-01-02 01:01:01");
+    }
+
+    #[test]
+    fn format_time_with_positive_offset_rolls_into_next_day() {
+        // 2024-03-01 23:30:00 UTC，UTC+8 下已是次日
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_335_800);
+        assert_eq!(format_time_utc(&time, true), "2024-03-01 23:30:00");
+        assert_eq!(
+            format_time_with_offset(&time, 8 * SECONDS_PER_HOUR, true),
+            "2024-03-02 07:30:00"
+        );
+    }
+
+    #[test]
+    fn format_time_with_negative_offset_rolls_into_previous_day() {
+        // 2024-03-01 02:00:00 UTC，UTC-5 下仍是前一天（闰年 2 月 29 日）
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_258_400);
+        assert_eq!(
+            format_time_with_offset(&time, -5 * SECONDS_PER_HOUR, false),
+            "2024-02-29"
+        );
+    }
+
+    #[test]
+    fn date_format_renders_supported_fields() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_335_800);
+        let parts = DateTimeParts::from_time(&time, 0);
+        assert_eq!(
+            parts.render("%Y/%m/%d %H:%M").as_deref(),
+            Some("2024/03/01 23:30")
+        );
+        assert_eq!(
+            parts.render("%d.%m.%Y %H:%M:%S (100%%)").as_deref(),
+            Some("01.03.2024 23:30:00 (100%)")
+        );
+    }
+
+    #[test]
+    fn invalid_date_format_falls_back_to_default_layout() {
+        assert!(!is_valid_date_format(""));
+        assert!(!is_valid_date_format("%Y-%q"));
+        assert!(!is_valid_date_format("%Y-%"));
+        assert!(is_valid_date_format("%Y 年 %m 月"));
+
+        let time = UNIX_EPOCH;
+        assert_eq!(
+            format_time_with(&time, Some("%Y-%q"), false),
+            format_time(&time, false)
+        );
+        assert_eq!(
+            format_time_with(&time, None, true),
+            format_time(&time, true)
+        );
+    }
+
+    #[test]
+    fn format_time_with_offset_clamps_before_epoch() {
+        assert_eq!(
+            format_time_with_offset(&UNIX_EPOCH, -SECONDS_PER_HOUR, true),
+            "1970-01-01 00:00:00"
+        );
+    }
+
+    fn relative(seconds_ago: u64) -> String {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        format_relative_time(&(now - Duration::from_secs(seconds_ago)), now)
+    }
+
+    #[test]
+    fn format_relative_time_boundaries() {
+        let hour = SECONDS_PER_HOUR as u64;
+        let day = SECONDS_PER_DAY as u64;
+        assert_eq!(relative(0), "刚刚");
+        assert_eq!(relative(59), "刚刚");
+        assert_eq!(relative(60), "1 分钟前");
+        assert_eq!(relative(hour - 1), "59 分钟前");
+        assert_eq!(relative(hour), "1 小时前");
+        assert_eq!(relative(23 * hour), "23 小时前");
+        assert_eq!(relative(day - 1), "23 小时前");
+        assert_eq!(relative(25 * hour), "1 天前");
+        assert_eq!(relative(6 * day), "6 天前");
+        assert_eq!(relative(7 * day), "1 周前");
+        assert_eq!(relative(29 * day), "4 周前");
+        assert_eq!(relative(30 * day), "1 个月前");
+        assert_eq!(relative(364 * day), "12 个月前");
+        assert_eq!(relative(365 * day), "1 年前");
+        assert_eq!(relative(3 * 365 * day), "3 年前");
+    }
+
+    #[test]
+    fn format_relative_time_future_is_just_now() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let future = now + Duration::from_secs(3_600);
+        assert_eq!(format_relative_time(&future, now), "刚刚");
     }
 
     #[test]
     fn expand_tilde_keeps_plain_path() {
         assert_eq!(expand_tilde("/tmp"), "/tmp");
     }
+
+    fn home_dir() -> String {
+        directories::UserDirs::new()
+            .expect("home dir")
+            .home_dir()
+            .display()
+            .to_string()
+    }
+
+    #[test]
+    fn expand_path_expands_bare_tilde() {
+        assert_eq!(expand_path("~"), home_dir());
+        assert_eq!(expand_path("~/Library"), format!("{}/Library", home_dir()));
+    }
+
+    #[test]
+    fn expand_path_keeps_unknown_user() {
+        assert_eq!(
+            expand_path("~vac-no-such-user/cache"),
+            "~vac-no-such-user/cache"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn expand_path_expands_named_user() {
+        let expected = user_home_dir("root").expect("root user");
+        assert_eq!(expand_path("~root/x"), format!("{expected}/x"));
+    }
+
+    #[test]
+    fn expand_path_expands_home_variable() {
+        let home = std::env::var("HOME").expect("HOME set");
+        assert_eq!(expand_path("$HOME/Library"), format!("{home}/Library"));
+        assert_eq!(expand_path("${HOME}/Library"), format!("{home}/Library"));
+        assert_eq!(expand_path("${HOME}x"), format!("{home}x"));
+    }
+
+    #[test]
+    fn expand_path_keeps_unset_or_malformed_variables() {
+        assert_eq!(
+            expand_path("/tmp/$VAC_TEST_UNSET_VAR/a"),
+            "/tmp/$VAC_TEST_UNSET_VAR/a"
+        );
+        assert_eq!(expand_path("/tmp/${HOME"), "/tmp/${HOME");
+        assert_eq!(expand_path("/tmp/$/a$"), "/tmp/$/a$");
+        assert_eq!(expand_path("/tmp/${1x}"), "/tmp/${1x}");
+    }
+
+    #[test]
+    fn csv_escape_quotes_special_fields() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_escape(""), "");
+    }
+
+    #[test]
+    fn format_elapsed_uses_minutes_and_seconds() {
+        assert_eq!(format_elapsed(Duration::from_millis(999)), "00:00");
+        assert_eq!(format_elapsed(Duration::from_secs(65)), "01:05");
+        assert_eq!(format_elapsed(Duration::from_secs(4_503)), "75:03");
+    }
+
+    #[test]
+    fn format_eta_picks_compact_units() {
+        assert_eq!(format_eta(Duration::from_millis(45_900)), "45s");
+        assert_eq!(format_eta(Duration::from_secs(80)), "1m20s");
+        assert_eq!(format_eta(Duration::from_secs(3_900)), "1h05m");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disk_usage_reports_volume_capacity() {
+        let (total, available) = disk_usage(Path::new("/")).expect("statvfs /");
+        assert!(total > 0);
+        assert!(available <= total);
+        assert!(disk_usage(Path::new("/nonexistent/vac-disk-usage")).is_none());
+    }
+
+    #[test]
+    fn home_dir_prefers_absolute_home_override() {
+        assert_eq!(
+            home_dir_from(Some(OsString::from("/srv/vac-home"))),
+            Some(PathBuf::from("/srv/vac-home"))
+        );
+        let fallback = directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        assert_eq!(
+            home_dir_from(Some(OsString::from("relative/home"))),
+            fallback
+        );
+        assert_eq!(home_dir_from(Some(OsString::new())), fallback);
+    }
+
+    #[test]
+    fn reveal_command_opens_directories_and_reveals_files() {
+        let args = |command: &std::process::Command| -> Vec<String> {
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+        let dir = reveal_command(Path::new("/tmp/cache"), true);
+        let file = reveal_command(Path::new("/tmp/cache/big.bin"), false);
+        #[cfg(target_os = "macos")]
+        {
+            assert_eq!(dir.get_program(), "open");
+            assert_eq!(args(&dir), ["/tmp/cache"]);
+            assert_eq!(args(&file), ["-R", "/tmp/cache/big.bin"]);
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            assert_eq!(dir.get_program(), "xdg-open");
+            assert_eq!(args(&dir), ["/tmp/cache"]);
+            assert_eq!(args(&file), ["/tmp/cache"]);
+        }
+    }
+
+    #[test]
+    fn overlaps_any_matches_same_ancestor_and_descendant_paths() {
+        let paths: BTreeSet<PathBuf> = ["/home/u/Downloads", "/home/u/Library/Caches"]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        assert!(overlaps_any(&paths, Path::new("/home/u/Downloads")));
+        assert!(overlaps_any(&paths, Path::new("/home/u/Downloads/a.zip")));
+        assert!(overlaps_any(&paths, Path::new("/home/u")));
+        assert!(!overlaps_any(&paths, Path::new("/home/u/Downloads-old")));
+        assert!(!overlaps_any(&paths, Path::new("/home/u/Library/Logs")));
+    }
 }